@@ -5,7 +5,7 @@ use std::fmt;
 use serde::{Serialize, Deserialize};
 use hex;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Address([u8; 24]);
 
 impl Address {
@@ -0,0 +1,209 @@
+// File: src/chain_spec.rs
+//
+// Network identity: the chain id, genesis allocations and validators, chain parameters,
+// scheduled forks and bootnodes of one network. `--chain` picks a built-in preset
+// (mainnet, testnet, dev) or a JSON file. Every node of a network must load the same
+// spec; any difference changes the genesis block, and peers on another genesis are
+// refused at sync.
+
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use k256::ecdsa::SigningKey;
+use crate::address::Address;
+use crate::config::DEV_VALIDATOR_KEY;
+use crate::core::forks::Fork;
+use crate::crypto::bls::BlsSecretKey;
+use crate::parameters::*;
+
+/// BLS consensus key of the RackNerd mainnet validator and its proof of possession.
+const MAINNET_CONSENSUS_KEY: &str = "8baf7894258ee8bb17bbacbbc9b0d695701a85bb14bfd96aefcad035cda0a130c29633fb1094421a7d2dbe703a5764ba";
+const MAINNET_CONSENSUS_POP: &str = "b90e18f51b5b184c2048f60b4c26bed448bd2f439203590ddcb902b03c4053906c0248b018fa218adff921f348cdf0c20470171edb10f10b442b72ffddbf87c004ccf9c70f5d8b929a6565d6d108b3f8d576b03c4b49f250504c32e89754d290";
+
+const DNR: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisAccount {
+    pub address: String,
+    pub balance: u128,
+}
+
+/// A validator registered at genesis. Its stake is bonded on top of any balance the
+/// spec gives the address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisValidator {
+    pub address: String,
+    pub moniker: String,
+    pub stake: u128,
+    #[serde(default = "default_commission_bps")]
+    pub commission_bps: u16,
+    /// Hex BLS consensus key and proof of possession. A validator without one cannot
+    /// vote until it registers a key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus_pop: Option<String>,
+}
+
+fn default_commission_bps() -> u16 {
+    DEFAULT_COMMISSION_BPS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSpec {
+    pub name: String,
+    pub chain_id: u64,
+    pub genesis_timestamp: u64,
+    /// Fields left out keep mainnet's values; `chain_id` always follows the spec's.
+    #[serde(default)]
+    pub params: ChainParams,
+    #[serde(default)]
+    pub accounts: Vec<GenesisAccount>,
+    pub validators: Vec<GenesisValidator>,
+    #[serde(default)]
+    pub bootnodes: Vec<String>,
+    /// Forks active from a height without an upgrade plan, e.g. from genesis on a devnet.
+    #[serde(default)]
+    pub forks: BTreeMap<Fork, u64>,
+}
+
+impl ChainSpec {
+    /// A preset name (`mainnet`, `testnet`, `dev`) or the path of a JSON spec.
+    pub fn load(chain: &str) -> Result<Self, String> {
+        match chain {
+            "mainnet" => Ok(Self::mainnet()),
+            "testnet" => Ok(Self::testnet()),
+            "dev" => Ok(Self::dev()),
+            path => Self::from_file(path),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        Self::from_json(&json).map_err(|e| format!("Invalid chain spec {}: {}", path, e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut spec: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        spec.params.chain_id = spec.chain_id;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Chain spec serializes")
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.params.chain_id != self.chain_id {
+            return Err("params.chain_id differs from chain_id".to_string());
+        }
+        self.params.validate()?;
+        if self.validators.is_empty() {
+            return Err("A chain spec needs at least one genesis validator".to_string());
+        }
+        for address in self.accounts.iter().map(|a| &a.address).chain(self.validators.iter().map(|v| &v.address)) {
+            Address::from_hex(address).map_err(|e| format!("Invalid address {}: {}", address, e))?;
+        }
+        Ok(())
+    }
+
+    /// Kortana mainnet: foundation, faucet and owner allocations and three genesis
+    /// validators bonding 1M DNR each.
+    pub fn mainnet() -> Self {
+        let validators = [
+            ("0xadf256ef0fa3c0fe774f7dac0fd04040e4fdef50".to_string(), Some((MAINNET_CONSENSUS_KEY, MAINNET_CONSENSUS_POP))),
+            (Address::from_pubkey(b"genesis_validator_2").to_full_hex(), None),
+            (Address::from_pubkey(b"genesis_validator_3").to_full_hex(), None),
+        ];
+        let stake = 1_000_000 * DNR;
+        let mut accounts = vec![account(&Address::from_pubkey(b"foundation").to_full_hex(), 490_000_000_000 * DNR)];
+        accounts.extend(validators.iter().map(|(address, _)| account(address, stake)));
+        accounts.push(account("0xc19d6dece56d290c71930c2f867ae9c2c652a19f7911ef64", 306_993_000_000 * DNR)); // Ecosystem faucet
+        accounts.push(account("0x28e514Ce1a0554B83f6d5EEEE11B07D0e294D9F9", 7_000_000 * DNR)); // Owner wallet
+        Self {
+            name: "mainnet".to_string(),
+            chain_id: CHAIN_ID,
+            genesis_timestamp: 1740268800, // Feb 23 2026
+            params: ChainParams::default(),
+            accounts,
+            validators: validators.into_iter().enumerate().map(|(i, (address, key))| GenesisValidator {
+                address,
+                moniker: format!("genesis-{}", i + 1),
+                stake,
+                commission_bps: DEFAULT_COMMISSION_BPS,
+                consensus_key: key.map(|(k, _)| k.to_string()),
+                consensus_pop: key.map(|(_, p)| p.to_string()),
+            }).collect(),
+            bootnodes: Vec::new(),
+            forks: BTreeMap::new(),
+        }
+    }
+
+    /// Public testnet: 800B DNR at genesis and a 32 DNR validator minimum. The
+    /// development key, which testnet nodes have always run, is its genesis validator
+    /// and holds the faucet allocation that `eth_requestDNR` pays out of.
+    pub fn testnet() -> Self {
+        let params = ChainParams {
+            chain_id: TESTNET_CHAIN_ID,
+            min_validator_stake: 32 * DNR,
+            active_validator_count: 50,
+            ..ChainParams::default()
+        };
+        let validator = dev_validator("genesis", params.min_validator_stake);
+        Self {
+            name: "testnet".to_string(),
+            chain_id: TESTNET_CHAIN_ID,
+            genesis_timestamp: 1738224000, // Jan 30 2026
+            accounts: vec![
+                account(&Address::from_pubkey(b"foundation").to_full_hex(), 500_000_000_000 * DNR),
+                account(&validator.address, 299_993_000_000 * DNR), // Ecosystem faucet
+                account("0x28e514Ce1a0554B83f6d5EEEE11B07D0e294D9F9", 7_000_000 * DNR), // Owner wallet
+            ],
+            validators: vec![validator],
+            params,
+            bootnodes: Vec::new(),
+            forks: BTreeMap::new(),
+        }
+    }
+
+    /// Single-validator devnet for local work: the development key validates and holds
+    /// 1B DNR, governance votes last 50 blocks and every fork is active from genesis.
+    pub fn dev() -> Self {
+        let params = ChainParams {
+            chain_id: DEV_CHAIN_ID,
+            gov_voting_period_blocks: 50,
+            ..ChainParams::default()
+        };
+        let validator = dev_validator("dev", params.min_validator_stake);
+        Self {
+            name: "dev".to_string(),
+            chain_id: DEV_CHAIN_ID,
+            genesis_timestamp: 1740268800,
+            accounts: vec![account(&validator.address, 1_000_000_000 * DNR)],
+            validators: vec![validator],
+            params,
+            bootnodes: Vec::new(),
+            forks: [Fork::CalldataGas, Fork::Mcopy].into_iter().map(|f| (f, 0)).collect(),
+        }
+    }
+}
+
+fn account(address: &str, balance: u128) -> GenesisAccount {
+    GenesisAccount { address: address.to_string(), balance }
+}
+
+/// The node that runs `NodeConfig::development()`, with its BLS key derived the way
+/// the node derives it.
+fn dev_validator(moniker: &str, stake: u128) -> GenesisValidator {
+    let seed = hex::decode(DEV_VALIDATOR_KEY).expect("Development key is valid hex");
+    let signing_key = SigningKey::from_bytes(seed.as_slice().into()).expect("Development key is a valid secp256k1 key");
+    let bls = BlsSecretKey::from_seed(&seed);
+    GenesisValidator {
+        address: Address::from_pubkey(&signing_key.verifying_key().to_sec1_bytes()).to_full_hex(),
+        moniker: moniker.to_string(),
+        stake,
+        commission_bps: DEFAULT_COMMISSION_BPS,
+        consensus_key: Some(hex::encode(bls.public_key())),
+        consensus_pop: Some(hex::encode(bls.proof_of_possession())),
+    }
+}
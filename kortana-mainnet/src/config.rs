@@ -6,6 +6,10 @@
 use std::env;
 use anyhow::{Context, Result};
 
+/// Publicly known validator key of `development()`; the dev and testnet chain specs
+/// make it their genesis validator. DO NOT USE IN PRODUCTION.
+pub const DEV_VALIDATOR_KEY: &str = "2d502aa349bb96c3676db8fd9ceb611594ca2a6dfbeeb9f2b175bf9116cbcdaa";
+
 /// Node Configuration - Loaded from environment variables for security
 #[derive(Debug, Clone)]
 pub struct NodeConfig {
@@ -79,7 +83,7 @@ impl NodeConfig {
         
         Self {
             // Default testnet validator key (publicly known - DO NOT USE IN PRODUCTION)
-            validator_private_key: hex::decode("2d502aa349bb96c3676db8fd9ceb611594ca2a6dfbeeb9f2b175bf9116cbcdaa")
+            validator_private_key: hex::decode(DEV_VALIDATOR_KEY)
                 .expect("Hardcoded key should always decode"),
             rpc_addr: "0.0.0.0:8545".to_string(),
             p2p_addr: "/ip4/0.0.0.0/tcp/30333".to_string(),
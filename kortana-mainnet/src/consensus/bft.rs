@@ -1,22 +1,221 @@
 // File: src/consensus/bft.rs
 
 use serde::{Serialize, Deserialize};
+use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use crate::address::Address;
 use crate::consensus::ValidatorInfo;
+use crate::crypto::bls::{self, BlsSecretKey};
 
+/// Prefix of every consensus signing message, so a vote signature can never be replayed
+/// as a transaction or block signature (or vice versa).
+pub const CONSENSUS_DOMAIN: &[u8] = b"KORTANA/BFT/v1";
+
+/// Message signed for a proposal or vote: domain, chain id, message type, height, round
+/// and block hash (zero for nil).
+fn consensus_message(chain_id: u64, kind: u8, height: u64, round: u32, block_hash: Option<[u8; 32]>) -> Keccak256 {
+    let mut hasher = Keccak256::new();
+    hasher.update(CONSENSUS_DOMAIN);
+    hasher.update(chain_id.to_be_bytes());
+    hasher.update([kind]);
+    hasher.update(height.to_be_bytes());
+    hasher.update(round.to_be_bytes());
+    hasher.update(block_hash.unwrap_or([0u8; 32]));
+    hasher
+}
+
+/// Validators that may vote, in the order of commit signer bitmaps (by address), so
+/// every node indexes them alike whatever order its validator list is in.
+fn voters(validators: &[ValidatorInfo]) -> Vec<&ValidatorInfo> {
+    let mut voters: Vec<&ValidatorInfo> = validators.iter().filter(|v| v.is_active && !v.consensus_pubkey.is_empty()).collect();
+    voters.sort_by_key(|v| v.address.to_bytes());
+    voters
+}
+
+/// Registered consensus key of `address` among `validators`, if it may vote.
+fn consensus_key(validators: &[ValidatorInfo], address: &Address) -> Result<Vec<u8>, String> {
+    validators.iter()
+        .find(|v| v.address == *address && v.is_active)
+        .filter(|v| !v.consensus_pubkey.is_empty())
+        .map(|v| v.consensus_pubkey.clone())
+        .ok_or_else(|| format!("{} is not a voting validator", address.to_hex()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VoteKind {
+    Prevote,
+    Precommit,
+}
+
+/// A validator's prevote or precommit; `block_hash: None` is a vote for nil.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vote {
+    pub kind: VoteKind,
+    pub height: u64,
+    pub round: u32,
+    pub block_hash: Option<[u8; 32]>,
+    pub validator: Address,
+    pub signature: Vec<u8>,
+}
+
+impl Vote {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(chain_id: u64, kind: VoteKind, height: u64, round: u32, block_hash: Option<[u8; 32]>, validator: Address, key: &BlsSecretKey) -> Self {
+        let mut vote = Self { kind, height, round, block_hash, validator, signature: Vec::new() };
+        vote.signature = key.sign(&vote.signing_hash(chain_id));
+        vote
+    }
+
+    pub fn signing_hash(&self, chain_id: u64) -> [u8; 32] {
+        let kind = match self.kind { VoteKind::Prevote => 1u8, VoteKind::Precommit => 2u8 };
+        consensus_message(chain_id, kind, self.height, self.round, self.block_hash).finalize().into()
+    }
+
+    /// Checks the BLS signature against the validator's registered consensus key.
+    pub fn verify(&self, chain_id: u64, validators: &[ValidatorInfo]) -> Result<(), String> {
+        self.verify_with_key(chain_id, &consensus_key(validators, &self.validator)?)
+    }
+
+    pub fn verify_with_key(&self, chain_id: u64, pubkey: &[u8]) -> Result<(), String> {
+        if !bls::verify(pubkey, &self.signing_hash(chain_id), &self.signature) {
+            return Err(format!("Invalid vote signature from {}", self.validator.to_hex()));
+        }
+        Ok(())
+    }
+}
+
+/// The round proposer's choice of block. `valid_round` is the round in which the proposer
+/// saw a polka for this block, if it is re-proposing one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Proposal {
+    pub height: u64,
+    pub round: u32,
+    pub block_hash: [u8; 32],
+    pub valid_round: Option<u32>,
+    pub proposer: Address,
+    pub signature: Vec<u8>,
+}
+
+impl Proposal {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_signed(chain_id: u64, height: u64, round: u32, block_hash: [u8; 32], valid_round: Option<u32>, proposer: Address, key: &BlsSecretKey) -> Self {
+        let mut proposal = Self { height, round, block_hash, valid_round, proposer, signature: Vec::new() };
+        proposal.signature = key.sign(&proposal.signing_hash(chain_id));
+        proposal
+    }
+
+    pub fn signing_hash(&self, chain_id: u64) -> [u8; 32] {
+        let mut hasher = consensus_message(chain_id, 0u8, self.height, self.round, Some(self.block_hash));
+        hasher.update(self.valid_round.map(|r| r as i64).unwrap_or(-1).to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    pub fn verify(&self, chain_id: u64, validators: &[ValidatorInfo]) -> Result<(), String> {
+        let pubkey = consensus_key(validators, &self.proposer)?;
+        if !bls::verify(&pubkey, &self.signing_hash(chain_id), &self.signature) {
+            return Err(format!("Invalid proposal signature from {}", self.proposer.to_hex()));
+        }
+        Ok(())
+    }
+}
+
+/// Precommits from more than 2/3 of the stake for one block; proof that it is final.
+/// The precommit signatures are aggregated into one BLS signature, and `signers` is a
+/// bitmap over the voting validators (ordered by address) saying whose are included.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FinalityCommit {
     pub block_hash: [u8; 32],
     pub height: u64,
     pub round: u32,
-    pub signatures: HashMap<Address, Vec<u8>>,
+    pub signers: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl FinalityCommit {
+    /// Aggregates already verified precommits for `block_hash` into a commit.
+    pub fn aggregate<'a>(
+        block_hash: [u8; 32],
+        height: u64,
+        round: u32,
+        votes: impl IntoIterator<Item = &'a Vote>,
+        validators: &[ValidatorInfo],
+    ) -> Result<Self, String> {
+        let voters = voters(validators);
+        let mut signers = vec![0u8; voters.len().div_ceil(8)];
+        let mut signatures = Vec::new();
+        for vote in votes {
+            let i = voters.iter().position(|v| v.address == vote.validator)
+                .ok_or_else(|| format!("{} is not a voting validator", vote.validator.to_hex()))?;
+            if signers[i / 8] & (1 << (i % 8)) == 0 {
+                signers[i / 8] |= 1 << (i % 8);
+                signatures.push(vote.signature.as_slice());
+            }
+        }
+        let signature = bls::aggregate_signatures(signatures).ok_or("Precommit signature does not decode")?;
+        Ok(Self { block_hash, height, round, signers, signature })
+    }
+
+    /// Validators whose precommits the commit includes.
+    pub fn signers(&self, validators: &[ValidatorInfo]) -> Vec<Address> {
+        voters(validators).into_iter()
+            .enumerate()
+            .filter(|(i, _)| self.signers.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0))
+            .map(|(_, v)| v.address)
+            .collect()
+    }
+
+    pub fn signer_count(&self) -> usize {
+        self.signers.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Checks that the signers hold more than 2/3 of the voting stake and that the
+    /// aggregate signature is their precommit for this block.
+    pub fn verify(&self, chain_id: u64, validators: &[ValidatorInfo]) -> Result<(), String> {
+        let voters = voters(validators);
+        if self.signers.len() != voters.len().div_ceil(8) {
+            return Err("Commit signer bitmap does not match the validator set".to_string());
+        }
+        if (voters.len()..self.signers.len() * 8).any(|i| self.signers[i / 8] & (1 << (i % 8)) != 0) {
+            return Err("Commit signer bitmap has bits past the validator set".to_string());
+        }
+        let signed: Vec<&ValidatorInfo> = voters.into_iter()
+            .enumerate()
+            .filter(|(i, _)| self.signers[i / 8] & (1 << (i % 8)) != 0)
+            .map(|(_, v)| v)
+            .collect();
+
+        let committed_stake: u128 = signed.iter().map(|v| v.stake).sum();
+        if committed_stake * 3 <= voting_stake(validators) * 2 {
+            return Err("Commit does not carry more than 2/3 of the stake".to_string());
+        }
+        let precommit = Vote {
+            kind: VoteKind::Precommit,
+            height: self.height,
+            round: self.round,
+            block_hash: Some(self.block_hash),
+            validator: Address::ZERO,
+            signature: Vec::new(),
+        };
+        let pubkeys = signed.iter().map(|v| v.consensus_pubkey.as_slice());
+        if !bls::fast_aggregate_verify(pubkeys, &precommit.signing_hash(chain_id), &self.signature) {
+            return Err("Invalid aggregate commit signature".to_string());
+        }
+        Ok(())
+    }
 }
 
+/// Stake of the validators that may vote: active and with a registered consensus key.
+pub fn voting_stake(validators: &[ValidatorInfo]) -> u128 {
+    validators.iter().filter(|v| v.is_active && !v.consensus_pubkey.is_empty()).map(|v| v.stake).sum()
+}
+
+/// Tallies precommits for heights the node is not running rounds for (e.g. while it is
+/// behind), so it can adopt a commit the rest of the network already reached.
 pub struct FinalityGadget {
     pub last_finalized_height: u64,
     pub last_finalized_hash: [u8; 32],
-    pub pending_commits: HashMap<u64, FinalityCommit>,
+    /// (height, round, block hash) -> precommits collected so far
+    pub pending_votes: HashMap<(u64, u32, [u8; 32]), HashMap<Address, Vote>>,
 }
 
 impl Default for FinalityGadget {
@@ -30,70 +229,44 @@ impl FinalityGadget {
         Self {
             last_finalized_height: 0,
             last_finalized_hash: [0u8; 32],
-            pending_commits: HashMap::new(),
+            pending_votes: HashMap::new(),
         }
     }
 
-    pub fn add_vote(&mut self, block_hash: [u8; 32], height: u64, round: u32, validator: Address, signature: Vec<u8>, validators: &[ValidatorInfo]) -> bool {
-        // Verify validator exists
-        if !validators.iter().any(|v| v.address == validator) {
-            return false;
-        }
-        
-        // Initialize commit state for this height if missing
-        let commit = self.pending_commits.entry(height).or_insert(FinalityCommit {
-            block_hash,
-            height,
-            round,
-            signatures: HashMap::new(),
-        });
-
-        // Simple conflict check
-        if commit.block_hash != block_hash || commit.round != round {
-            return false;
-        }
-
-        // Add vote
-        commit.signatures.insert(validator, signature);
-        
-        // Check for finality
-        self.check_finality(height, validators)
-    }
-
-    fn check_finality(&mut self, height: u64, validators: &[ValidatorInfo]) -> bool {
-         if let Some(commit) = self.pending_commits.get(&height) {
-            let total_stake: u128 = validators.iter().filter(|v| v.is_active).map(|v| v.stake).sum();
-            let mut committed_stake = 0u128;
+    /// Verifies and adds a precommit; returns the aggregated commit once the precommits
+    /// reach 2/3 of the stake. Votes with a bad signature or from outside the set are
+    /// rejected.
+    pub fn add_vote(&mut self, vote: &Vote, validators: &[ValidatorInfo], chain_id: u64) -> Result<Option<FinalityCommit>, String> {
+        vote.verify(chain_id, validators)?;
+        let block_hash = match vote.block_hash {
+            Some(hash) if vote.kind == VoteKind::Precommit && vote.height > self.last_finalized_height => hash,
+            _ => return Ok(None),
+        };
 
-            for v in validators {
-                if commit.signatures.contains_key(&v.address) {
-                    committed_stake += v.stake;
-                }
-            }
-
-            if committed_stake * 3 > total_stake * 2 {
-                self.last_finalized_height = commit.height;
-                self.last_finalized_hash = commit.block_hash;
-                return true;
-            }
-         }
-         false
+        let votes = self.pending_votes.entry((vote.height, vote.round, block_hash)).or_default();
+        votes.entry(vote.validator).or_insert_with(|| vote.clone());
+        let commit = FinalityCommit::aggregate(block_hash, vote.height, vote.round, votes.values(), validators)?;
+        if self.process_commit(&commit, validators) {
+            Ok(Some(commit))
+        } else {
+            Ok(None)
+        }
     }
 
-    // Deprecated in favor of internal check, but kept for compatibility if needed (or removed)
+    /// Records `commit` as the latest finality if its signers hold 2/3 of the stake.
+    /// The aggregate signature is not checked here; see `FinalityCommit::verify`.
     pub fn process_commit(&mut self, commit: &FinalityCommit, validators: &[ValidatorInfo]) -> bool {
-         let total_stake: u128 = validators.iter().filter(|v| v.is_active).map(|v| v.stake).sum();
-         let mut committed_stake = 0u128;
-
-         for v in validators {
-             if commit.signatures.contains_key(&v.address) {
-                 committed_stake += v.stake;
-             }
-         }
+         let total_stake = voting_stake(validators);
+         let signers = commit.signers(validators);
+         let committed_stake: u128 = validators.iter()
+             .filter(|v| signers.contains(&v.address))
+             .map(|v| v.stake)
+             .sum();
 
          if committed_stake * 3 > total_stake * 2 {
              self.last_finalized_height = commit.height;
              self.last_finalized_hash = commit.block_hash;
+             self.pending_votes.retain(|(h, _, _), _| *h > commit.height);
              return true;
          }
          false
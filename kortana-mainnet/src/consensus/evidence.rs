@@ -0,0 +1,234 @@
+// File: src/consensus/evidence.rs
+//
+// Proof that a validator signed two conflicting messages: two blocks for one slot, or
+// two different votes of one kind at the same height and round. Any node that sees both
+// messages turns them into `Evidence`, gossips it and includes it in the next block it
+// proposes; every node verifies it against the state and burns part of the offender's
+// stake, once per offence.
+
+use serde::{Serialize, Deserialize};
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use crate::address::Address;
+use crate::consensus::bft::{Vote, VoteKind};
+use crate::consensus::SlashReason;
+use crate::parameters::*;
+use crate::staking::StakingStore;
+use crate::types::block::{Block, BlockHeader};
+
+/// How many heights below the newest one the pool remembers signed headers and votes for.
+pub const OBSERVATION_WINDOW: u64 = 256;
+
+/// A header with the proposer's signature over its hash.
+pub type SignedHeader = (BlockHeader, Vec<u8>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Evidence {
+    /// Two different headers for one slot, each with the proposer's signature.
+    DoubleProposal { first: Box<SignedHeader>, second: Box<SignedHeader> },
+    /// Two votes of one kind from the same validator at the same height and round, for
+    /// different blocks.
+    DuplicateVote { first: Vote, second: Vote },
+}
+
+impl Evidence {
+    pub fn offender(&self) -> Address {
+        match self {
+            Evidence::DoubleProposal { first, .. } => first.0.proposer,
+            Evidence::DuplicateVote { first, .. } => first.validator,
+        }
+    }
+
+    /// Height of the offence; for blocks on different forks, the lower one.
+    pub fn height(&self) -> u64 {
+        match self {
+            Evidence::DoubleProposal { first, second } => first.0.height.min(second.0.height),
+            Evidence::DuplicateVote { first, .. } => first.height,
+        }
+    }
+
+    pub fn reason(&self) -> SlashReason {
+        match self {
+            Evidence::DoubleProposal { .. } => SlashReason::DoubleProposal,
+            Evidence::DuplicateVote { .. } => SlashReason::Equivocation,
+        }
+    }
+
+    /// Share of the offender's stake burned, in basis points.
+    pub fn slash_fraction(&self, params: &ChainParams) -> u16 {
+        match self {
+            Evidence::DoubleProposal { .. } => params.slash_double_proposal_bps,
+            Evidence::DuplicateVote { .. } => params.slash_equivocation_bps,
+        }
+    }
+
+    /// Identifies the offence rather than the pair of messages, so signing three blocks
+    /// for a slot is not punished twice.
+    pub fn offence_id(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        match self {
+            Evidence::DoubleProposal { first, .. } => {
+                hasher.update(b"double-proposal");
+                hasher.update(first.0.proposer.to_bytes());
+                hasher.update(first.0.slot.to_be_bytes());
+            }
+            Evidence::DuplicateVote { first, .. } => {
+                hasher.update(b"duplicate-vote");
+                hasher.update(first.validator.to_bytes());
+                hasher.update(first.height.to_be_bytes());
+                hasher.update(first.round.to_be_bytes());
+                hasher.update([matches!(first.kind, VoteKind::Precommit) as u8]);
+            }
+        }
+        hasher.finalize().into()
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        let bytes = bincode::serialize(self).expect("Evidence serializes");
+        Keccak256::digest(bytes).into()
+    }
+
+    /// Checks that both messages are validly signed by a bonded validator and conflict,
+    /// and that the offence is recent enough to be punished at `height` and was not
+    /// punished already.
+    pub fn verify(&self, staking: &StakingStore, params: &ChainParams, chain_id: u64, height: u64) -> Result<(), String> {
+        if height.saturating_sub(self.height()) > params.max_evidence_age_blocks {
+            return Err(format!("Evidence from height {} is too old", self.height()));
+        }
+        if staking.is_slashed(&self.offence_id()) {
+            return Err("Offence has already been punished".to_string());
+        }
+        if staking.bonded_stake(&self.offender()) == 0 {
+            return Err(format!("{} has no bonded stake", self.offender().to_hex()));
+        }
+
+        match self {
+            Evidence::DoubleProposal { first, second } => {
+                if first.0.slot != second.0.slot || first.0.proposer != second.0.proposer {
+                    return Err("Headers are not from the same proposer and slot".to_string());
+                }
+                if first.0.hash() == second.0.hash() {
+                    return Err("Headers are identical".to_string());
+                }
+                for (header, signature) in [first.as_ref(), second.as_ref()] {
+                    let signer = crate::crypto::recover_pubkey(&header.hash(), signature).map(|pk| Address::from_pubkey(&pk));
+                    if signer != Some(header.proposer) {
+                        return Err("Header is not signed by its proposer".to_string());
+                    }
+                }
+            }
+            Evidence::DuplicateVote { first, second } => {
+                if (first.validator, first.height, first.round, first.kind) != (second.validator, second.height, second.round, second.kind) {
+                    return Err("Votes are not from the same validator, height, round and step".to_string());
+                }
+                if first.block_hash == second.block_hash {
+                    return Err("Votes do not conflict".to_string());
+                }
+                let key = staking.consensus_keys.get(&first.validator).ok_or("Validator has no consensus key")?;
+                first.verify_with_key(chain_id, key)?;
+                second.verify_with_key(chain_id, key)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remembers recent signed headers and votes to detect conflicts, and holds verified
+/// evidence until it is included in a block.
+#[derive(Default)]
+pub struct EvidencePool {
+    pending: HashMap<[u8; 32], Evidence>,
+    headers: HashMap<(Address, u64), Box<SignedHeader>>,
+    votes: HashMap<(Address, u64, u32, VoteKind), Vote>,
+}
+
+impl EvidencePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Records a block whose proposer signature checks out; returns evidence if the
+    /// proposer already signed another block for the same slot.
+    pub fn observe_block(&mut self, block: &Block) -> Option<Evidence> {
+        if block.recover_signer() != Some(block.header.proposer) {
+            return None;
+        }
+        let signed = Box::new((block.header.clone(), block.signature.clone()));
+        match self.headers.get(&(block.header.proposer, block.header.slot)) {
+            Some(seen) if seen.0.hash() != block.header.hash() => {
+                Some(Evidence::DoubleProposal { first: seen.clone(), second: signed })
+            }
+            Some(_) => None,
+            None => {
+                self.headers.insert((block.header.proposer, block.header.slot), signed);
+                None
+            }
+        }
+    }
+
+    /// Records a vote whose signature has been verified; returns evidence if the
+    /// validator already voted differently in the same step.
+    pub fn observe_vote(&mut self, vote: &Vote) -> Option<Evidence> {
+        let key = (vote.validator, vote.height, vote.round, vote.kind);
+        match self.votes.get(&key) {
+            Some(seen) if seen.block_hash != vote.block_hash => {
+                Some(Evidence::DuplicateVote { first: seen.clone(), second: vote.clone() })
+            }
+            Some(_) => None,
+            None => {
+                self.votes.insert(key, vote.clone());
+                None
+            }
+        }
+    }
+
+    /// Verifies and queues evidence for inclusion. Returns false if the offence is
+    /// already queued.
+    pub fn add(&mut self, evidence: Evidence, staking: &StakingStore, params: &ChainParams, chain_id: u64, height: u64) -> Result<bool, String> {
+        let id = evidence.offence_id();
+        if self.pending.contains_key(&id) {
+            return Ok(false);
+        }
+        evidence.verify(staking, params, chain_id, height)?;
+        self.pending.insert(id, evidence);
+        Ok(true)
+    }
+
+    /// Queues evidence from a block that left the canonical chain; it is verified again
+    /// before inclusion.
+    pub fn restore(&mut self, evidence: Evidence) {
+        self.pending.entry(evidence.offence_id()).or_insert(evidence);
+    }
+
+    /// Up to `max_evidence_per_block` pieces of evidence that are still valid for a
+    /// block at `height`, oldest offence first. Evidence that is no longer valid is dropped.
+    pub fn select(&mut self, staking: &StakingStore, params: &ChainParams, chain_id: u64, height: u64) -> Vec<Evidence> {
+        self.pending.retain(|_, e| e.verify(staking, params, chain_id, height).is_ok());
+        let mut selected: Vec<Evidence> = self.pending.values().cloned().collect();
+        selected.sort_by_key(|e| (e.height(), e.offence_id()));
+        selected.truncate(params.max_evidence_per_block as usize);
+        selected
+    }
+
+    /// Forgets evidence that made it into a block.
+    pub fn remove_included(&mut self, included: &[Evidence]) {
+        for evidence in included {
+            self.pending.remove(&evidence.offence_id());
+        }
+    }
+
+    /// Drops observations more than `OBSERVATION_WINDOW` heights below `height`.
+    pub fn prune(&mut self, height: u64) {
+        let min = height.saturating_sub(OBSERVATION_WINDOW);
+        self.headers.retain(|_, signed| signed.0.height >= min);
+        self.votes.retain(|(_, h, _, _), _| *h >= min);
+    }
+}
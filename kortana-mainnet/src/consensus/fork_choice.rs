@@ -0,0 +1,232 @@
+// File: src/consensus/fork_choice.rs
+//
+// Tree of blocks above the last final block, and the fork-choice rule that picks the
+// canonical tip among them. The tree holds canonical and competing blocks alike, so the
+// node can switch branches without refetching anything.
+//
+// Fork choice: the longest branch on top of the root wins; between branches of equal
+// length the tip with the lowest hash wins, so every node settles on the same head
+// regardless of arrival order.
+
+use std::collections::{HashMap, HashSet};
+use crate::types::block::Block;
+
+/// Blocks deeper than this below the head are treated as final even without a BFT
+/// commit, which bounds both reorg depth and the size of the tree.
+pub const MAX_REORG_DEPTH: u64 = 64;
+
+pub struct BlockTree {
+    root_height: u64,
+    root_hash: [u8; 32],
+    blocks: HashMap<[u8; 32], Block>,
+    children: HashMap<[u8; 32], Vec<[u8; 32]>>,
+    /// Blocks that failed execution; their descendants are refused too.
+    invalid: HashSet<[u8; 32]>,
+    /// Block the validators committed to; fork choice only considers its branch.
+    required: Option<[u8; 32]>,
+}
+
+impl BlockTree {
+    pub fn new(root_height: u64, root_hash: [u8; 32]) -> Self {
+        Self {
+            root_height,
+            root_hash,
+            blocks: HashMap::new(),
+            children: HashMap::new(),
+            invalid: HashSet::new(),
+            required: None,
+        }
+    }
+
+    pub fn root(&self) -> (u64, [u8; 32]) {
+        (self.root_height, self.root_hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn contains(&self, hash: &[u8; 32]) -> bool {
+        *hash == self.root_hash || self.blocks.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &[u8; 32]) -> Option<&Block> {
+        self.blocks.get(hash)
+    }
+
+    pub fn is_invalid(&self, hash: &[u8; 32]) -> bool {
+        self.invalid.contains(hash)
+    }
+
+    /// Height of `hash` if it is the root or in the tree.
+    pub fn height_of(&self, hash: &[u8; 32]) -> Option<u64> {
+        if *hash == self.root_hash {
+            return Some(self.root_height);
+        }
+        self.blocks.get(hash).map(|b| b.header.height)
+    }
+
+    /// Adds a block whose parent is the root or already in the tree.
+    pub fn insert(&mut self, block: Block) -> Result<(), String> {
+        let hash = block.header.hash();
+        let parent = block.header.parent_hash;
+        if self.blocks.contains_key(&hash) {
+            return Ok(());
+        }
+        if self.invalid.contains(&parent) {
+            self.invalid.insert(hash);
+            return Err("Block descends from an invalid block".to_string());
+        }
+        match self.height_of(&parent) {
+            Some(h) if h + 1 == block.header.height => {}
+            Some(_) => return Err("Block height does not follow its parent".to_string()),
+            None => return Err("Unknown parent".to_string()),
+        }
+        self.children.entry(parent).or_default().push(hash);
+        self.blocks.insert(hash, block);
+        Ok(())
+    }
+
+    /// Restricts fork choice to tips descending from `hash` (which must be in the tree)
+    /// until it is finalized.
+    pub fn require(&mut self, hash: [u8; 32]) -> Result<(), String> {
+        if !self.blocks.contains_key(&hash) {
+            return Err("Required block is not in the block tree".to_string());
+        }
+        self.required = Some(hash);
+        Ok(())
+    }
+
+    /// True if `hash` is `ancestor` or descends from it within the tree.
+    fn descends_from(&self, hash: &[u8; 32], ancestor: &[u8; 32]) -> bool {
+        let mut cursor = *hash;
+        loop {
+            if cursor == *ancestor {
+                return true;
+            }
+            match self.blocks.get(&cursor) {
+                Some(block) => cursor = block.header.parent_hash,
+                None => return false,
+            }
+        }
+    }
+
+    /// Tip selected by the fork-choice rule; the root if the tree is empty.
+    pub fn best_tip(&self) -> [u8; 32] {
+        self.blocks.values()
+            .filter(|b| self.children.get(&b.header.hash()).is_none_or(|c| c.is_empty()))
+            .filter(|b| self.required.is_none_or(|r| self.descends_from(&b.header.hash(), &r)))
+            .map(|b| (b.header.height, b.header.hash()))
+            .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))
+            .map(|(_, hash)| hash)
+            .or(self.required)
+            .unwrap_or(self.root_hash)
+    }
+
+    /// Hashes from just above the root up to and including `tip`, lowest first.
+    pub fn branch_to(&self, tip: &[u8; 32]) -> Vec<[u8; 32]> {
+        let mut branch = Vec::new();
+        let mut cursor = *tip;
+        while let Some(block) = self.blocks.get(&cursor) {
+            branch.push(cursor);
+            cursor = block.header.parent_hash;
+        }
+        branch.reverse();
+        branch
+    }
+
+    /// Drops `hash` and all of its descendants and refuses them from now on.
+    pub fn mark_invalid(&mut self, hash: &[u8; 32]) {
+        let mut stack = vec![*hash];
+        while let Some(h) = stack.pop() {
+            self.invalid.insert(h);
+            if let Some(block) = self.blocks.remove(&h) {
+                if let Some(siblings) = self.children.get_mut(&block.header.parent_hash) {
+                    siblings.retain(|c| *c != h);
+                }
+            }
+            if let Some(kids) = self.children.remove(&h) {
+                stack.extend(kids);
+            }
+        }
+    }
+
+    /// Makes `hash` (which must be in the tree) the new root, discarding every block
+    /// that does not descend from it.
+    pub fn finalize(&mut self, hash: &[u8; 32]) -> Result<(), String> {
+        if *hash == self.root_hash {
+            return Ok(());
+        }
+        let height = self.height_of(hash).ok_or("Finalized block is not in the block tree")?;
+
+        let mut keep = HashSet::new();
+        let mut stack = vec![*hash];
+        while let Some(h) = stack.pop() {
+            if let Some(kids) = self.children.get(&h) {
+                stack.extend(kids.iter().copied());
+            }
+            keep.insert(h);
+        }
+        keep.remove(hash);
+
+        self.blocks.retain(|h, _| keep.contains(h));
+        self.children.retain(|h, _| keep.contains(h) || h == hash);
+        // Anything at or below the new root can no longer be imported anyway.
+        self.invalid.clear();
+        self.required = None;
+        self.root_height = height;
+        self.root_hash = *hash;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::genesis::create_genesis_block;
+
+    fn child(parent: &Block, salt: u8) -> Block {
+        let mut block = create_genesis_block([salt; 32]);
+        block.header.height = parent.header.height + 1;
+        block.header.parent_hash = parent.header.hash();
+        block
+    }
+
+    #[test]
+    fn test_longest_branch_wins_and_ties_break_on_hash() {
+        let genesis = create_genesis_block([0u8; 32]);
+        let mut tree = BlockTree::new(0, genesis.header.hash());
+        let a1 = child(&genesis, 1);
+        let b1 = child(&genesis, 2);
+        tree.insert(a1.clone()).unwrap();
+        tree.insert(b1.clone()).unwrap();
+        let expected = a1.header.hash().min(b1.header.hash());
+        assert_eq!(tree.best_tip(), expected);
+
+        let b2 = child(&b1, 3);
+        tree.insert(b2.clone()).unwrap();
+        assert_eq!(tree.best_tip(), b2.header.hash());
+        assert_eq!(tree.branch_to(&b2.header.hash()), vec![b1.header.hash(), b2.header.hash()]);
+
+        tree.mark_invalid(&b1.header.hash());
+        assert_eq!(tree.best_tip(), a1.header.hash());
+        assert!(tree.insert(child(&b2, 4)).is_err());
+
+        // A committed block outranks a longer competing branch
+        let c1 = child(&genesis, 5);
+        let c2 = child(&c1, 6);
+        tree.insert(c1).unwrap();
+        tree.insert(c2.clone()).unwrap();
+        assert_eq!(tree.best_tip(), c2.header.hash());
+        tree.require(a1.header.hash()).unwrap();
+        assert_eq!(tree.best_tip(), a1.header.hash());
+
+        tree.finalize(&a1.header.hash()).unwrap();
+        assert_eq!(tree.root(), (1, a1.header.hash()));
+        assert!(tree.is_empty());
+    }
+}
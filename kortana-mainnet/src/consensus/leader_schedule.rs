@@ -0,0 +1,97 @@
+// File: src/consensus/leader_schedule.rs
+//
+// Leader schedule of an epoch. It is drawn once, when the epoch's validator set is
+// elected, from the epoch seed and the elected stakes, so every node holds the same
+// schedule for the whole epoch and validators know their slots in advance.
+
+use serde::{Serialize, Deserialize};
+use sha3::{Digest, Keccak256};
+use crate::address::Address;
+use crate::consensus::ValidatorInfo;
+use crate::parameters::BLOCKS_PER_EPOCH;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderSchedule {
+    pub epoch: u64,
+    pub seed: [u8; 32],
+    /// Leader of every slot `s` with `s % BLOCKS_PER_EPOCH == i` at index `i`.
+    pub leaders: Vec<Address>,
+}
+
+impl LeaderSchedule {
+    /// Draws `BLOCKS_PER_EPOCH` leaders from the active validators with stake, each slot
+    /// going to a validator with probability proportional to its stake.
+    pub fn new(epoch: u64, seed: [u8; 32], validators: &[ValidatorInfo]) -> Self {
+        let mut eligible: Vec<&ValidatorInfo> = validators.iter().filter(|v| v.is_active && v.stake > 0).collect();
+        eligible.sort_by_key(|v| v.address);
+        let total_stake: u128 = eligible.iter().map(|v| v.stake).sum();
+        let leaders = if total_stake == 0 {
+            Vec::new()
+        } else {
+            (0..BLOCKS_PER_EPOCH).map(|index| Self::draw(&eligible, total_stake, &seed, index)).collect()
+        };
+        Self { epoch, seed, leaders }
+    }
+
+    fn draw(eligible: &[&ValidatorInfo], total_stake: u128, seed: &[u8; 32], index: u64) -> Address {
+        let mut hasher = Keccak256::new();
+        hasher.update(seed);
+        hasher.update(index.to_be_bytes());
+        let hash = hasher.finalize();
+        let mut ticket = u128::from_be_bytes(hash[0..16].try_into().unwrap()) % total_stake;
+        for v in eligible {
+            if ticket < v.stake {
+                return v.address;
+            }
+            ticket -= v.stake;
+        }
+        unreachable!("ticket is below the total stake")
+    }
+
+    pub fn leader(&self, slot: u64) -> Option<Address> {
+        if self.leaders.is_empty() {
+            return None;
+        }
+        self.leaders.get((slot % self.leaders.len() as u64) as usize).copied()
+    }
+
+    /// Slot indices (`slot % BLOCKS_PER_EPOCH`) led by `validator`.
+    pub fn slots_of(&self, validator: &Address) -> Vec<u64> {
+        self.leaders.iter().enumerate().filter(|(_, a)| *a == validator).map(|(i, _)| i as u64).collect()
+    }
+}
+
+/// Seed of the schedule for `epoch`, mixing in the VRF output of the block that
+/// elected it, which its proposer could not choose.
+pub fn epoch_seed(epoch: u64, vrf_output: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"KORTANA/EPOCH/v1");
+    hasher.update(epoch.to_be_bytes());
+    hasher.update(vrf_output);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_is_stake_weighted() {
+        let heavy = Address::from_pubkey(b"v1");
+        let validators = vec![
+            ValidatorInfo { address: heavy, stake: 900, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+            ValidatorInfo { address: Address::from_pubkey(b"v2"), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+            ValidatorInfo { address: Address::from_pubkey(b"v3"), stake: 5000, is_active: false, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+        ];
+        let schedule = LeaderSchedule::new(3, epoch_seed(3, &[7u8; 32]), &validators);
+        assert_eq!(schedule.leaders.len() as u64, BLOCKS_PER_EPOCH);
+        let won = schedule.slots_of(&heavy).len() as u64;
+        assert!(won > BLOCKS_PER_EPOCH * 8 / 10, "heavy validator led {} slots", won);
+        assert!(schedule.slots_of(&Address::from_pubkey(b"v3")).is_empty());
+        assert_eq!(schedule.leader(BLOCKS_PER_EPOCH + 5), schedule.leader(5));
+
+        // Same inputs, same schedule; another seed reshuffles it
+        assert_eq!(schedule, LeaderSchedule::new(3, epoch_seed(3, &[7u8; 32]), &validators));
+        assert_ne!(schedule.leaders, LeaderSchedule::new(4, epoch_seed(4, &[7u8; 32]), &validators).leaders);
+    }
+}
@@ -1,16 +1,26 @@
 pub mod sync;
 pub mod bft;
+pub mod evidence;
+pub mod fork_choice;
+pub mod leader_schedule;
+pub mod slot_clock;
+pub mod snap;
+pub mod tendermint;
 
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use crate::address::Address;
 use serde::{Serialize, Deserialize};
 use crate::parameters::*;
+use leader_schedule::LeaderSchedule;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PohEntry {
     pub hash: [u8; 32],
     pub sequence: u64,
+    /// Transaction hash mixed into the last hash of this entry, if any.
+    #[serde(default)]
+    pub mixin: Option<[u8; 32]>,
 }
 
 pub struct PohGenerator {
@@ -30,18 +40,37 @@ impl PohGenerator {
         }
     }
 
+    /// Continues a stream from a known point, e.g. the PoH tail of a block header.
+    pub fn resume(last_hash: [u8; 32], sequence: u64) -> Self {
+        Self { last_hash, sequence }
+    }
+
+    pub fn last_hash(&self) -> [u8; 32] {
+        self.last_hash
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Advances the stream by `n` hashes without recording an entry.
+    pub fn hash(&mut self, n: u64) {
+        for _ in 0..n {
+            self.last_hash = Keccak256::digest(self.last_hash).into();
+        }
+        self.sequence += n;
+    }
+
     pub fn tick(&mut self) -> PohEntry {
-        let mut hasher = Keccak256::new();
-        hasher.update(self.last_hash);
-        self.last_hash = hasher.finalize().into();
-        self.sequence += 1;
+        self.hash(1);
         PohEntry {
             hash: self.last_hash,
             sequence: self.sequence,
+            mixin: None,
         }
     }
 
-    pub fn hash_transaction(&mut self, tx_hash: &[u8]) -> PohEntry {
+    pub fn hash_transaction(&mut self, tx_hash: &[u8; 32]) -> PohEntry {
         let mut hasher = Keccak256::new();
         hasher.update(self.last_hash);
         hasher.update(tx_hash);
@@ -50,19 +79,46 @@ impl PohGenerator {
         PohEntry {
             hash: self.last_hash,
             sequence: self.sequence,
+            mixin: Some(*tx_hash),
         }
     }
 
-    pub fn verify(start_hash: [u8; 32], entries: &[PohEntry]) -> bool {
-        let mut current_hash = start_hash;
+    /// Checks that `entries` continue the stream at (`start_hash`, `start_sequence`).
+    /// Each entry starts from the hash of the one before it, so the segments are
+    /// replayed independently across threads.
+    pub fn verify(start_hash: [u8; 32], start_sequence: u64, entries: &[PohEntry]) -> bool {
+        if entries.is_empty() {
+            return true;
+        }
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(entries.len());
+        let chunk_len = entries.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = entries.chunks(chunk_len).enumerate().map(|(i, chunk)| {
+                let (hash, sequence) = match i {
+                    0 => (start_hash, start_sequence),
+                    _ => {
+                        let prev = &entries[i * chunk_len - 1];
+                        (prev.hash, prev.sequence)
+                    }
+                };
+                scope.spawn(move || Self::verify_segment(hash, sequence, chunk))
+            }).collect();
+            handles.into_iter().all(|h| h.join().unwrap_or(false))
+        })
+    }
+
+    fn verify_segment(start_hash: [u8; 32], start_sequence: u64, entries: &[PohEntry]) -> bool {
+        let mut poh = Self::resume(start_hash, start_sequence);
         for entry in entries {
-            let mut hasher = Keccak256::new();
-            hasher.update(current_hash);
-            // If the entry contains a transaction (indicated by hash jump), 
-            // we would need that tx hash here. For simplicity, we assume 
-            // pure PoH chain verification here.
-            current_hash = hasher.finalize().into();
-            if current_hash != entry.hash {
+            if entry.sequence <= poh.sequence {
+                return false;
+            }
+            poh.hash(entry.sequence - poh.sequence - 1);
+            let replayed = match &entry.mixin {
+                Some(tx_hash) => poh.hash_transaction(tx_hash),
+                None => poh.tick(),
+            };
+            if replayed != *entry {
                 return false;
             }
         }
@@ -70,12 +126,54 @@ impl PohGenerator {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Vote {
-    pub block_hash: [u8; 32],
-    pub validator: Address,
-    pub slot: u64,
-    pub signature: Vec<u8>,
+/// The local PoH stream, running on top of the current head. It ticks through every
+/// slot as it passes and records transactions as the proposer includes them; the
+/// entries recorded since the head become the PoH segment of the next block.
+pub struct PohRecorder {
+    generator: PohGenerator,
+    head_hash: [u8; 32],
+    slot: u64,
+    entries: Vec<PohEntry>,
+}
+
+impl PohRecorder {
+    pub fn new(head: &crate::types::block::BlockHeader) -> Self {
+        Self {
+            generator: PohGenerator::resume(head.poh_hash, head.poh_sequence),
+            head_hash: head.hash(),
+            slot: head.slot,
+            entries: Vec::new(),
+        }
+    }
+
+    /// True if the stream continues the block with hash `head_hash`.
+    pub fn is_on(&self, head_hash: &[u8; 32]) -> bool {
+        self.head_hash == *head_hash
+    }
+
+    /// Ticks through every slot up to and including `slot`.
+    pub fn tick_to(&mut self, slot: u64) {
+        while self.slot < slot {
+            for _ in 0..POH_TICKS_PER_SLOT {
+                self.generator.hash(POH_HASHES_PER_TICK - 1);
+                self.entries.push(self.generator.tick());
+            }
+            self.slot += 1;
+        }
+    }
+
+    pub fn record(&mut self, tx_hash: &[u8; 32]) {
+        self.entries.push(self.generator.hash_transaction(tx_hash));
+    }
+
+    /// The current tail of the stream, as it goes into a block header.
+    pub fn tail(&self) -> ([u8; 32], u64) {
+        (self.generator.last_hash(), self.generator.sequence())
+    }
+
+    pub fn entries(&self) -> &[PohEntry] {
+        &self.entries
+    }
 }
 
 pub struct VoteAggregator {
@@ -129,8 +227,12 @@ pub struct ConsensusEngine {
     pub vote_aggregator: VoteAggregator,
     pub finalized_height: u64,
     pub finalized_hash: [u8; 32],
+    /// Canonical head selected by fork choice; new blocks are built on top of it.
+    pub head_hash: [u8; 32],
     pub slashing_history: SlashingHistory,
     pub jailed_validators: HashMap<Address, u64>, // addr -> jail_until_slot
+    /// Schedule of the current epoch, copied from the staking state.
+    pub leader_schedule: LeaderSchedule,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,27 +242,32 @@ pub struct ValidatorInfo {
     pub is_active: bool,
     pub commission: u16, // basis colors
     pub missed_blocks: u64,
+    /// BLS key that signs this validator's BFT messages; empty if none is registered,
+    /// in which case the validator cannot vote.
+    pub consensus_pubkey: Vec<u8>,
 }
 
 impl ConsensusEngine {
     pub fn new(validators: Vec<ValidatorInfo>) -> Self {
         Self {
             current_slot: 0,
+            leader_schedule: LeaderSchedule::new(0, [0u8; 32], &validators),
             validators,
             vote_aggregator: VoteAggregator::new(),
             finalized_height: 0,
             finalized_hash: [0u8; 32],
+            head_hash: [0u8; 32],
             slashing_history: SlashingHistory { records: HashMap::new() },
             jailed_validators: HashMap::new(),
         }
     }
 
-    pub fn slash_validator(&mut self, addr: Address, reason: SlashReason, slot: u64) {
+    pub fn slash_validator(&mut self, addr: Address, reason: SlashReason, slot: u64, params: &ChainParams) {
         let slash_percent = match reason {
-            SlashReason::DoubleProposal => SLASH_DOUBLE_PROPOSAL,
-            SlashReason::Equivocation => SLASH_EQUIVOCATION,
-            SlashReason::Downtime => SLASH_DOWNTIME,
-            SlashReason::Byzantine => SLASH_BYZANTINE,
+            SlashReason::DoubleProposal => params.slash_double_proposal_bps,
+            SlashReason::Equivocation => params.slash_equivocation_bps,
+            SlashReason::Downtime => params.slash_downtime_bps,
+            SlashReason::Byzantine => params.slash_byzantine_bps,
         };
 
         if let Some(validator) = self.validators.iter_mut().find(|v| v.address == addr) {
@@ -172,7 +279,7 @@ impl ConsensusEngine {
             
             // Jail if necessary
             if slash_percent >= 1000 { // 10% or more
-                self.jail_validator(addr, slot + JAIL_DURATION_SLOTS);
+                self.jail_validator(addr, slot + params.jail_duration_slots);
             }
         }
     }
@@ -184,108 +291,56 @@ impl ConsensusEngine {
         }
     }
 
-    pub fn check_unjail(&mut self, addr: Address, current_slot: u64) {
-        if let Some(&until) = self.jailed_validators.get(&addr) {
-            if current_slot >= until {
-                self.jailed_validators.remove(&addr);
-                if let Some(v) = self.validators.iter_mut().find(|v| v.address == addr) {
-                    if v.stake >= MIN_VALIDATOR_STAKE {
-                        v.is_active = true;
-                    }
-                }
-            }
+    /// Replaces the validator set and jail sentences with the ones in the staking state,
+    /// which every node derives identically from the blocks it applied; the set changes
+    /// at epoch boundaries, see `StakingStore::elect_validators`. The leader schedule is
+    /// only copied when a new epoch's has been drawn.
+    pub fn sync_validators(&mut self, staking: &crate::staking::StakingStore) {
+        self.validators = staking.validator_set().to_vec();
+        self.jailed_validators = staking.jailed.iter().map(|(a, until)| (*a, *until)).collect();
+        let schedule = staking.leader_schedule();
+        if (schedule.epoch, schedule.seed) != (self.leader_schedule.epoch, self.leader_schedule.seed) {
+            self.leader_schedule = schedule.clone();
         }
     }
 
-    pub fn record_block_participation(&mut self, proposer: Address, _active_at_slot: u64) {
-        for v in self.validators.iter_mut() {
-            if v.is_active {
-                if v.address == proposer {
-                    v.missed_blocks = 0;
-                } else {
-                    v.missed_blocks += 1;
-                    if v.missed_blocks >= MAX_MISSED_BLOCKS_BEFORE_JAIL {
-                        // This would be called by the node logic
-                        // self.slash_validator(v.address, SlashReason::Downtime, active_at_slot);
-                    }
-                }
-            }
-        }
-    }
+    /// Verifies a precommit against the voter's consensus key and tallies it; a super
+    /// majority for a block finalizes it. Invalid votes are returned as errors so the
+    /// caller can report the sender.
+    pub fn process_vote(&mut self, vote: &bft::Vote, chain_id: u64) -> Result<(), String> {
+        vote.verify(chain_id, &self.bft_validators())?;
+        let block_hash = match vote.block_hash {
+            Some(hash) if vote.kind == bft::VoteKind::Precommit => hash,
+            _ => return Ok(()),
+        };
+        self.vote_aggregator.add_vote(block_hash, vote.validator, vote.signature.clone());
 
-    pub fn process_vote(&mut self, vote: Vote) {
-        self.vote_aggregator.add_vote(vote.block_hash, vote.validator, vote.signature);
-        
-        let stake = self.vote_aggregator.get_stake_for_block(vote.block_hash, &self.validators);
+        let stake = self.vote_aggregator.get_stake_for_block(block_hash, &self.bft_validators());
         if self.is_super_majority(stake) {
-            self.finalized_hash = vote.block_hash;
+            self.finalized_hash = block_hash;
         }
+        Ok(())
     }
 
-    pub fn get_leader(&self, slot: u64, prev_hash: [u8; 32]) -> Option<Address> {
-        let active_validators: Vec<_> = self.validators.iter()
-            .filter(|v| v.is_active && !self.jailed_validators.contains_key(&v.address))
-            .collect();
-            
-        if active_validators.is_empty() {
-            return None;
-        }
-        
-        let mut hasher = Keccak256::new();
-        hasher.update(slot.to_be_bytes());
-        hasher.update(prev_hash);
-        let hash = hasher.finalize();
-        let index = (u64::from_be_bytes(hash[0..8].try_into().unwrap()) % active_validators.len() as u64) as usize;
-        
-        Some(active_validators[index].address)
+    /// Validators that take part in BFT voting: active, not jailed and with a registered
+    /// consensus key.
+    pub fn bft_validators(&self) -> Vec<ValidatorInfo> {
+        self.validators.iter()
+            .filter(|v| v.is_active && !self.jailed_validators.contains_key(&v.address) && !v.consensus_pubkey.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Leader of `slot` in the current epoch's schedule; a jailed leader's slots stay empty.
+    pub fn get_leader(&self, slot: u64) -> Option<Address> {
+        self.leader_schedule.leader(slot).filter(|leader| !self.jailed_validators.contains_key(leader))
     }
 
     pub fn is_super_majority(&self, vote_stake: u128) -> bool {
-        let total_stake: u128 = self.validators.iter().filter(|v| v.is_active).map(|v| v.stake).sum();
+        let total_stake = bft::voting_stake(&self.bft_validators());
         if total_stake == 0 { return false; }
         vote_stake * 3 > total_stake * 2
     }
-
-    pub fn advance_era(&mut self, current_height: u64) {
-        if current_height.is_multiple_of(BLOCKS_PER_EPOCH) {
-            self.distribute_rewards(current_height);
-            self.recompute_active_set();
-        }
-    }
-
-    fn distribute_rewards(&mut self, height: u64) {
-        let reward_per_block = calculate_block_reward(height);
-        let total_reward = reward_per_block * BLOCKS_PER_EPOCH as u128;
-        
-        let total_active_stake: u128 = self.validators.iter()
-            .filter(|v| v.is_active)
-            .map(|v| v.stake)
-            .sum();
-
-        if total_active_stake == 0 { return; }
-
-        for v in self.validators.iter_mut() {
-            if v.is_active {
-                let validator_share = (total_reward * v.stake) / total_active_stake;
-                let commission_amount = (validator_share * v.commission as u128) / 10000;
-                let delegator_share = validator_share - commission_amount;
-                
-                v.stake += commission_amount;
-                // In a real system, delegator_share would be distributed to delegators
-                // For this implementation, we simplify and add it to the validator's pool balance
-                v.stake += delegator_share; 
-            }
-        }
-    }
-
-    fn recompute_active_set(&mut self) {
-        // Sort by stake descending
-        self.validators.sort_by(|a, b| b.stake.cmp(&a.stake));
-        
-        for (i, v) in self.validators.iter_mut().enumerate() {
-            v.is_active = i < ACTIVE_VALIDATOR_COUNT && v.stake >= MIN_VALIDATOR_STAKE && !self.jailed_validators.contains_key(&v.address);
-        }
-    }
 }
 
 #[cfg(test)]
@@ -301,11 +356,33 @@ mod tests {
         assert_eq!(tick2.sequence, 2);
     }
 
+    #[test]
+    fn test_poh_verifies_transaction_mixins() {
+        let start = PohGenerator::new(b"genesis");
+        let (start_hash, start_sequence) = (start.last_hash(), start.sequence());
+        let mut poh = PohGenerator::resume(start_hash, start_sequence);
+        let mut entries = Vec::new();
+        for i in 0..20u8 {
+            poh.hash(7);
+            entries.push(if i % 3 == 0 { poh.hash_transaction(&[i; 32]) } else { poh.tick() });
+        }
+        assert!(PohGenerator::verify(start_hash, start_sequence, &entries));
+
+        let mut swapped = entries.clone();
+        swapped[9].mixin = Some([42u8; 32]);
+        assert!(!PohGenerator::verify(start_hash, start_sequence, &swapped));
+
+        let mut dropped = entries.clone();
+        dropped[12].mixin = None;
+        assert!(!PohGenerator::verify(start_hash, start_sequence, &dropped));
+        assert!(!PohGenerator::verify([1u8; 32], start_sequence, &entries));
+    }
+
     #[test]
     fn test_leader_election() {
         let validators = vec![
-            ValidatorInfo { address: Address::from_pubkey(b"v1"), stake: 100, is_active: true, commission: 500, missed_blocks: 0 },
-            ValidatorInfo { address: Address::from_pubkey(b"v2"), stake: 100, is_active: true, commission: 500, missed_blocks: 0 },
+            ValidatorInfo { address: Address::from_pubkey(b"v1"), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+            ValidatorInfo { address: Address::from_pubkey(b"v2"), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
         ];
         let engine = ConsensusEngine::new(validators);
         let leader1 = engine.get_leader(1).unwrap();
@@ -313,4 +390,17 @@ mod tests {
         // Deterministic but likely different
         assert!(leader1 == leader1); 
     }
+
+    #[test]
+    fn test_jailed_leader_loses_its_slots() {
+        let validators = vec![
+            ValidatorInfo { address: Address::from_pubkey(b"v1"), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+            ValidatorInfo { address: Address::from_pubkey(b"v2"), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: vec![] },
+        ];
+        let mut engine = ConsensusEngine::new(validators);
+        let leader = engine.get_leader(1).unwrap();
+        engine.jail_validator(leader, 100);
+        assert_eq!(engine.get_leader(1), None);
+        assert!((0..BLOCKS_PER_EPOCH).filter_map(|slot| engine.get_leader(slot)).all(|l| l != leader));
+    }
 }
@@ -0,0 +1,47 @@
+// File: src/consensus/slot_clock.rs
+//
+// Slot timer of the node. A slot is one tick of an interval as long as the chain
+// parameter `block_time_secs`. Governance may change it at an epoch boundary, so the
+// node hands the clock the committed parameters every slot and the clock restarts at
+// the new length once they change.
+
+use std::time::Duration;
+use tokio::time::{Instant, Interval};
+use crate::parameters::ChainParams;
+
+pub struct SlotClock {
+    interval: Interval,
+    block_time_secs: u64,
+}
+
+impl SlotClock {
+    /// Clock ticking every `params.block_time_secs`; the first tick completes at once.
+    /// Must be called inside a Tokio runtime.
+    pub fn new(params: &ChainParams) -> Self {
+        Self {
+            interval: tokio::time::interval(Duration::from_secs(params.block_time_secs)),
+            block_time_secs: params.block_time_secs,
+        }
+    }
+
+    pub fn slot_duration(&self) -> Duration {
+        self.interval.period()
+    }
+
+    /// Completes at the start of the next slot.
+    pub async fn tick(&mut self) -> Instant {
+        self.interval.tick().await
+    }
+
+    /// Adopts the slot length in `params` if it differs from the current one: the next
+    /// slot starts a full new slot length from now. Returns true if the length changed.
+    pub fn follow(&mut self, params: &ChainParams) -> bool {
+        if params.block_time_secs == self.block_time_secs {
+            return false;
+        }
+        let period = Duration::from_secs(params.block_time_secs);
+        self.interval = tokio::time::interval_at(Instant::now() + period, period);
+        self.block_time_secs = params.block_time_secs;
+        true
+    }
+}
@@ -0,0 +1,451 @@
+// File: src/consensus/snap.rs
+//
+// Snap-style state sync. Instead of replaying from genesis, a fresh node takes the
+// operator's trusted checkpoint as its pivot, downloads the header chain up to it and
+// checks that it ends at the checkpoint hash, then fetches the pivot state in ranges:
+//
+//   1. account trie ranges, each checked against the pivot state root with edge proofs;
+//   2. storage trie ranges of every contract, checked against its `storage_root`;
+//   3. contract code, checked against its `code_hash`;
+//   4. the staking store, checked against the root in the staking account's storage.
+//
+// The assembled state is installed at the pivot and `SyncEngine` executes the remaining
+// blocks normally.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Instant;
+use sha3::{Digest, Keccak256};
+use crate::address::Address;
+use crate::consensus::sync::{MAX_BUFFERED_BATCHES, SYNC_REQUEST_TIMEOUT};
+use crate::consensus::ConsensusEngine;
+use crate::core::importer::BlockImporter;
+use crate::network::messages::{StateRange, SyncRequest, SyncResponse};
+use crate::staking::{staking_address, StakingStore, STAKING_ROOT_SLOT};
+use crate::state::account::{Account, State};
+use crate::state::trie::{verify_range_proof, MerklePatriciaTrie};
+use crate::storage::snapshot::{Checkpoint, SnapshotArchive};
+use crate::storage::Storage;
+use crate::types::block::{Block, BlockHeader};
+
+pub const MAX_HEADERS_PER_REQUEST: u64 = 512;
+pub const MAX_RANGE_ENTRIES: u64 = 256;
+pub const MAX_CODES_PER_REQUEST: usize = 32;
+/// The account key space is split into this many ranges, downloaded in parallel.
+pub const ACCOUNT_RANGE_PARTITIONS: usize = 4;
+
+const ACCOUNT_KEY_LEN: usize = 24;
+const STORAGE_KEY_LEN: usize = 32;
+
+/// Entries of `trie` in `[start, end]`, at most `max`, with edge proofs.
+pub fn state_range(trie: &MerklePatriciaTrie, start: &[u8], end: &[u8], max: u64) -> StateRange {
+    let max = max.min(MAX_RANGE_ENTRIES) as usize;
+    let mut entries = trie.range(start, max + 1);
+    entries.retain(|(k, _)| k.as_slice() <= end);
+    let complete = entries.len() <= max;
+    entries.truncate(max);
+    let mut proof = trie.prove(start);
+    proof.extend(trie.prove(&range_end(end, &entries, complete)));
+    StateRange { entries, proof, complete }
+}
+
+/// Last key covered by a range answer.
+fn range_end(requested_end: &[u8], entries: &[(Vec<u8>, Vec<u8>)], complete: bool) -> Vec<u8> {
+    match entries.last() {
+        Some((key, _)) if !complete => key.clone(),
+        _ => requested_end.to_vec(),
+    }
+}
+
+/// The key right after `key`, or None if `key` is the last possible one.
+fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+    let mut next = key.to_vec();
+    for byte in next.iter_mut().rev() {
+        if *byte == 0xff {
+            *byte = 0;
+        } else {
+            *byte += 1;
+            return Some(next);
+        }
+    }
+    None
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Remaining part of a trie key range; `next` is None once it is fully downloaded.
+#[derive(Debug, Clone)]
+struct RangeTask {
+    next: Option<Vec<u8>>,
+    end: Vec<u8>,
+}
+
+impl RangeTask {
+    fn full(key_len: usize) -> Self {
+        Self { next: Some(vec![0u8; key_len]), end: vec![0xffu8; key_len] }
+    }
+
+    fn advance(&mut self, range: &StateRange) {
+        let covered = range_end(&self.end, &range.entries, range.complete);
+        self.next = if covered >= self.end { None } else { next_key(&covered) };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnapTask {
+    Headers { start_height: u64 },
+    PivotBlock,
+    Accounts { partition: usize },
+    Storage { address: Address },
+    Codes { hashes: Vec<[u8; 32]> },
+    Staking,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapPhase {
+    Headers,
+    State,
+    Done,
+}
+
+pub struct SnapSync {
+    pub pivot_height: u64,
+    /// Trusted block at `pivot_height` the downloaded header chain must end in
+    pub checkpoint: Checkpoint,
+    pub phase: SnapPhase,
+    /// Verified headers 0..headers.len()
+    headers: Vec<BlockHeader>,
+    /// Downloaded header batches not yet connected to `headers`
+    header_batches: BTreeMap<u64, Vec<(BlockHeader, Vec<u8>)>>,
+    pivot_block: Option<Block>,
+    account_tasks: Vec<RangeTask>,
+    storage_tasks: HashMap<Address, RangeTask>,
+    pending_codes: BTreeSet<[u8; 32]>,
+    accounts: HashMap<Address, Account>,
+    storage: HashMap<Address, HashMap<[u8; 32], [u8; 32]>>,
+    codes: HashMap<[u8; 32], Vec<u8>>,
+    staking: Option<StakingStore>,
+    /// One outstanding request per peer
+    in_flight: HashMap<String, (SnapTask, Instant)>,
+    next_header_height: u64,
+}
+
+impl SnapSync {
+    pub fn new(genesis: BlockHeader, checkpoint: Checkpoint) -> Self {
+        let pivot_height = checkpoint.height;
+        let partition_size = 256 / ACCOUNT_RANGE_PARTITIONS;
+        let account_tasks = (0..ACCOUNT_RANGE_PARTITIONS)
+            .map(|i| {
+                let mut next = vec![0u8; ACCOUNT_KEY_LEN];
+                next[0] = (i * partition_size) as u8;
+                let mut end = vec![0xffu8; ACCOUNT_KEY_LEN];
+                end[0] = ((i + 1) * partition_size - 1) as u8;
+                RangeTask { next: Some(next), end }
+            })
+            .collect();
+        println!("Starting Snap Sync at pivot height {}", pivot_height);
+        Self {
+            pivot_height,
+            checkpoint,
+            phase: SnapPhase::Headers,
+            headers: vec![genesis],
+            header_batches: BTreeMap::new(),
+            pivot_block: None,
+            account_tasks,
+            storage_tasks: HashMap::new(),
+            pending_codes: BTreeSet::new(),
+            accounts: HashMap::new(),
+            storage: HashMap::new(),
+            codes: HashMap::new(),
+            staking: None,
+            in_flight: HashMap::new(),
+            next_header_height: 1,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == SnapPhase::Done
+    }
+
+    /// Accounts downloaded so far.
+    pub fn accounts_synced(&self) -> usize {
+        self.accounts.len()
+    }
+
+    fn pivot_state_root(&self) -> [u8; 32] {
+        self.headers[self.pivot_height as usize].state_root
+    }
+
+    pub fn on_request_failed(&mut self, peer: &str) {
+        if let Some((SnapTask::Headers { start_height }, _)) = self.in_flight.remove(peer) {
+            self.next_header_height = self.next_header_height.min(start_height);
+        }
+    }
+
+    /// Requests for idle peers out of `peers` (which must all be at or above the pivot).
+    pub fn next_requests(&mut self, peers: &[String], now: Instant) -> Vec<(String, SyncRequest)> {
+        let timed_out: Vec<String> = self.in_flight.iter()
+            .filter(|(_, (_, sent_at))| now.duration_since(*sent_at) > SYNC_REQUEST_TIMEOUT)
+            .map(|(p, _)| p.clone())
+            .collect();
+        for peer in timed_out {
+            self.on_request_failed(&peer);
+        }
+
+        let mut requests = Vec::new();
+        for peer in peers {
+            if self.in_flight.contains_key(peer) {
+                continue;
+            }
+            let task = match self.next_task() {
+                Some(t) => t,
+                None => break,
+            };
+            requests.push((peer.clone(), self.request_for(&task)));
+            self.in_flight.insert(peer.clone(), (task, now));
+        }
+        requests
+    }
+
+    fn next_task(&mut self) -> Option<SnapTask> {
+        let busy = |t: &SnapTask| self.in_flight.values().any(|(task, _)| task == t);
+        match self.phase {
+            SnapPhase::Headers => {
+                let start_height = self.next_header_height;
+                if start_height > self.pivot_height || self.header_batches.len() >= MAX_BUFFERED_BATCHES {
+                    return None;
+                }
+                self.next_header_height = (start_height + MAX_HEADERS_PER_REQUEST).min(self.pivot_height + 1);
+                Some(SnapTask::Headers { start_height })
+            }
+            SnapPhase::State => {
+                if self.pivot_block.is_none() && !busy(&SnapTask::PivotBlock) {
+                    return Some(SnapTask::PivotBlock);
+                }
+                if self.staking.is_none() && !busy(&SnapTask::Staking) {
+                    return Some(SnapTask::Staking);
+                }
+                for (partition, task) in self.account_tasks.iter().enumerate() {
+                    let t = SnapTask::Accounts { partition };
+                    if task.next.is_some() && !busy(&t) {
+                        return Some(t);
+                    }
+                }
+                for address in self.storage_tasks.keys() {
+                    let t = SnapTask::Storage { address: *address };
+                    if !busy(&t) {
+                        return Some(t);
+                    }
+                }
+                let requested: BTreeSet<[u8; 32]> = self.in_flight.values()
+                    .filter_map(|(t, _)| match t { SnapTask::Codes { hashes } => Some(hashes.clone()), _ => None })
+                    .flatten()
+                    .collect();
+                let hashes: Vec<[u8; 32]> = self.pending_codes.difference(&requested).take(MAX_CODES_PER_REQUEST).copied().collect();
+                if !hashes.is_empty() {
+                    return Some(SnapTask::Codes { hashes });
+                }
+                None
+            }
+            SnapPhase::Done => None,
+        }
+    }
+
+    fn request_for(&self, task: &SnapTask) -> SyncRequest {
+        let height = self.pivot_height;
+        match task {
+            SnapTask::Headers { start_height } => SyncRequest::Headers {
+                start_height: *start_height,
+                count: MAX_HEADERS_PER_REQUEST.min(self.pivot_height + 1 - start_height),
+            },
+            SnapTask::PivotBlock => SyncRequest::Blocks { start_height: height, count: 1 },
+            SnapTask::Accounts { partition } => {
+                let task = &self.account_tasks[*partition];
+                SyncRequest::AccountRange { height, start: task.next.clone().unwrap_or_default(), end: task.end.clone(), max: MAX_RANGE_ENTRIES }
+            }
+            SnapTask::Storage { address } => {
+                let task = &self.storage_tasks[address];
+                SyncRequest::StorageRange { height, address: *address, start: task.next.clone().unwrap_or_default(), end: task.end.clone(), max: MAX_RANGE_ENTRIES }
+            }
+            SnapTask::Codes { hashes } => SyncRequest::ByteCodes { hashes: hashes.clone() },
+            SnapTask::Staking => SyncRequest::Staking { height },
+        }
+    }
+
+    /// Verifies and stores an answer. An error means the peer sent invalid data; the task
+    /// goes back to the queue either way.
+    pub fn on_response(&mut self, peer: &str, response: SyncResponse, consensus: &ConsensusEngine, storage: &Storage, now: u64) -> Result<(), String> {
+        let task = match self.in_flight.remove(peer) {
+            Some((task, _)) => task,
+            None => return Ok(()),
+        };
+        let result = self.apply(&task, response, consensus, storage, now);
+        if result.is_err() {
+            if let SnapTask::Headers { start_height } = task {
+                self.next_header_height = self.next_header_height.min(start_height);
+            }
+        }
+        self.advance_phase();
+        result
+    }
+
+    fn apply(&mut self, task: &SnapTask, response: SyncResponse, consensus: &ConsensusEngine, storage: &Storage, now: u64) -> Result<(), String> {
+        match (task, response) {
+            (_, SyncResponse::Error(e)) => Err(format!("Peer could not serve snap request: {}", e)),
+            (SnapTask::Headers { start_height }, SyncResponse::Headers { start_height: got, headers }) => {
+                if got != *start_height || headers.is_empty() {
+                    return Err("Unexpected header batch".to_string());
+                }
+                // A short batch leaves the rest to be requested again
+                self.next_header_height = self.next_header_height.min(start_height + headers.len() as u64);
+                self.header_batches.insert(*start_height, headers);
+                self.connect_headers(consensus, storage, now)
+            }
+            (SnapTask::PivotBlock, SyncResponse::Blocks { blocks, .. }) => {
+                let block = blocks.into_iter().next().ok_or("Empty pivot block response")?;
+                if block.header.hash() != self.headers[self.pivot_height as usize].hash() {
+                    return Err("Pivot block does not match the verified header".to_string());
+                }
+                self.pivot_block = Some(block);
+                Ok(())
+            }
+            (SnapTask::Accounts { partition }, SyncResponse::AccountRange(range)) => {
+                let task = &self.account_tasks[*partition];
+                let start = task.next.clone().ok_or("Account range already complete")?;
+                let end = range_end(&task.end, &range.entries, range.complete);
+                verify_range_proof(self.pivot_state_root(), &start, &end, &range.entries, &range.proof)?;
+                for (key, value) in &range.entries {
+                    let bytes: [u8; ACCOUNT_KEY_LEN] = key.as_slice().try_into().map_err(|_| "Bad account key length")?;
+                    let address = Address::from_bytes(bytes)?;
+                    let account: Account = serde_json::from_slice(value).map_err(|e| e.to_string())?;
+                    if account.storage_root != [0u8; 32] {
+                        self.storage_tasks.insert(address, RangeTask::full(STORAGE_KEY_LEN));
+                    }
+                    if account.code_hash != [0u8; 32] && !self.codes.contains_key(&account.code_hash) {
+                        self.pending_codes.insert(account.code_hash);
+                    }
+                    self.accounts.insert(address, account);
+                }
+                self.account_tasks[*partition].advance(&range);
+                Ok(())
+            }
+            (SnapTask::Storage { address }, SyncResponse::StorageRange(range)) => {
+                let root = self.accounts.get(address).map(|a| a.storage_root).ok_or("Storage for unknown account")?;
+                let task = self.storage_tasks.get(address).ok_or("Storage range already complete")?;
+                let start = task.next.clone().ok_or("Storage range already complete")?;
+                let end = range_end(&task.end, &range.entries, range.complete);
+                verify_range_proof(root, &start, &end, &range.entries, &range.proof)?;
+                let slots = self.storage.entry(*address).or_default();
+                for (key, value) in &range.entries {
+                    let key: [u8; STORAGE_KEY_LEN] = key.as_slice().try_into().map_err(|_| "Bad storage key length")?;
+                    let value: [u8; 32] = value.as_slice().try_into().map_err(|_| "Bad storage value length")?;
+                    slots.insert(key, value);
+                }
+                let task = self.storage_tasks.get_mut(address).unwrap();
+                task.advance(&range);
+                if task.next.is_none() {
+                    self.storage_tasks.remove(address);
+                }
+                Ok(())
+            }
+            (SnapTask::Codes { hashes }, SyncResponse::ByteCodes(codes)) => {
+                for code in codes {
+                    let hash = keccak(&code);
+                    if !hashes.contains(&hash) {
+                        return Err("Peer sent code that was not requested".to_string());
+                    }
+                    self.pending_codes.remove(&hash);
+                    self.codes.insert(hash, code);
+                }
+                Ok(())
+            }
+            (SnapTask::Staking, SyncResponse::Staking(staking)) => {
+                self.staking = Some(*staking);
+                Ok(())
+            }
+            _ => Err("Response does not match the snap request".to_string()),
+        }
+    }
+
+    /// Verifies buffered header batches that extend the verified chain.
+    fn connect_headers(&mut self, consensus: &ConsensusEngine, storage: &Storage, now: u64) -> Result<(), String> {
+        let importer = BlockImporter::new(storage, consensus);
+        // Batches may overlap after a short answer was re-requested
+        while let Some((&start, _)) = self.header_batches.range(..=self.headers.len() as u64).next_back() {
+            let batch = self.header_batches.remove(&start).unwrap();
+            let skip = self.headers.len() - start as usize;
+            for (header, signature) in batch.into_iter().skip(skip) {
+                if self.headers.len() as u64 > self.pivot_height {
+                    break;
+                }
+                let parent = self.headers.last().unwrap();
+                let block = Block { header, transactions: Vec::new(), signature, evidence: Vec::new(), last_commit: None, poh_entries: Vec::new() };
+                if let Err(e) = importer.check_header(&block, parent, now) {
+                    self.next_header_height = self.next_header_height.min(self.headers.len() as u64);
+                    self.header_batches.clear();
+                    return Err(format!("Invalid header at height {}: {}", block.header.height, e));
+                }
+                if block.header.height == self.pivot_height && block.header.hash() != self.checkpoint.hash {
+                    self.next_header_height = self.next_header_height.min(self.headers.len() as u64);
+                    self.header_batches.clear();
+                    return Err(format!("Header at pivot {} is not the trusted checkpoint", self.pivot_height));
+                }
+                self.headers.push(block.header);
+            }
+        }
+        Ok(())
+    }
+
+    fn advance_phase(&mut self) {
+        if self.phase == SnapPhase::Headers && self.headers.len() as u64 > self.pivot_height {
+            println!("Snap Sync: headers verified up to pivot {}", self.pivot_height);
+            self.phase = SnapPhase::State;
+        }
+        if self.phase == SnapPhase::State
+            && self.pivot_block.is_some()
+            && self.staking.is_some()
+            && self.account_tasks.iter().all(|t| t.next.is_none())
+            && self.storage_tasks.is_empty()
+            && self.pending_codes.is_empty()
+        {
+            self.phase = SnapPhase::Done;
+        }
+    }
+
+    /// Assembles the downloaded state into a snapshot at the pivot. The caller installs
+    /// it with `Storage::install_snap_state`, which verifies it once more as a whole.
+    pub fn finish(self, chain_id: u64) -> Result<SnapshotArchive, String> {
+        if !self.is_done() {
+            return Err("Snap sync is not finished".to_string());
+        }
+        let mut state = State::new();
+        for (address, slots) in self.storage {
+            let trie = state.storage_tries.entry(address).or_default();
+            for (key, value) in &slots {
+                trie.insert(key, value.to_vec());
+            }
+            state.storage.insert(address, slots);
+        }
+        for (address, account) in self.accounts {
+            state.update_account(address, account);
+        }
+        state.codes = self.codes;
+        // The peer's staking store must hash to the root committed in the staking account
+        state.staking = self.staking.unwrap_or_default();
+        let committed = state.storage.get(&staking_address()).and_then(|slots| slots.get(&STAKING_ROOT_SLOT)).copied();
+        if committed != Some(state.staking.root()) {
+            return Err("Staking store does not match the state root".to_string());
+        }
+
+        let state_roots = self.headers.iter().map(|h| (h.height, h.state_root)).collect();
+        Ok(SnapshotArchive {
+            chain_id,
+            height: self.pivot_height,
+            headers: self.headers,
+            state_roots,
+            head_block: self.pivot_block.ok_or("Missing pivot block")?,
+            state,
+        })
+    }
+}
@@ -1,18 +1,113 @@
 // File: src/consensus/sync.rs
+//
+// Range sync over the `/kortana/sync/1` request-response protocol. Peers report their
+// head via `SyncRequest::Status`; while a peer is ahead of us the engine splits the gap
+// into fixed-size batches, downloads up to `MAX_PARALLEL_REQUESTS` of them at once from
+// different peers, and hands contiguous blocks back to the node, which executes them
+// through the normal import path.
 
-use crate::types::block::Block;
-use crate::storage::Storage;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::consensus::snap::{state_range, MAX_CODES_PER_REQUEST, MAX_HEADERS_PER_REQUEST};
+use crate::network::messages::{SyncRequest, SyncResponse};
+use crate::state::account::State;
+use crate::storage::Storage;
+use crate::types::block::Block;
+
+/// Blocks per batch request (and the most a node will serve in one response).
+pub const MAX_BLOCKS_PER_REQUEST: u64 = 64;
+/// Batches in flight at the same time.
+pub const MAX_PARALLEL_REQUESTS: usize = 4;
+/// A batch not answered within this time is re-requested from another peer.
+pub const SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Downloaded-but-unexecuted blocks are capped so a fast peer cannot exhaust memory.
+pub const MAX_BUFFERED_BATCHES: usize = 16;
+
+/// Answers an inbound sync request from local storage.
+pub fn serve_request(storage: &Storage, request: &SyncRequest) -> SyncResponse {
+    let result = match request {
+        SyncRequest::Status => (|| {
+            let genesis = storage.get_header(0)?.ok_or("No genesis block")?;
+            let head_height = storage.latest_height()?.unwrap_or(0);
+            let head = storage.get_header(head_height)?.ok_or("Missing head header")?;
+            Ok(SyncResponse::Status { genesis_hash: genesis.hash(), head_height, head_hash: head.hash() })
+        })(),
+        SyncRequest::Blocks { start_height, count } => (|| {
+            let head_height = storage.latest_height()?.unwrap_or(0);
+            let end = start_height.saturating_add((*count).min(MAX_BLOCKS_PER_REQUEST)).min(head_height + 1);
+            let mut blocks = Vec::new();
+            for h in *start_height..end {
+                match storage.get_block(h)? {
+                    Some(block) => blocks.push(block),
+                    None => break,
+                }
+            }
+            Ok(SyncResponse::Blocks { start_height: *start_height, blocks })
+        })(),
+        SyncRequest::Headers { start_height, count } => (|| {
+            let end = start_height.saturating_add((*count).min(MAX_HEADERS_PER_REQUEST));
+            let mut headers = Vec::new();
+            // Heights below a snapshot pivot have no signed block to serve
+            for h in *start_height..end {
+                match storage.get_block(h)? {
+                    Some(block) => headers.push((block.header, block.signature)),
+                    None => break,
+                }
+            }
+            Ok(SyncResponse::Headers { start_height: *start_height, headers })
+        })(),
+        SyncRequest::AccountRange { height, start, end, max } => load_state(storage, *height)
+            .map(|state| SyncResponse::AccountRange(state_range(&state.trie, start, end, *max))),
+        SyncRequest::StorageRange { height, address, start, end, max } => load_state(storage, *height).map(|state| {
+            let trie = state.storage_tries.get(address).cloned().unwrap_or_default();
+            SyncResponse::StorageRange(state_range(&trie, start, end, *max))
+        }),
+        SyncRequest::ByteCodes { hashes } => storage.latest_height().and_then(|h| load_state(storage, h.unwrap_or(0))).map(|state| {
+            SyncResponse::ByteCodes(hashes.iter().take(MAX_CODES_PER_REQUEST).filter_map(|h| state.get_code(h)).collect())
+        }),
+        SyncRequest::Staking { height } => load_state(storage, *height).map(|state| SyncResponse::Staking(Box::new(state.staking))),
+    };
+    result.unwrap_or_else(|e: String| SyncResponse::Error(e))
+}
+
+fn load_state(storage: &Storage, height: u64) -> Result<State, String> {
+    storage.get_state(height)?.ok_or_else(|| format!("No state stored at height {}", height))
+}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncState {
     Idle,
     FastSyncing { start_height: u64, target_height: u64 },
+    /// Caught up with the best known peer; following the chain via gossip.
     Broadcasting,
 }
 
+/// `eth_syncing` view of the sync engine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncProgress {
+    pub starting_block: u64,
+    pub current_block: u64,
+    pub highest_block: u64,
+}
+
+struct InFlight {
+    peer: String,
+    count: u64,
+    sent_at: Instant,
+}
+
 pub struct SyncEngine {
     pub state: SyncState,
     pub storage: Arc<Storage>,
+    /// peer id -> reported head height
+    peers: HashMap<String, u64>,
+    /// batch start height -> outstanding request
+    in_flight: BTreeMap<u64, InFlight>,
+    /// batch start height -> downloaded blocks awaiting execution
+    downloaded: BTreeMap<u64, Vec<Block>>,
+    /// Lowest height not yet requested.
+    next_height: u64,
 }
 
 impl SyncEngine {
@@ -20,33 +115,192 @@ impl SyncEngine {
         Self {
             state: SyncState::Idle,
             storage,
+            peers: HashMap::new(),
+            in_flight: BTreeMap::new(),
+            downloaded: BTreeMap::new(),
+            next_height: 0,
         }
     }
 
+    pub fn is_syncing(&self) -> bool {
+        matches!(self.state, SyncState::FastSyncing { .. })
+    }
+
+    pub fn progress(&self, current_height: u64) -> Option<SyncProgress> {
+        match self.state {
+            SyncState::FastSyncing { start_height, target_height } => Some(SyncProgress {
+                starting_block: start_height,
+                current_block: current_height,
+                highest_block: target_height,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Highest head reported by any peer.
+    pub fn best_peer_height(&self) -> u64 {
+        self.peers.values().copied().max().unwrap_or(0)
+    }
+
+    /// Peers whose reported head is at least `height`.
+    pub fn peers_at(&self, height: u64) -> Vec<String> {
+        self.peers.iter().filter(|(_, head)| **head >= height).map(|(p, _)| p.clone()).collect()
+    }
+
     pub fn start_fast_sync(&mut self, current_height: u64, target_height: u64) {
         println!("Starting Fast Sync: {} -> {}", current_height, target_height);
         self.state = SyncState::FastSyncing {
             start_height: current_height,
             target_height,
         };
+        self.next_height = self.next_height.max(current_height + 1);
     }
 
-    pub fn process_blocks_batch(&mut self, blocks: Vec<Block>) -> Result<u64, String> {
-        let mut last_height = 0;
-        for block in blocks {
-            // 1. Verify block exists in chain
-            // 2. Persist to storage
-            self.storage.put_block(&block)?;
-            last_height = block.header.height;
+    /// Records a peer's head; returns false if the peer is on a different chain.
+    pub fn on_status(&mut self, peer: &str, genesis_hash: [u8; 32], head_height: u64, local_genesis: [u8; 32], current_height: u64) -> bool {
+        if genesis_hash != local_genesis {
+            self.on_peer_disconnected(peer);
+            return false;
         }
-        
+        self.note_peer_height(peer, head_height, current_height);
+        true
+    }
+
+    /// Raises what we know about a peer's head, e.g. from a gossiped block we could not
+    /// attach, and starts syncing if it is ahead of us.
+    pub fn note_peer_height(&mut self, peer: &str, height: u64, current_height: u64) {
+        let known = self.peers.entry(peer.to_string()).or_insert(0);
+        *known = (*known).max(height);
+        let target = self.best_peer_height();
+        match self.state {
+            SyncState::FastSyncing { start_height, target_height } if target > target_height => {
+                self.state = SyncState::FastSyncing { start_height, target_height: target };
+            }
+            SyncState::FastSyncing { .. } => {}
+            _ if target > current_height + 1 => self.start_fast_sync(current_height, target),
+            _ => {}
+        }
+    }
+
+    pub fn on_peer_disconnected(&mut self, peer: &str) {
+        self.peers.remove(peer);
+        self.on_request_failed(peer);
+    }
+
+    /// Returns the batches assigned to `peer` to the queue.
+    pub fn on_request_failed(&mut self, peer: &str) {
+        let failed: Vec<u64> = self.in_flight.iter().filter(|(_, r)| r.peer == peer).map(|(h, _)| *h).collect();
+        for start in failed {
+            self.in_flight.remove(&start);
+            self.next_height = self.next_height.min(start);
+        }
+    }
+
+    /// Requests to send now: timed-out batches are reassigned and new batches are
+    /// spread over peers whose head covers them.
+    pub fn next_requests(&mut self, current_height: u64, now: Instant) -> Vec<(String, SyncRequest)> {
+        let target_height = match self.state {
+            SyncState::FastSyncing { target_height, .. } => target_height,
+            _ => return Vec::new(),
+        };
+        let timed_out: Vec<u64> = self.in_flight.iter()
+            .filter(|(_, r)| now.duration_since(r.sent_at) > SYNC_REQUEST_TIMEOUT)
+            .map(|(h, _)| *h)
+            .collect();
+        for start in timed_out {
+            self.in_flight.remove(&start);
+            self.next_height = self.next_height.min(start);
+        }
+        self.next_height = self.next_height.max(current_height + 1);
+
+        let mut requests = Vec::new();
+        while self.in_flight.len() < MAX_PARALLEL_REQUESTS
+            && self.downloaded.len() + self.in_flight.len() < MAX_BUFFERED_BATCHES
+            && self.next_height <= target_height
+        {
+            let start = self.next_height;
+            let covered = self.in_flight.get(&start).map(|r| r.count)
+                .or_else(|| self.downloaded.get(&start).map(|b| b.len() as u64));
+            if let Some(len) = covered {
+                self.next_height += len.max(1);
+                continue;
+            }
+            let count = MAX_BLOCKS_PER_REQUEST.min(target_height - start + 1);
+            // Least-loaded peer that has the whole batch
+            let peer = self.peers.iter()
+                .filter(|(_, head)| **head >= start + count - 1)
+                .min_by_key(|(p, _)| self.in_flight.values().filter(|r| &r.peer == *p).count())
+                .map(|(p, _)| p.clone());
+            let peer = match peer {
+                Some(p) => p,
+                None => break,
+            };
+            self.in_flight.insert(start, InFlight { peer: peer.clone(), count, sent_at: now });
+            requests.push((peer, SyncRequest::Blocks { start_height: start, count }));
+            self.next_height = start + count;
+        }
+        requests
+    }
+
+    /// Stores a batch answer. Returns an error (and requeues the batch) if the blocks
+    /// are not the consecutive chain segment that was asked for.
+    pub fn on_blocks(&mut self, peer: &str, start_height: u64, blocks: Vec<Block>) -> Result<(), String> {
+        let request = match self.in_flight.get(&start_height) {
+            Some(r) if r.peer == peer => r,
+            // Late answer for a batch that has since been reassigned
+            _ => return Ok(()),
+        };
+        let well_formed = !blocks.is_empty()
+            && blocks.len() as u64 <= request.count
+            && blocks.iter().enumerate().all(|(i, b)| b.header.height == start_height + i as u64)
+            && blocks.windows(2).all(|w| w[1].header.parent_hash == w[0].header.hash());
+        self.in_flight.remove(&start_height);
+        if !well_formed {
+            self.next_height = self.next_height.min(start_height);
+            return Err(format!("Malformed block batch from {} at height {}", peer, start_height));
+        }
+        // A short answer leaves the rest of the batch to be requested again
+        self.next_height = self.next_height.min(start_height + blocks.len() as u64);
+        self.downloaded.insert(start_height, blocks);
+        Ok(())
+    }
+
+    /// Downloaded blocks that directly extend `current_height`, in order.
+    pub fn take_ready(&mut self, current_height: u64) -> Vec<Block> {
+        let mut ready = Vec::new();
+        let mut next = current_height + 1;
+        // Drop batches that are already behind the head
+        self.downloaded.retain(|start, blocks| start + blocks.len() as u64 > next);
+        while let Some(mut batch) = self.downloaded.remove(&next).or_else(|| self.take_overlapping(next)) {
+            next += batch.len() as u64;
+            ready.append(&mut batch);
+        }
+        ready
+    }
+
+    fn take_overlapping(&mut self, next: u64) -> Option<Vec<Block>> {
+        let start = *self.downloaded.range(..next).next_back()?.0;
+        let batch = self.downloaded.remove(&start)?;
+        Some(batch.into_iter().skip((next - start) as usize).collect())
+    }
+
+    /// Blocks from a failed batch could not be executed; fetch that range again.
+    pub fn reset_from(&mut self, height: u64) {
+        self.downloaded.retain(|start, _| *start < height);
+        self.next_height = self.next_height.min(height);
+    }
+
+    /// Leaves `FastSyncing` once the head has reached the target.
+    pub fn check_complete(&mut self, current_height: u64) -> bool {
         if let SyncState::FastSyncing { target_height, .. } = self.state {
-            if last_height >= target_height {
+            if current_height >= target_height {
                 self.state = SyncState::Broadcasting;
-                println!("Fast Sync Complete at height {}", last_height);
+                self.in_flight.clear();
+                self.downloaded.clear();
+                println!("Fast Sync Complete at height {}", current_height);
+                return true;
             }
         }
-        
-        Ok(last_height)
+        false
     }
 }
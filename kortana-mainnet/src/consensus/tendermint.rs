@@ -0,0 +1,341 @@
+// File: src/consensus/tendermint.rs
+//
+// Tendermint round state machine that finalizes the blocks produced by slot leaders.
+// For each height the validators run rounds of propose -> prevote -> precommit:
+//
+//   * the round proposer proposes the block it holds at that height (or the block it
+//     saw a polka for in an earlier round);
+//   * validators prevote it if they executed it and are not locked on another block;
+//   * 2/3+ prevotes for a block (a polka) lock the validator on it and it precommits;
+//   * 2/3+ precommits for a block decide the height; 2/3+ precommits without a
+//     decision time out into the next round, with longer timeouts each round.
+//
+// The machine does no IO: every input returns the messages to broadcast, the timeouts
+// to schedule and, once decided, the `FinalityCommit`.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use crate::address::Address;
+use crate::consensus::bft::{FinalityCommit, Proposal, Vote, VoteKind};
+use crate::consensus::ValidatorInfo;
+use crate::crypto::bls::BlsSecretKey;
+
+pub const TIMEOUT_PROPOSE: Duration = Duration::from_millis(3000);
+pub const TIMEOUT_PREVOTE: Duration = Duration::from_millis(1000);
+pub const TIMEOUT_PRECOMMIT: Duration = Duration::from_millis(1000);
+/// Added to every timeout per round, so rounds slow down until messages get through.
+pub const TIMEOUT_DELTA: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Propose,
+    Prevote,
+    Precommit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    pub height: u64,
+    pub round: u32,
+    pub step: Step,
+    pub after: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub enum BftOutput {
+    Proposal(Proposal),
+    Vote(Vote),
+    Timeout(Timeout),
+    Commit(FinalityCommit),
+}
+
+/// Proposer of `round` at `height`: voting validators take turns.
+pub fn proposer_for(validators: &[ValidatorInfo], height: u64, round: u32) -> Option<Address> {
+    let active: Vec<&ValidatorInfo> = validators.iter().filter(|v| v.is_active && !v.consensus_pubkey.is_empty()).collect();
+    if active.is_empty() {
+        return None;
+    }
+    Some(active[((height + round as u64) % active.len() as u64) as usize].address)
+}
+
+pub struct RoundState {
+    chain_id: u64,
+    pub height: u64,
+    pub round: u32,
+    pub step: Step,
+    validators: Vec<ValidatorInfo>,
+    total_stake: u128,
+    /// Our address and BLS consensus key, if we are a validator
+    signer: Option<(Address, BlsSecretKey)>,
+    locked: Option<(u32, [u8; 32])>,
+    valid: Option<(u32, [u8; 32])>,
+    /// Blocks at this height we have executed; only these can be voted for
+    candidates: HashSet<[u8; 32]>,
+    /// Latest candidate, which we propose when it is our turn
+    preferred: Option<[u8; 32]>,
+    proposals: HashMap<u32, Proposal>,
+    votes: HashMap<(u32, VoteKind), HashMap<Address, Vote>>,
+    proposed: HashSet<u32>,
+    polka_seen: HashSet<u32>,
+    prevote_wait: HashSet<u32>,
+    precommit_wait: HashSet<u32>,
+    decision: Option<FinalityCommit>,
+}
+
+impl RoundState {
+    pub fn new(chain_id: u64, height: u64, validators: &[ValidatorInfo], signer: Option<(Address, BlsSecretKey)>) -> Self {
+        let validators: Vec<ValidatorInfo> = validators.iter()
+            .filter(|v| v.is_active && !v.consensus_pubkey.is_empty())
+            .cloned()
+            .collect();
+        let signer = signer.filter(|(addr, _)| validators.iter().any(|v| v.address == *addr));
+        Self {
+            chain_id,
+            height,
+            round: 0,
+            step: Step::Propose,
+            total_stake: validators.iter().map(|v| v.stake).sum(),
+            validators,
+            signer,
+            locked: None,
+            valid: None,
+            candidates: HashSet::new(),
+            preferred: None,
+            proposals: HashMap::new(),
+            votes: HashMap::new(),
+            proposed: HashSet::new(),
+            polka_seen: HashSet::new(),
+            prevote_wait: HashSet::new(),
+            precommit_wait: HashSet::new(),
+            decision: None,
+        }
+    }
+
+    pub fn start(&mut self) -> Vec<BftOutput> {
+        let mut out = Vec::new();
+        self.start_round(0, &mut out);
+        self.evaluate(&mut out);
+        out
+    }
+
+    pub fn decision(&self) -> Option<&FinalityCommit> {
+        self.decision.as_ref()
+    }
+
+    /// Registers a block at this height that the node has executed.
+    pub fn add_candidate(&mut self, block_hash: [u8; 32]) -> Vec<BftOutput> {
+        let mut out = Vec::new();
+        if self.candidates.insert(block_hash) {
+            self.preferred = Some(block_hash);
+            self.try_propose(&mut out);
+            self.evaluate(&mut out);
+        }
+        out
+    }
+
+    pub fn on_proposal(&mut self, proposal: Proposal) -> Result<Vec<BftOutput>, String> {
+        if proposal.height != self.height {
+            return Ok(Vec::new());
+        }
+        if proposer_for(&self.validators, proposal.height, proposal.round) != Some(proposal.proposer) {
+            return Err(format!("{} is not the proposer of round {}", proposal.proposer.to_hex(), proposal.round));
+        }
+        proposal.verify(self.chain_id, &self.validators)?;
+        let mut out = Vec::new();
+        self.proposals.entry(proposal.round).or_insert(proposal);
+        self.evaluate(&mut out);
+        Ok(out)
+    }
+
+    pub fn on_vote(&mut self, vote: Vote) -> Result<Vec<BftOutput>, String> {
+        if vote.height != self.height {
+            return Ok(Vec::new());
+        }
+        vote.verify(self.chain_id, &self.validators)?;
+        let mut out = Vec::new();
+        // The first vote of a validator per round and kind counts
+        self.votes.entry((vote.round, vote.kind)).or_default().entry(vote.validator).or_insert(vote);
+        self.evaluate(&mut out);
+        Ok(out)
+    }
+
+    pub fn on_timeout(&mut self, timeout: Timeout) -> Vec<BftOutput> {
+        let mut out = Vec::new();
+        if timeout.height != self.height || timeout.round != self.round || self.decision.is_some() {
+            return out;
+        }
+        match timeout.step {
+            Step::Propose if self.step == Step::Propose => self.cast(VoteKind::Prevote, None, &mut out),
+            Step::Prevote if self.step == Step::Prevote => self.cast(VoteKind::Precommit, None, &mut out),
+            Step::Precommit => self.start_round(self.round + 1, &mut out),
+            _ => {}
+        }
+        self.evaluate(&mut out);
+        out
+    }
+
+    fn timeout(&self, step: Step) -> BftOutput {
+        let base = match step {
+            Step::Propose => TIMEOUT_PROPOSE,
+            Step::Prevote => TIMEOUT_PREVOTE,
+            Step::Precommit => TIMEOUT_PRECOMMIT,
+        };
+        BftOutput::Timeout(Timeout { height: self.height, round: self.round, step, after: base + TIMEOUT_DELTA * self.round })
+    }
+
+    fn start_round(&mut self, round: u32, out: &mut Vec<BftOutput>) {
+        self.round = round;
+        self.step = Step::Propose;
+        out.push(self.timeout(Step::Propose));
+        self.try_propose(out);
+    }
+
+    fn try_propose(&mut self, out: &mut Vec<BftOutput>) {
+        let (address, key) = match &self.signer {
+            Some(s) => s.clone(),
+            None => return,
+        };
+        if self.step != Step::Propose
+            || self.proposed.contains(&self.round)
+            || proposer_for(&self.validators, self.height, self.round) != Some(address)
+        {
+            return;
+        }
+        let (valid_round, block_hash) = match (self.valid, self.preferred) {
+            (Some((r, hash)), _) => (Some(r), hash),
+            (None, Some(hash)) => (None, hash),
+            (None, None) => return,
+        };
+        let proposal = Proposal::new_signed(self.chain_id, self.height, self.round, block_hash, valid_round, address, &key);
+        self.proposed.insert(self.round);
+        self.proposals.insert(self.round, proposal.clone());
+        out.push(BftOutput::Proposal(proposal));
+    }
+
+    /// Records our own vote, broadcasts it and moves to the matching step.
+    fn cast(&mut self, kind: VoteKind, block_hash: Option<[u8; 32]>, out: &mut Vec<BftOutput>) {
+        self.step = match kind {
+            VoteKind::Prevote => Step::Prevote,
+            VoteKind::Precommit => Step::Precommit,
+        };
+        if let Some((address, key)) = &self.signer {
+            let vote = Vote::new_signed(self.chain_id, kind, self.height, self.round, block_hash, *address, key);
+            self.votes.entry((self.round, kind)).or_default().insert(*address, vote.clone());
+            out.push(BftOutput::Vote(vote));
+        }
+    }
+
+    fn stake_of<'a>(&self, votes: impl Iterator<Item = &'a Vote>) -> u128 {
+        votes.filter_map(|v| self.validators.iter().find(|i| i.address == v.validator)).map(|i| i.stake).sum()
+    }
+
+    /// Stake behind votes of `kind` in `round`; `target: None` counts votes for anything.
+    fn tally(&self, round: u32, kind: VoteKind, target: Option<Option<[u8; 32]>>) -> u128 {
+        match self.votes.get(&(round, kind)) {
+            Some(votes) => self.stake_of(votes.values().filter(|v| target.is_none_or(|t| v.block_hash == t))),
+            None => 0,
+        }
+    }
+
+    fn is_quorum(&self, stake: u128) -> bool {
+        stake * 3 > self.total_stake * 2
+    }
+
+    fn evaluate(&mut self, out: &mut Vec<BftOutput>) {
+        while self.decision.is_none() && self.apply_rule(out) {}
+    }
+
+    /// Applies the first Tendermint rule whose condition holds; false if none did.
+    fn apply_rule(&mut self, out: &mut Vec<BftOutput>) -> bool {
+        let round = self.round;
+
+        // Decide: 2/3+ precommits for a block in any round. The commit is proof enough;
+        // if we do not have the block yet the node fetches it before finalizing.
+        let decided = self.votes.iter()
+            .filter(|((_, kind), _)| *kind == VoteKind::Precommit)
+            .flat_map(|((r, _), votes)| votes.values().filter_map(move |v| v.block_hash.map(|h| (*r, h))))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .find(|(r, hash)| self.is_quorum(self.tally(*r, VoteKind::Precommit, Some(Some(*hash)))));
+        if let Some((r, hash)) = decided {
+            let precommits = self.votes[&(r, VoteKind::Precommit)].values().filter(|v| v.block_hash == Some(hash));
+            let commit = match FinalityCommit::aggregate(hash, self.height, r, precommits, &self.validators) {
+                Ok(commit) => commit,
+                // Votes are verified on arrival, so they always aggregate
+                Err(_) => return false,
+            };
+            self.decision = Some(commit.clone());
+            out.push(BftOutput::Commit(commit));
+            return true;
+        }
+
+        // Skip ahead: more than 1/3 of the stake is already in a later round
+        let later = self.votes.keys().map(|(r, _)| *r).filter(|r| *r > round).collect::<HashSet<_>>().into_iter().max_by_key(|r| *r);
+        if let Some(later) = later {
+            let voters: HashSet<Address> = [VoteKind::Prevote, VoteKind::Precommit].iter()
+                .filter_map(|k| self.votes.get(&(later, *k)))
+                .flat_map(|votes| votes.keys().copied())
+                .collect();
+            let stake: u128 = self.validators.iter().filter(|v| voters.contains(&v.address)).map(|v| v.stake).sum();
+            if stake * 3 > self.total_stake {
+                self.start_round(later, out);
+                return true;
+            }
+        }
+
+        let proposal = self.proposals.get(&round).cloned();
+        if self.step == Step::Propose {
+            if let Some(p) = &proposal {
+                if self.candidates.contains(&p.block_hash) {
+                    let hash = p.block_hash;
+                    match p.valid_round {
+                        None => {
+                            let acceptable = self.locked.is_none_or(|(_, locked)| locked == hash);
+                            self.cast(VoteKind::Prevote, acceptable.then_some(hash), out);
+                            return true;
+                        }
+                        Some(vr) if vr < round && self.is_quorum(self.tally(vr, VoteKind::Prevote, Some(Some(hash)))) => {
+                            let acceptable = self.locked.is_none_or(|(locked_round, locked)| locked_round <= vr || locked == hash);
+                            self.cast(VoteKind::Prevote, acceptable.then_some(hash), out);
+                            return true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if self.step == Step::Prevote && !self.prevote_wait.contains(&round) && self.is_quorum(self.tally(round, VoteKind::Prevote, None)) {
+            self.prevote_wait.insert(round);
+            out.push(self.timeout(Step::Prevote));
+            return true;
+        }
+
+        if self.step >= Step::Prevote && !self.polka_seen.contains(&round) {
+            if let Some(p) = &proposal {
+                let hash = p.block_hash;
+                if self.candidates.contains(&hash) && self.is_quorum(self.tally(round, VoteKind::Prevote, Some(Some(hash)))) {
+                    self.polka_seen.insert(round);
+                    if self.step == Step::Prevote {
+                        self.locked = Some((round, hash));
+                        self.cast(VoteKind::Precommit, Some(hash), out);
+                    }
+                    self.valid = Some((round, hash));
+                    return true;
+                }
+            }
+        }
+
+        if self.step == Step::Prevote && self.is_quorum(self.tally(round, VoteKind::Prevote, Some(None))) {
+            self.cast(VoteKind::Precommit, None, out);
+            return true;
+        }
+
+        if !self.precommit_wait.contains(&round) && self.is_quorum(self.tally(round, VoteKind::Precommit, None)) {
+            self.precommit_wait.insert(round);
+            out.push(self.timeout(Step::Precommit));
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,217 @@
+// File: src/core/chain.rs
+//
+// Canonical chain maintenance. Incoming blocks are header-checked and added to the
+// block tree; `update_head` then moves the canonical head to the fork-choice tip,
+// importing blocks on the way and, when the tip lies on another branch, rewinding
+// storage and state to the common ancestor first (a reorg). `finalize_block` applies a
+// BFT commit: the committed block becomes canonical and final, whatever fork choice says.
+
+use std::collections::HashMap;
+use crate::consensus::fork_choice::{BlockTree, MAX_REORG_DEPTH};
+use crate::consensus::ConsensusEngine;
+use crate::core::fees::FeeMarket;
+use crate::core::importer::{BlockImporter, ImportError};
+use crate::state::account::State;
+use crate::storage::Storage;
+use crate::types::block::Block;
+
+/// A switch of the canonical chain to a competing branch.
+#[derive(Debug, Clone)]
+pub struct ChainReorg {
+    pub depth: u64,
+    pub common_ancestor_height: u64,
+    pub common_ancestor_hash: [u8; 32],
+    pub old_head: [u8; 32],
+    pub new_head: [u8; 32],
+    pub new_height: u64,
+    /// Blocks that left the canonical chain, lowest first.
+    pub removed: Vec<[u8; 32]>,
+    /// Blocks that joined the canonical chain, lowest first.
+    pub added: Vec<[u8; 32]>,
+}
+
+#[derive(Debug, Default)]
+pub struct HeadUpdate {
+    /// Newly canonical blocks, lowest first.
+    pub imported: Vec<Block>,
+    /// Blocks no longer canonical; their transactions go back to the mempool.
+    pub orphaned: Vec<Block>,
+    /// Blocks that failed execution and were dropped from the tree.
+    pub rejected: Vec<(Block, ImportError)>,
+    pub reorg: Option<ChainReorg>,
+}
+
+/// Header-checks `block` against its parent (canonical or in the tree) and adds it to
+/// the tree. Returns false if the block was already known.
+pub fn accept_block(tree: &mut BlockTree, storage: &Storage, consensus: &ConsensusEngine, block: &Block, now: u64) -> Result<bool, ImportError> {
+    let hash = block.header.hash();
+    if tree.contains(&hash) || tree.is_invalid(&hash) {
+        return Ok(false);
+    }
+    let (root_height, root_hash) = tree.root();
+    if block.header.height <= root_height {
+        return Err(ImportError::UnexpectedHeight { expected: root_height + 1, got: block.header.height });
+    }
+
+    let parent_hash = block.header.parent_hash;
+    let parent = if parent_hash == root_hash {
+        storage.get_header(root_height).map_err(ImportError::Storage)?
+    } else {
+        tree.get(&parent_hash).map(|b| b.header.clone())
+    };
+    let parent = parent.ok_or(ImportError::MissingParent(block.header.height))?;
+    let importer = BlockImporter::new(storage, consensus);
+    importer.check_header(block, &parent, now)?;
+    importer.check_poh(block, &parent)?;
+
+    tree.insert(block.clone()).map_err(ImportError::Execution)?;
+    Ok(true)
+}
+
+/// Moves the canonical head (`consensus.head_hash`) to the tree's fork-choice tip.
+/// Blocks that fail execution are marked invalid and fork choice is re-run, so the
+/// node always ends on the best valid branch.
+pub fn update_head(
+    tree: &mut BlockTree,
+    storage: &Storage,
+    consensus: &mut ConsensusEngine,
+    state: &mut State,
+    fees: &mut FeeMarket,
+    now: u64,
+) -> Result<HeadUpdate, ImportError> {
+    let mut update = HeadUpdate::default();
+    let original_head = consensus.head_hash;
+    let original_branch = tree.branch_to(&original_head);
+    let mut rewound: HashMap<[u8; 32], Block> = HashMap::new();
+
+    loop {
+        let tip = tree.best_tip();
+        if tip == consensus.head_hash {
+            break;
+        }
+        let new_branch = tree.branch_to(&tip);
+        let old_branch = tree.branch_to(&consensus.head_hash);
+        let shared = common_prefix(&new_branch, &old_branch);
+
+        if old_branch.len() > shared {
+            let (root_height, root_hash) = tree.root();
+            let ancestor_height = root_height + shared as u64;
+            let ancestor_hash = if shared == 0 { root_hash } else { new_branch[shared - 1] };
+            for block in storage.rewind_to(ancestor_height).map_err(ImportError::Storage)? {
+                rewound.insert(block.header.hash(), block);
+            }
+            *state = storage.get_state(ancestor_height)
+                .map_err(ImportError::Storage)?
+                .ok_or_else(|| ImportError::Storage(format!("No state stored at height {}", ancestor_height)))?;
+            consensus.head_hash = ancestor_hash;
+            // The ancestor may sit in an earlier epoch, with another schedule and jail list
+            consensus.sync_validators(&state.staking);
+        }
+
+        for hash in &new_branch[shared..] {
+            let block = match tree.get(hash) {
+                Some(b) => b.clone(),
+                None => break,
+            };
+            match BlockImporter::new(storage, consensus).import(&block, state, fees, now) {
+                Ok(_) => {
+                    consensus.head_hash = *hash;
+                    // The next block is checked against the schedule its parent left behind
+                    consensus.sync_validators(&state.staking);
+                }
+                Err(e) if e.is_invalid() => {
+                    tree.mark_invalid(hash);
+                    update.rejected.push((block, e));
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Summarise against where we started; a branch that was rewound and then restored
+    // (because the competitor turned out invalid) is not a reorg.
+    let final_branch = tree.branch_to(&consensus.head_hash);
+    let shared = common_prefix(&final_branch, &original_branch);
+    update.imported = final_branch[shared..].iter().filter_map(|h| tree.get(h).cloned()).collect();
+    update.orphaned = original_branch[shared..].iter()
+        .filter_map(|h| rewound.remove(h).or_else(|| tree.get(h).cloned()))
+        .collect();
+
+    if !update.orphaned.is_empty() {
+        let (root_height, root_hash) = tree.root();
+        update.reorg = Some(ChainReorg {
+            depth: update.orphaned.len() as u64,
+            common_ancestor_height: root_height + shared as u64,
+            common_ancestor_hash: if shared == 0 { root_hash } else { final_branch[shared - 1] },
+            old_head: original_head,
+            new_head: consensus.head_hash,
+            new_height: root_height + final_branch.len() as u64,
+            removed: original_branch[shared..].to_vec(),
+            added: final_branch[shared..].to_vec(),
+        });
+    }
+
+    prune(tree, storage, consensus)?;
+    Ok(update)
+}
+
+/// Makes the committed block `hash` at `height` canonical and final. Fails with
+/// `UnknownBlock` if the block is neither canonical nor in the tree yet; the caller keeps
+/// the commit and retries once the block arrives.
+#[allow(clippy::too_many_arguments)]
+pub fn finalize_block(
+    tree: &mut BlockTree,
+    storage: &Storage,
+    consensus: &mut ConsensusEngine,
+    state: &mut State,
+    fees: &mut FeeMarket,
+    hash: [u8; 32],
+    height: u64,
+    now: u64,
+) -> Result<HeadUpdate, ImportError> {
+    if height <= consensus.finalized_height {
+        return Ok(HeadUpdate::default());
+    }
+    let is_canonical = |storage: &Storage| -> Result<bool, ImportError> {
+        Ok(storage.get_header(height).map_err(ImportError::Storage)?.is_some_and(|h| h.hash() == hash))
+    };
+
+    let mut update = HeadUpdate::default();
+    if !is_canonical(storage)? {
+        if tree.get(&hash).is_none() {
+            return Err(ImportError::UnknownBlock(hash));
+        }
+        tree.require(hash).map_err(ImportError::Storage)?;
+        update = update_head(tree, storage, consensus, state, fees, now)?;
+        if !is_canonical(storage)? {
+            return Err(ImportError::Execution(format!("committed block 0x{} failed execution", hex::encode(hash))));
+        }
+    }
+    consensus.finalized_height = height;
+    consensus.finalized_hash = hash;
+    prune(tree, storage, consensus)?;
+    Ok(update)
+}
+
+/// Advances the tree root to the BFT-finalized block or to `MAX_REORG_DEPTH` below the
+/// head, whichever is higher.
+pub fn prune(tree: &mut BlockTree, storage: &Storage, consensus: &ConsensusEngine) -> Result<(), ImportError> {
+    let (root_height, _) = tree.root();
+    let head_height = match tree.height_of(&consensus.head_hash) {
+        Some(h) => h,
+        None => return Ok(()),
+    };
+    let target = consensus.finalized_height.max(head_height.saturating_sub(MAX_REORG_DEPTH)).min(head_height);
+    if target <= root_height {
+        return Ok(());
+    }
+    let header = storage.get_header(target)
+        .map_err(ImportError::Storage)?
+        .ok_or_else(|| ImportError::Storage(format!("Missing canonical header at height {}", target)))?;
+    tree.finalize(&header.hash()).map_err(ImportError::Storage)
+}
+
+fn common_prefix(a: &[[u8; 32]], b: &[[u8; 32]]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
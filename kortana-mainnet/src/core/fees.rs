@@ -24,7 +24,25 @@ impl FeeMarket {
         }
     }
 
-    pub fn update_base_fee(&mut self, gas_used: u64) {
+    /// Fee market at the floor of `params`, targeting half the block gas limit.
+    pub fn for_params(params: &crate::parameters::ChainParams) -> Self {
+        let mut market = Self::new();
+        market.base_fee = params.min_gas_price;
+        market.target_gas_per_block = params.block_gas_limit / market.elasticity_multiplier;
+        market
+    }
+
+    /// Fee market for the child of `parent`: the parent's base fee moved by the gas the
+    /// parent used. It depends only on the header chain and `params`, so every node
+    /// agrees on it after a restart, a rewind or a snapshot import.
+    pub fn after(parent: &crate::types::block::BlockHeader, params: &crate::parameters::ChainParams) -> Self {
+        let mut market = Self::for_params(params);
+        market.base_fee = parent.base_fee.max(params.min_gas_price);
+        market.update_base_fee(parent.gas_used, params);
+        market
+    }
+
+    pub fn update_base_fee(&mut self, gas_used: u64, params: &crate::parameters::ChainParams) {
         let target = self.target_gas_per_block;
         let diff = if gas_used > target {
             (gas_used - target) as i128
@@ -32,10 +50,14 @@ impl FeeMarket {
             -((target - gas_used) as i128)
         };
 
-        // EIP-1559 base fee adjustment: clamped to ±12.5% per block, floored at MIN_GAS_PRICE
-        let delta = (self.base_fee as i128 * diff) / (target as i128 * 8);
+        // EIP-1559 base fee adjustment: clamped to ±12.5% per block, floored at min_gas_price.
+        // A busy block raises it by at least 1 wei so it can leave a low floor.
+        let mut delta = (self.base_fee as i128 * diff) / (target as i128 * 8);
+        if diff > 0 {
+            delta = delta.max(1);
+        }
         self.base_fee = (self.base_fee as i128 + delta)
-            .max(crate::parameters::MIN_GAS_PRICE as i128) as u128;
+            .max(params.min_gas_price as i128) as u128;
     }
 
     pub fn calculate_priority_fee(&self, max_fee: u128, max_priority_fee: u128) -> u128 {
@@ -0,0 +1,54 @@
+// File: src/core/forks.rs
+//
+// Hard forks: protocol rules that switch on at a height. The rules themselves live in
+// the binary, in `BlockProcessor` and `EvmExecutor`, which ask the schedule whether a
+// fork is active at the block they execute. A fork is scheduled either at genesis or by
+// an upgrade plan: the release a plan names turns on its forks at the plan's height.
+
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Fork {
+    /// Intrinsic gas charges calldata: 4 per zero byte and 16 per other byte (EIP-2028).
+    CalldataGas,
+    /// MCOPY (EIP-5656) becomes a valid opcode.
+    Mcopy,
+}
+
+/// Forks each release turns on at the height of the upgrade plan that names it.
+pub const RELEASE_FORKS: &[(&str, &[Fork])] = &[
+    ("1.2.0", &[Fork::CalldataGas, Fork::Mcopy]),
+];
+
+/// Forks of a release; none for a release that only fixes bugs.
+pub fn release_forks(version: &str) -> &'static [Fork] {
+    RELEASE_FORKS.iter().find(|(v, _)| *v == version).map_or(&[], |(_, forks)| forks)
+}
+
+/// Activation height of every scheduled fork.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkSchedule {
+    pub activations: BTreeMap<Fork, u64>,
+}
+
+impl ForkSchedule {
+    pub fn is_active(&self, fork: Fork, height: u64) -> bool {
+        self.activations.get(&fork).is_some_and(|at| *at <= height)
+    }
+
+    /// Schedules `fork` at `height`. A fork already scheduled keeps its height.
+    pub fn activate(&mut self, fork: Fork, height: u64) {
+        self.activations.entry(fork).or_insert(height);
+    }
+
+    /// Gas a transaction pays before execution: the base cost plus, once
+    /// `CalldataGas` is active, its calldata.
+    pub fn intrinsic_gas(&self, base: u64, data: &[u8], height: u64) -> u64 {
+        if !self.is_active(Fork::CalldataGas, height) {
+            return base;
+        }
+        let zeros = data.iter().filter(|b| **b == 0).count() as u64;
+        base + zeros * 4 + (data.len() as u64 - zeros) * 16
+    }
+}
@@ -1,62 +1,74 @@
 // File: src/core/genesis.rs
 
 use crate::address::Address;
-use crate::state::account::{State, Account};
+use crate::state::account::State;
 use crate::types::block::{Block, BlockHeader};
-use crate::parameters::*;
+use crate::chain_spec::ChainSpec;
+use crate::staking::ValidatorProfile;
 
+/// Mainnet genesis state.
 pub fn create_genesis_state() -> State {
-    let mut state = State::new();
-
-    // -------------------------------------------------------
-    // Total Genesis Supply: 500,000,000,000 DNR (500 Billion)
-    // Circulating at launch: 10,000,000,000 DNR (10 Billion)
-    // Treasury (Foundation + Reserve): 490,000,000,000 DNR
-    // -------------------------------------------------------
-
-    // Foundation Reserve — 490B DNR
-    let foundation_addr = Address::from_pubkey(b"foundation");
-    let mut foundation_acc = Account::new();
-    foundation_acc.balance = 490_000_000_000_000_000_000_000_000_000_000; // 490B DNR
-    state.update_account(foundation_addr, foundation_acc);
+    create_genesis_state_for(&ChainSpec::mainnet()).expect("Mainnet chain spec is valid")
+}
 
-    // Initial Validators - Including your RackNerd Node
-    let validator_stakes = 1_000_000_000_000_000_000_000_000; // 1M DNR each
-    
-    let validator_addresses = vec![
-        Address::from_hex("0xadf256ef0fa3c0fe774f7dac0fd04040e4fdef50").unwrap(), // Your RackNerd Node
-        Address::from_pubkey(b"genesis_validator_2"),
-        Address::from_pubkey(b"genesis_validator_3"),
-    ];
+/// Genesis state of `spec`: its parameters and forks in force from the first block,
+/// its accounts funded and its validators bonded and elected.
+pub fn create_genesis_state_for(spec: &ChainSpec) -> Result<State, String> {
+    spec.validate()?;
+    let mut state = State::new();
+    state.params = spec.params.clone();
+    for (fork, height) in &spec.forks {
+        state.governance.forks.activate(*fork, *height);
+    }
 
-    for addr in validator_addresses {
-        let mut acc = Account::new();
-        acc.balance = validator_stakes;
+    for genesis in &spec.accounts {
+        let addr = Address::from_hex(&genesis.address)?;
+        let mut acc = state.get_account(&addr);
+        acc.balance += genesis.balance;
         state.update_account(addr, acc);
-        state.staking.delegate(addr, addr, validator_stakes, 0);
     }
 
-    // Ecosystem Faucet — 306.993B DNR
-    let faucet_addr = Address::from_hex("0xc19d6dece56d290c71930c2f867ae9c2c652a19f7911ef64").unwrap();
-    let mut faucet_acc = Account::new();
-    faucet_acc.balance = 306_993_000_000_000_000_000_000_000_000_000;
-    state.update_account(faucet_addr, faucet_acc);
-
-    // Owner Wallet — 7M DNR
-    let owner_addr = Address::from_hex("0x28e514Ce1a0554B83f6d5EEEE11B07D0e294D9F9").unwrap();
-    let mut owner_acc = Account::new();
-    owner_acc.balance = 7_000_000_000_000_000_000_000_000; // 7M DNR
-    state.update_account(owner_addr, owner_acc);
+    for validator in &spec.validators {
+        let addr = Address::from_hex(&validator.address)?;
+        let profile = ValidatorProfile::new(&validator.moniker, validator.commission_bps, 2000, 100);
+        let (key, pop) = match (&validator.consensus_key, &validator.consensus_pop) {
+            (Some(key), Some(pop)) => (
+                Some(hex::decode(key).map_err(|_| format!("Invalid consensus key of {}", validator.address))?),
+                hex::decode(pop).map_err(|_| format!("Invalid proof of possession of {}", validator.address))?,
+            ),
+            (None, None) => (None, Vec::new()),
+            _ => return Err(format!("Validator {} needs both a consensus key and its proof of possession", validator.address)),
+        };
+        state.staking.create_validator(addr, profile, key.map(|k| (k, pop.as_slice())), validator.stake, 0, &state.params)
+            .map_err(|e| format!("Genesis validator {}: {}", validator.address, e))?;
+    }
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
 
-    state
+    let balances: u128 = state.accounts.values().map(|a| a.balance).sum();
+    let bonded: u128 = state.staking.delegations.values().flat_map(|ds| ds.values()).map(|d| d.amount).sum();
+    state.staking.total_supply = balances + bonded;
+    state.commit_staking();
+    state.commit_governance();
+    Ok(state)
 }
 
+/// Mainnet genesis block over `state_root`.
 pub fn create_genesis_block(state_root: [u8; 32]) -> Block {
+    create_genesis_block_for(&ChainSpec::mainnet(), state_root)
+}
+
+/// Hash of the genesis block of `spec`, which identifies its network to peers.
+pub fn genesis_hash(spec: &ChainSpec) -> Result<[u8; 32], String> {
+    let root = create_genesis_state_for(spec)?.calculate_root();
+    Ok(create_genesis_block_for(spec, root).header.hash())
+}
+
+pub fn create_genesis_block_for(spec: &ChainSpec, state_root: [u8; 32]) -> Block {
     let header = BlockHeader {
         version: 1,
         height: 0,
         slot: 0,
-        timestamp: 1740268800, // Feb 23 2026 
+        timestamp: spec.genesis_timestamp,
         parent_hash: [0u8; 32],
         state_root,
         transactions_root: [0u8; 32],
@@ -65,14 +77,21 @@ pub fn create_genesis_block(state_root: [u8; 32]) -> Block {
         poh_sequence: 0,
         proposer: Address::ZERO,
         gas_used: 0,
-        gas_limit: GAS_LIMIT_PER_BLOCK,
-        base_fee: MIN_GAS_PRICE, // 1 wei — Kortana minimum gas price
+        gas_limit: spec.params.block_gas_limit,
+        base_fee: spec.params.min_gas_price,
         vrf_output: [0u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
     };
 
     Block {
         header,
         transactions: vec![],
         signature: vec![],
+        evidence: vec![],
+        last_commit: None,
+        poh_entries: vec![],
     }
 }
@@ -1,12 +1,95 @@
 // File: src/core/governance.rs
+//
+// On-chain governance. Anyone can submit a proposal with a deposit; bonded stake votes
+// on it, a delegator that does not vote following its validator, and the proposal is
+// tallied in the `end_block` of its last voting block. A passed `ParameterChange`
+// takes effect at the next epoch boundary, so a validator set is never elected under
+// one set of parameters and run under another. A passed `SoftwareUpgrade` becomes the
+// upgrade plan: at its height, nodes that do not run the named release halt, and the
+// release's forks switch on.
 
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use sha3::{Digest, Keccak256};
 use crate::address::Address;
+use crate::core::forks::{release_forks, ForkSchedule};
+use crate::parameters::*;
+use crate::staking::StakingStore;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Storage slot of the governance system account that holds `GovernanceStore::root`.
+pub const GOVERNANCE_ROOT_SLOT: [u8; 32] = [0u8; 32];
+/// Storage slot of the governance system account that holds `ChainParams::root`.
+pub const PARAMS_ROOT_SLOT: [u8; 32] = [1u8; 32];
+
+/// The account at `GOVERNANCE_CONTRACT_ADDRESS`; governance transactions are sent to it.
+pub fn governance_address() -> Address {
+    Address::from_hex(GOVERNANCE_CONTRACT_ADDRESS).expect("Governance contract address is valid hex")
+}
+
+/// True if a node at release `node_version` can follow a plan naming `required`: the
+/// same release, or a later `MAJOR.MINOR.PATCH` one.
+fn runs_release(node_version: &str, required: &str) -> bool {
+    fn number(version: &str) -> Option<(u64, u64, u64)> {
+        let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+        let number = (parts.next()??, parts.next()??, parts.next()??);
+        parts.next().is_none().then_some(number)
+    }
+    node_version == required || matches!((number(node_version), number(required)), (Some(node), Some(req)) if node >= req)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProposalType {
     ParameterChange { key: String, value: String },
-    SoftwareUpgrade { version: String, hash: [u8; 32] },
+    /// Switch to release `version`, whose binary hashes to `hash`, at `height`.
+    SoftwareUpgrade { version: String, hash: [u8; 32], height: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+    NoWithVeto,
+}
+
+impl VoteOption {
+    pub fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            0 => Some(VoteOption::Yes),
+            1 => Some(VoteOption::No),
+            2 => Some(VoteOption::Abstain),
+            3 => Some(VoteOption::NoWithVeto),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProposalStatus {
+    Voting,
+    Passed,
+    Rejected,
+    /// Too little stake voted; the deposit is burned.
+    NoQuorum,
+    /// Too much of the vote was NoWithVeto; the deposit is burned.
+    Vetoed,
+    /// Passed, but could not be executed.
+    Failed(String),
+}
+
+/// Bonded stake behind each option.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    pub yes: u128,
+    pub no: u128,
+    pub abstain: u128,
+    pub veto: u128,
+}
+
+impl Tally {
+    pub fn total(&self) -> u128 {
+        self.yes + self.no + self.abstain + self.veto
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,55 +97,232 @@ pub struct Proposal {
     pub id: u64,
     pub proposer: Address,
     pub p_type: ProposalType,
-    pub votes_for: u128,
-    pub votes_against: u128,
+    pub deposit: u128,
+    pub submit_block: u64,
+    /// Last block votes are accepted in; the proposal is tallied at its end.
     pub end_block: u64,
-    pub executed: bool,
+    pub votes: BTreeMap<Address, VoteOption>,
+    pub status: ProposalStatus,
+    pub tally: Tally,
+}
+
+/// Release every node must run from `height` on; see `GovernanceStore::begin_block`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradePlan {
+    pub proposal_id: u64,
+    pub version: String,
+    /// Hash of the release binary, so operators can check what they install.
+    pub hash: [u8; 32],
+    pub height: u64,
+}
+
+/// What `GovernanceStore::end_block` settled: deposits to refund and the amount burned.
+#[derive(Debug, Default)]
+pub struct TallyOutcome {
+    pub refunds: Vec<(Address, u128)>,
+    pub burned: u128,
+    pub passed: Vec<u64>,
 }
 
-pub struct GovernanceModule {
-    pub proposals: Vec<Proposal>,
+/// Governance state of the chain, part of `State` and committed to its root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceStore {
+    pub proposals: BTreeMap<u64, Proposal>,
     pub next_id: u64,
+    /// Passed parameter changes by the epoch-boundary height they take effect at.
+    #[serde(default)]
+    pub scheduled: BTreeMap<u64, Vec<u64>>,
+    /// The pending upgrade, if any. A later plan replaces it.
+    #[serde(default)]
+    pub upgrade_plan: Option<UpgradePlan>,
+    /// Upgrades carried out, by height.
+    #[serde(default)]
+    pub upgrades: BTreeMap<u64, UpgradePlan>,
+    #[serde(default)]
+    pub forks: ForkSchedule,
 }
 
-impl Default for GovernanceModule {
+impl Default for GovernanceStore {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl GovernanceModule {
+impl GovernanceStore {
     pub fn new() -> Self {
         Self {
-            proposals: Vec::new(),
+            proposals: BTreeMap::new(),
             next_id: 1,
+            scheduled: BTreeMap::new(),
+            upgrade_plan: None,
+            upgrades: BTreeMap::new(),
+            forks: ForkSchedule::default(),
         }
     }
 
-    pub fn submit_proposal(&mut self, proposer: Address, p_type: ProposalType, current_height: u64) -> u64 {
+    /// Opens voting on `p_type` until `gov_voting_period_blocks` after `height`. The
+    /// caller takes `deposit` from the proposer, at least `gov_min_deposit`.
+    pub fn submit_proposal(&mut self, proposer: Address, p_type: ProposalType, deposit: u128, height: u64, params: &ChainParams) -> Result<u64, String> {
+        if deposit < params.gov_min_deposit {
+            return Err("Deposit is below the minimum".to_string());
+        }
+        let end_block = height + params.gov_voting_period_blocks;
+        match &p_type {
+            // Refuse what could never execute
+            ProposalType::ParameterChange { key, value } => params.clone().set(key, value)?,
+            ProposalType::SoftwareUpgrade { version, height: at, .. } => {
+                if version.is_empty() {
+                    return Err("Upgrade names no version".to_string());
+                }
+                if *at <= end_block {
+                    return Err("Upgrade height must come after the voting period".to_string());
+                }
+            }
+        }
         let id = self.next_id;
         self.next_id += 1;
-        self.proposals.push(Proposal {
+        self.proposals.insert(id, Proposal {
             id,
             proposer,
             p_type,
-            votes_for: 0,
-            votes_against: 0,
-            end_block: current_height + 1000, // 1000 blocks voting period
-            executed: false,
+            deposit,
+            submit_block: height,
+            end_block,
+            votes: BTreeMap::new(),
+            status: ProposalStatus::Voting,
+            tally: Tally::default(),
         });
-        id
+        Ok(id)
     }
 
-    pub fn vote(&mut self, proposal_id: u64, weight: u128, in_favor: bool) -> Result<(), String> {
-        let proposal = self.proposals.iter_mut().find(|p| p.id == proposal_id)
-            .ok_or("Proposal not found")?;
-        
-        if in_favor {
-            proposal.votes_for += weight;
-        } else {
-            proposal.votes_against += weight;
+    /// Records (or replaces) the vote of `voter`, who must have stake bonded.
+    pub fn vote(&mut self, proposal_id: u64, voter: Address, option: VoteOption, staking: &StakingStore, height: u64) -> Result<(), String> {
+        let proposal = self.proposals.get_mut(&proposal_id).ok_or("Proposal not found")?;
+        if proposal.status != ProposalStatus::Voting || height > proposal.end_block {
+            return Err("Voting on this proposal has ended".to_string());
+        }
+        if !staking.delegations.values().any(|ds| ds.contains_key(&voter)) {
+            return Err("Only bonded stake can vote".to_string());
         }
+        proposal.votes.insert(voter, option);
         Ok(())
     }
+
+    /// Counts the votes on `proposal` by bonded stake: each delegation votes as its
+    /// delegator did or, if it did not vote, as its validator did.
+    pub fn tally(proposal: &Proposal, staking: &StakingStore) -> Tally {
+        let mut tally = Tally::default();
+        for (validator, ds) in &staking.delegations {
+            let inherited = proposal.votes.get(validator);
+            for (delegator, d) in ds {
+                match proposal.votes.get(delegator).or(inherited) {
+                    Some(VoteOption::Yes) => tally.yes += d.amount,
+                    Some(VoteOption::No) => tally.no += d.amount,
+                    Some(VoteOption::Abstain) => tally.abstain += d.amount,
+                    Some(VoteOption::NoWithVeto) => tally.veto += d.amount,
+                    None => {}
+                }
+            }
+        }
+        tally
+    }
+
+    /// Tallies the proposals whose voting ends at `height`. A proposal needs votes from
+    /// `gov_quorum_bps` of all bonded stake, less than `gov_veto_bps` of the votes as
+    /// NoWithVeto and more than `gov_threshold_bps` of the non-abstaining votes as Yes.
+    /// Deposits come back unless quorum is missed or the proposal is vetoed. Passed
+    /// parameter changes are scheduled for the next epoch boundary; a passed upgrade
+    /// becomes the upgrade plan unless its height has been reached.
+    pub fn end_block(&mut self, height: u64, staking: &StakingStore, params: &ChainParams) -> TallyOutcome {
+        let mut outcome = TallyOutcome::default();
+        let bonded: u128 = staking.delegations.values().flat_map(|ds| ds.values()).map(|d| d.amount).sum();
+        let (quorum, threshold, veto) = (params.gov_quorum_bps as u128, params.gov_threshold_bps as u128, params.gov_veto_bps as u128);
+        let ending: Vec<u64> = self.proposals.values()
+            .filter(|p| p.status == ProposalStatus::Voting && p.end_block <= height)
+            .map(|p| p.id)
+            .collect();
+
+        for id in ending {
+            let proposal = self.proposals.get_mut(&id).unwrap();
+            let tally = Self::tally(proposal, staking);
+            let voted = tally.total();
+            proposal.status = if voted * 10000 < quorum * bonded || voted == 0 {
+                ProposalStatus::NoQuorum
+            } else if tally.veto * 10000 > veto * voted {
+                ProposalStatus::Vetoed
+            } else if tally.yes * 10000 > threshold * (voted - tally.abstain) {
+                ProposalStatus::Passed
+            } else {
+                ProposalStatus::Rejected
+            };
+            proposal.tally = tally;
+            match proposal.status {
+                ProposalStatus::NoQuorum | ProposalStatus::Vetoed => outcome.burned += proposal.deposit,
+                _ => outcome.refunds.push((proposal.proposer, proposal.deposit)),
+            }
+            if proposal.status == ProposalStatus::Passed {
+                outcome.passed.push(id);
+                match &proposal.p_type {
+                    ProposalType::ParameterChange { .. } => {
+                        let boundary = (height / BLOCKS_PER_EPOCH + 1) * BLOCKS_PER_EPOCH;
+                        self.scheduled.entry(boundary).or_default().push(id);
+                    }
+                    ProposalType::SoftwareUpgrade { height: at, .. } if *at <= height => {
+                        proposal.status = ProposalStatus::Failed("Upgrade height has passed".to_string());
+                    }
+                    ProposalType::SoftwareUpgrade { version, hash, height: at } => {
+                        self.upgrade_plan = Some(UpgradePlan { proposal_id: id, version: version.clone(), hash: *hash, height: *at });
+                    }
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Applies the parameter changes scheduled for `height` to `params`, in the order
+    /// they passed. A change that no longer validates against the parameters then in
+    /// force marks its proposal `Failed` and the others still apply. Returns the ids applied.
+    pub fn activate(&mut self, height: u64, params: &mut ChainParams) -> Vec<u64> {
+        let mut applied = Vec::new();
+        for id in self.scheduled.remove(&height).unwrap_or_default() {
+            let proposal = self.proposals.get_mut(&id).expect("Scheduled proposal exists");
+            if let ProposalType::ParameterChange { key, value } = &proposal.p_type {
+                match params.set(key, value) {
+                    Ok(()) => applied.push(id),
+                    Err(e) => proposal.status = ProposalStatus::Failed(e),
+                }
+            }
+        }
+        applied
+    }
+
+    /// The upgrade plan, if it is due at `height` and names a release newer than
+    /// `node_version`: the node cannot execute the block and must halt. Later releases
+    /// carry every earlier release's rules, so they run through older plans.
+    pub fn upgrade_needed(&self, height: u64, node_version: &str) -> Option<&UpgradePlan> {
+        self.upgrade_plan.as_ref().filter(|plan| plan.height <= height && !runs_release(node_version, &plan.version))
+    }
+
+    /// Carries out the upgrade plan if it is due at `height`, before the block's
+    /// transactions: the release's forks switch on from `height`. Errors, leaving the
+    /// plan in place, if this binary is older than the release the plan names.
+    pub fn begin_block(&mut self, height: u64, node_version: &str) -> Result<Option<UpgradePlan>, String> {
+        if let Some(plan) = self.upgrade_needed(height, node_version) {
+            return Err(format!("UPGRADE NEEDED: release {} is required from height {}, this node runs {}", plan.version, plan.height, node_version));
+        }
+        let plan = match self.upgrade_plan.take_if(|plan| plan.height <= height) {
+            Some(plan) => plan,
+            None => return Ok(None),
+        };
+        for fork in release_forks(&plan.version) {
+            self.forks.activate(*fork, height);
+        }
+        self.upgrades.insert(height, plan.clone());
+        Ok(Some(plan))
+    }
+
+    /// Hash the state root commits to, through `GOVERNANCE_ROOT_SLOT`.
+    pub fn root(&self) -> [u8; 32] {
+        Keccak256::digest(bincode::serialize(self).expect("Governance store serializes")).into()
+    }
 }
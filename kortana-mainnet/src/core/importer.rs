@@ -0,0 +1,238 @@
+// File: src/core/importer.rs
+//
+// Import pipeline for blocks received from peers. A block is only applied after every
+// header and body rule has been checked; execution happens against a snapshot of the
+// state that is rolled back on any failure, and the block, receipts and post-state are
+// committed atomically once everything agrees with the header.
+
+use thiserror::Error;
+use crate::consensus::ConsensusEngine;
+use crate::core::fees::FeeMarket;
+use crate::core::processor::BlockProcessor;
+use crate::consensus::PohGenerator;
+use crate::parameters::{NODE_VERSION, POH_HASHES_PER_TICK, POH_TICKS_PER_SLOT};
+use crate::state::account::State;
+use crate::storage::Storage;
+use crate::types::block::{Block, BlockHeader, HEADER_VERSION};
+use crate::types::transaction::TransactionReceipt;
+
+/// How far a block timestamp may run ahead of the local clock.
+pub const MAX_FUTURE_DRIFT_SECS: u64 = 15;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("unexpected height {got}, expected {expected}")]
+    UnexpectedHeight { expected: u64, got: u64 },
+    #[error("header version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("parent header for height {0} not found")]
+    MissingParent(u64),
+    #[error("parent hash 0x{} does not match local head 0x{}", hex::encode(.got), hex::encode(.expected))]
+    ParentMismatch { expected: [u8; 32], got: [u8; 32] },
+    #[error("invalid or missing proposer signature")]
+    BadSignature,
+    #[error("block signed by {signer} but header names proposer {proposer}")]
+    SignerMismatch { signer: String, proposer: String },
+    #[error("proposer {proposer} is not the leader for slot {slot}")]
+    WrongLeader { proposer: String, slot: u64 },
+    #[error("invalid VRF proof from {proposer}")]
+    BadVrfProof { proposer: String },
+    #[error("PoH advances {hashes} hashes, at least {required} required for the slots elapsed")]
+    PohTooShort { hashes: u64, required: u64 },
+    #[error("invalid PoH segment: {0}")]
+    BadPoh(String),
+    #[error("slot {slot} does not advance past parent slot {parent_slot}")]
+    SlotNotIncreasing { slot: u64, parent_slot: u64 },
+    #[error("timestamp {timestamp} is before parent timestamp {parent_timestamp}")]
+    TimestampBeforeParent { timestamp: u64, parent_timestamp: u64 },
+    #[error("timestamp {timestamp} is more than {MAX_FUTURE_DRIFT_SECS}s ahead of local time {now}")]
+    TimestampInFuture { timestamp: u64, now: u64 },
+    #[error("gas limit {got} differs from protocol limit {expected}")]
+    BadGasLimit { expected: u64, got: u64 },
+    #[error("gas used {used} exceeds gas limit {limit}")]
+    GasLimitExceeded { used: u64, limit: u64 },
+    #[error("header gas used {header} does not match executed gas {executed}")]
+    GasUsedMismatch { header: u64, executed: u64 },
+    #[error("receipts root mismatch")]
+    ReceiptsRootMismatch,
+    #[error("state root 0x{} does not match executed root 0x{}", hex::encode(.header), hex::encode(.executed))]
+    StateRootMismatch { header: [u8; 32], executed: [u8; 32] },
+    #[error("execution failed: {0}")]
+    Execution(String),
+    #[error("storage error: {0}")]
+    Storage(String),
+    #[error("block 0x{} is not known locally", hex::encode(.0))]
+    UnknownBlock([u8; 32]),
+    #[error("upgrade needed: release {version} is required from height {height}")]
+    UpgradeNeeded { version: String, height: u64 },
+}
+
+impl ImportError {
+    /// True if the block itself is invalid, as opposed to not being importable right now
+    /// (wrong height, unknown parent, local storage trouble).
+    pub fn is_invalid(&self) -> bool {
+        !matches!(
+            self,
+            ImportError::UnexpectedHeight { .. } | ImportError::MissingParent(_) | ImportError::Storage(_) | ImportError::UnknownBlock(_)
+                | ImportError::UpgradeNeeded { .. }
+        )
+    }
+
+    /// Reputation penalty for the peer that relayed the block.
+    pub fn peer_penalty(&self) -> i32 {
+        match self {
+            ImportError::UnexpectedHeight { .. } | ImportError::MissingParent(_) | ImportError::Storage(_) | ImportError::UnknownBlock(_)
+                | ImportError::UpgradeNeeded { .. } => 0,
+            ImportError::TimestampInFuture { .. } | ImportError::ParentMismatch { .. } => 5,
+            ImportError::BadSignature | ImportError::SignerMismatch { .. } | ImportError::WrongLeader { .. } | ImportError::BadVrfProof { .. } => 50,
+            _ => 20,
+        }
+    }
+}
+
+/// Number of tick hashes the PoH stream must spend on `slots` slots.
+fn poh_hashes_for(slots: u64) -> u64 {
+    slots.saturating_mul(POH_TICKS_PER_SLOT * POH_HASHES_PER_TICK)
+}
+
+pub struct BlockImporter<'a> {
+    storage: &'a Storage,
+    consensus: &'a ConsensusEngine,
+}
+
+impl<'a> BlockImporter<'a> {
+    pub fn new(storage: &'a Storage, consensus: &'a ConsensusEngine) -> Self {
+        Self { storage, consensus }
+    }
+
+    /// Stateless and parent-relative header checks; cheap enough to run before execution.
+    pub fn check_header(&self, block: &Block, parent: &BlockHeader, now: u64) -> Result<(), ImportError> {
+        let header = &block.header;
+        if header.version != HEADER_VERSION {
+            return Err(ImportError::UnsupportedVersion(header.version));
+        }
+        if header.height != parent.height + 1 {
+            return Err(ImportError::UnexpectedHeight { expected: parent.height + 1, got: header.height });
+        }
+        if header.parent_hash != parent.hash() {
+            return Err(ImportError::ParentMismatch { expected: parent.hash(), got: header.parent_hash });
+        }
+
+        let signer_key = crate::crypto::recover_pubkey(&header.hash(), &block.signature).ok_or(ImportError::BadSignature)?;
+        let signer = crate::address::Address::from_pubkey(&signer_key);
+        if signer != header.proposer {
+            return Err(ImportError::SignerMismatch { signer: signer.to_hex(), proposer: header.proposer.to_hex() });
+        }
+        if self.consensus.get_leader(header.slot) != Some(header.proposer) {
+            return Err(ImportError::WrongLeader { proposer: header.proposer.to_hex(), slot: header.slot });
+        }
+        if !header.verify_vrf(&signer_key, &parent.vrf_output) {
+            return Err(ImportError::BadVrfProof { proposer: header.proposer.to_hex() });
+        }
+
+        if header.slot <= parent.slot {
+            return Err(ImportError::SlotNotIncreasing { slot: header.slot, parent_slot: parent.slot });
+        }
+        let required = poh_hashes_for(header.slot - parent.slot);
+        let hashes = header.poh_sequence.saturating_sub(parent.poh_sequence);
+        if hashes < required {
+            return Err(ImportError::PohTooShort { hashes, required });
+        }
+        if header.timestamp < parent.timestamp {
+            return Err(ImportError::TimestampBeforeParent { timestamp: header.timestamp, parent_timestamp: parent.timestamp });
+        }
+        if header.timestamp > now + MAX_FUTURE_DRIFT_SECS {
+            return Err(ImportError::TimestampInFuture { timestamp: header.timestamp, now });
+        }
+
+        if header.gas_used > header.gas_limit {
+            return Err(ImportError::GasLimitExceeded { used: header.gas_used, limit: header.gas_limit });
+        }
+        Ok(())
+    }
+
+    /// Replays the block's PoH segment from the parent's tail. The segment must end at the
+    /// header's PoH hash, mix in exactly the block's transactions in order, and spend
+    /// exactly the protocol's hash count on the slots between parent and block.
+    pub fn check_poh(&self, block: &Block, parent: &BlockHeader) -> Result<(), ImportError> {
+        let header = &block.header;
+        let tail = block.poh_entries.last().map(|e| (e.hash, e.sequence)).unwrap_or((parent.poh_hash, parent.poh_sequence));
+        if tail != (header.poh_hash, header.poh_sequence) {
+            return Err(ImportError::BadPoh("segment does not end at the header's PoH hash".to_string()));
+        }
+        let mixins: Vec<[u8; 32]> = block.poh_entries.iter().filter_map(|e| e.mixin).collect();
+        let tx_hashes: Vec<[u8; 32]> = block.transactions.iter().map(|tx| tx.hash()).collect();
+        if mixins != tx_hashes {
+            return Err(ImportError::BadPoh("mixed-in transactions do not match the block body".to_string()));
+        }
+        let ticks = header.poh_sequence.saturating_sub(parent.poh_sequence).saturating_sub(mixins.len() as u64);
+        let required = poh_hashes_for(header.slot.saturating_sub(parent.slot));
+        if ticks != required {
+            return Err(ImportError::BadPoh(format!("{} tick hashes, expected {}", ticks, required)));
+        }
+        if !PohGenerator::verify(parent.poh_hash, parent.poh_sequence, &block.poh_entries) {
+            return Err(ImportError::BadPoh("segment does not replay from the parent".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Fully validates `block` on top of the local head, executes it against `state` and
+    /// commits it. The expected base fee is derived from the parent header; on success
+    /// `fees` becomes the market for the next block. On any error `state` and `fees` are
+    /// left untouched.
+    pub fn import(&self, block: &Block, state: &mut State, fees: &mut FeeMarket, now: u64) -> Result<Vec<TransactionReceipt>, ImportError> {
+        let height = block.header.height;
+        if height == 0 {
+            return Err(ImportError::UnexpectedHeight { expected: 1, got: 0 });
+        }
+        let parent = self.storage.get_header(height - 1)
+            .map_err(ImportError::Storage)?
+            .ok_or(ImportError::MissingParent(height))?;
+        self.check_header(block, &parent, now)?;
+        self.check_poh(block, &parent)?;
+
+        let snapshot = state.snapshot();
+        let result = self.execute(block, &parent, state).and_then(|receipts| {
+            self.storage.commit_block(block, &receipts, state).map_err(ImportError::Storage)?;
+            Ok(receipts)
+        });
+        match result {
+            Ok(receipts) => {
+                *fees = FeeMarket::after(&block.header, &state.params);
+                Ok(receipts)
+            }
+            Err(e) => {
+                state.rollback(snapshot);
+                Err(e)
+            }
+        }
+    }
+
+    fn execute(&self, block: &Block, parent: &BlockHeader, state: &mut State) -> Result<Vec<TransactionReceipt>, ImportError> {
+        // This binary cannot execute blocks past an upgrade to a newer release
+        if let Some(plan) = state.governance.upgrade_needed(block.header.height, NODE_VERSION) {
+            return Err(ImportError::UpgradeNeeded { version: plan.version.clone(), height: plan.height });
+        }
+        // The gas limit is a chain parameter, so it is checked against the parent state
+        if block.header.gas_limit != state.params.block_gas_limit {
+            return Err(ImportError::BadGasLimit { expected: state.params.block_gas_limit, got: block.header.gas_limit });
+        }
+        let fees = FeeMarket::after(parent, &state.params);
+        let mut processor = BlockProcessor::new(state, fees);
+        let receipts = processor.validate_block(block).map_err(ImportError::Execution)?;
+
+        let executed_gas: u64 = receipts.iter().map(|r| r.gas_used).sum();
+        if executed_gas != block.header.gas_used {
+            return Err(ImportError::GasUsedMismatch { header: block.header.gas_used, executed: executed_gas });
+        }
+        let (_, receipts_root) = Block::calculate_merkle_roots(&block.transactions, &receipts);
+        if receipts_root != block.header.receipts_root {
+            return Err(ImportError::ReceiptsRootMismatch);
+        }
+        let executed_root = state.calculate_root();
+        if executed_root != block.header.state_root {
+            return Err(ImportError::StateRootMismatch { header: block.header.state_root, executed: executed_root });
+        }
+        Ok(receipts)
+    }
+}
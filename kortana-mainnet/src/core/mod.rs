@@ -1,5 +1,8 @@
 // File: src/core/mod.rs
 pub mod fees;
 pub mod processor;
+pub mod importer;
+pub mod chain;
 pub mod governance;
+pub mod forks;
 pub mod genesis;
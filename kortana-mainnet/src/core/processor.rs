@@ -1,17 +1,24 @@
 // File: src/core/processor.rs
 
 use crate::state::account::State;
+use crate::types::bloom::Bloom;
 use crate::types::transaction::{Transaction, TransactionReceipt};
 use crate::vm::evm::EvmExecutor;
 use crate::address::Address;
 use crate::parameters::*;
+use crate::crypto::bls::{PUBLIC_KEY_LEN, SIGNATURE_LEN};
+use crate::staking::ValidatorProfile;
+use crate::core::forks::Fork;
+use crate::core::governance::{ProposalType, VoteOption};
 
 pub struct BlockProcessor<'a> {
     pub state: &'a mut State,
     pub fee_market: crate::core::fees::FeeMarket,
 }
 
-use crate::types::block::Block;
+use crate::types::block::{Block, HEADER_VERSION};
+use crate::consensus::bft::FinalityCommit;
+use crate::consensus::evidence::Evidence;
 
 impl<'a> BlockProcessor<'a> {
     pub fn new(state: &'a mut State, fee_market: crate::core::fees::FeeMarket) -> Self {
@@ -26,17 +33,13 @@ impl<'a> BlockProcessor<'a> {
         let _ = std::io::stdout().flush();
 
         // 0. Chain ID validation (EIP-155)
-        if tx.chain_id != CHAIN_ID {
-             println!("[PROCESSOR ERROR] Invalid Chain ID. Expected: {}, Got: {}", CHAIN_ID, tx.chain_id);
-            return Err(format!("Invalid chain ID: expected {}, got {}", CHAIN_ID, tx.chain_id));
+        let chain_id = self.state.params.chain_id;
+        if tx.chain_id != chain_id {
+             println!("[PROCESSOR ERROR] Invalid Chain ID. Expected: {}, Got: {}", chain_id, tx.chain_id);
+            return Err(format!("Invalid chain ID: expected {}, got {}", chain_id, tx.chain_id));
         }
-
-        // Process matured unbonding at the start of each tx or block
-        let matured = self.state.staking.process_matured_unbonding(header.height);
-        for (recipient_addr, amount) in matured {
-            let mut acc = self.state.get_account(&recipient_addr);
-            acc.balance += amount;
-            self.state.update_account(recipient_addr, acc);
+        if tx.gas_limit > self.state.params.max_tx_gas {
+            return Err(format!("Gas limit too high: {} > {}", tx.gas_limit, self.state.params.max_tx_gas));
         }
 
         // 1. Basic validation
@@ -64,10 +67,12 @@ impl<'a> BlockProcessor<'a> {
         let mut logs = Vec::new();
         
         let is_staking = tx.to.to_hex() == STAKING_CONTRACT_ADDRESS;
+        let is_governance = tx.to.to_hex() == GOVERNANCE_CONTRACT_ADDRESS;
         let is_deployment = tx.to.is_evm_zero();
          
         // Calculate intrinsic gas
-        let intrinsic_gas = if is_deployment { 53000 } else { 21000 };
+        let base_gas = if is_deployment { 53000 } else { 21000 };
+        let intrinsic_gas = self.state.governance.forks.intrinsic_gas(base_gas, &tx.data, header.height);
         if tx.gas_limit < intrinsic_gas {
             return Err(format!("Gas limit too low: {} < {}", tx.gas_limit, intrinsic_gas));
         }
@@ -84,7 +89,9 @@ impl<'a> BlockProcessor<'a> {
                             val_bytes.copy_from_slice(&tx.data[1..25]);
                             if let Ok(validator_addr) = Address::from_bytes(val_bytes) {
                                 // Transfer value to stake
-                                if tx.value > 0 {
+                                if !self.state.staking.is_registered(&validator_addr) {
+                                    (0, 50000, None)
+                                } else if tx.value > 0 {
                                     let mut s = self.state.get_account(&tx.from);
                                     if s.balance >= tx.value {
                                         s.balance -= tx.value;
@@ -102,16 +109,162 @@ impl<'a> BlockProcessor<'a> {
                             val_bytes.copy_from_slice(&tx.data[1..25]);
                             let amount = tx.value;
                             if let Ok(validator_addr) = Address::from_bytes(val_bytes) {
-                                match self.state.staking.undelegate(tx.from, validator_addr, amount, header.height) {
+                                match self.state.staking.undelegate(tx.from, validator_addr, amount, header.height, &self.state.params) {
                                     Ok(_) => (1, 50000, None),
                                     Err(_) => (0, 50000, None),
                                 }
                             } else { (0, 21000, None) }
                          } else { (0, 21000, None) }
                     }
+                    3 => { // Register consensus key (48-byte BLS public key + 96-byte proof of possession)
+                        if tx.data.len() == 1 + PUBLIC_KEY_LEN + SIGNATURE_LEN {
+                            let (pubkey, proof) = tx.data[1..].split_at(PUBLIC_KEY_LEN);
+                            match self.state.staking.register_consensus_key(tx.from, pubkey.to_vec(), proof) {
+                                Ok(_) => (1, 50000, None),
+                                Err(_) => (0, 50000, None),
+                            }
+                        } else { (0, 21000, None) }
+                    }
+                    4 => { // Unjail (sender is the jailed validator)
+                        match self.state.staking.unjail(tx.from, header.slot, &self.state.params) {
+                            Ok(_) => (1, 50000, None),
+                            Err(_) => (0, 50000, None),
+                        }
+                    }
+                    5 => { // Withdraw rewards (and the commission, if the sender operates the validator)
+                        if tx.data.len() >= 25 {
+                            let mut val_bytes = [0u8; 24];
+                            val_bytes.copy_from_slice(&tx.data[1..25]);
+                            if let Ok(validator_addr) = Address::from_bytes(val_bytes) {
+                                match self.state.staking.withdraw_rewards(tx.from, validator_addr) {
+                                    Ok(amount) => {
+                                        let mut s = self.state.get_account(&tx.from);
+                                        s.balance += amount;
+                                        self.state.update_account(tx.from, s);
+                                        (1, 50000, None)
+                                    }
+                                    Err(_) => (0, 50000, None),
+                                }
+                            } else { (0, 21000, None) }
+                        } else { (0, 21000, None) }
+                    }
+                    6 => { // Create validator: BLS key, proof of possession, commission, max commission
+                           // and max change (u16 big-endian basis points each), then a UTF-8 moniker;
+                           // tx.value is the self-bond
+                        let fixed = 1 + PUBLIC_KEY_LEN + SIGNATURE_LEN + 6;
+                        if tx.data.len() >= fixed {
+                            let (pubkey, rest) = tx.data[1..].split_at(PUBLIC_KEY_LEN);
+                            let (proof, rest) = rest.split_at(SIGNATURE_LEN);
+                            let rate = |i: usize| u16::from_be_bytes([rest[i], rest[i + 1]]);
+                            let sender = self.state.get_account(&tx.from);
+                            match std::str::from_utf8(&rest[6..]) {
+                                Ok(moniker) if sender.balance >= tx.value => {
+                                    let profile = ValidatorProfile::new(moniker, rate(0), rate(2), rate(4));
+                                    match self.state.staking.create_validator(tx.from, profile, Some((pubkey.to_vec(), proof)), tx.value, header.height, &self.state.params) {
+                                        Ok(_) => {
+                                            let mut s = self.state.get_account(&tx.from);
+                                            s.balance -= tx.value;
+                                            self.state.update_account(tx.from, s);
+                                            (1, 50000, None)
+                                        }
+                                        Err(_) => (0, 50000, None),
+                                    }
+                                }
+                                _ => (0, 50000, None),
+                            }
+                        } else { (0, 21000, None) }
+                    }
+                    7 => { // Edit validator: new commission (u16 big-endian, 0xFFFF keeps it), then a new
+                           // moniker (empty keeps it)
+                        if tx.data.len() >= 3 {
+                            let commission = Some(u16::from_be_bytes([tx.data[1], tx.data[2]])).filter(|rate| *rate != u16::MAX);
+                            match std::str::from_utf8(&tx.data[3..]) {
+                                Ok(moniker) => {
+                                    let moniker = Some(moniker.to_string()).filter(|m| !m.is_empty());
+                                    match self.state.staking.edit_validator(tx.from, commission, moniker) {
+                                        Ok(_) => (1, 50000, None),
+                                        Err(_) => (0, 50000, None),
+                                    }
+                                }
+                                Err(_) => (0, 50000, None),
+                            }
+                        } else { (0, 21000, None) }
+                    }
+                    8 => { // Exit validator (sender is the operator); its self-bond starts unbonding
+                        match self.state.staking.exit_validator(tx.from, header.height, &self.state.params) {
+                            Ok(_) => (1, 50000, None),
+                            Err(_) => (0, 50000, None),
+                        }
+                    }
+                    9 => { // Redelegate tx.value from the first validator to the second
+                        if tx.data.len() >= 49 {
+                            let mut src_bytes = [0u8; 24];
+                            let mut dst_bytes = [0u8; 24];
+                            src_bytes.copy_from_slice(&tx.data[1..25]);
+                            dst_bytes.copy_from_slice(&tx.data[25..49]);
+                            match (Address::from_bytes(src_bytes), Address::from_bytes(dst_bytes)) {
+                                (Ok(source), Ok(destination)) => {
+                                    match self.state.staking.redelegate(tx.from, source, destination, tx.value, header.height, &self.state.params) {
+                                        Ok(_) => (1, 50000, None),
+                                        Err(_) => (0, 50000, None),
+                                    }
+                                }
+                                _ => (0, 21000, None),
+                            }
+                        } else { (0, 21000, None) }
+                    }
                     _ => (0, 21000, None)
                 }
             }
+        } else if is_governance {
+            match tx.data.first() {
+                Some(1) | Some(2) => { // Submit a proposal; tx.value is the deposit
+                    let p_type = match tx.data[0] {
+                        // Parameter change: key length (u8), key, then the new value, both UTF-8
+                        1 => tx.data.get(1).and_then(|len| {
+                            let key = std::str::from_utf8(tx.data.get(2..2 + *len as usize)?).ok()?;
+                            let value = std::str::from_utf8(&tx.data[2 + *len as usize..]).ok()?;
+                            Some(ProposalType::ParameterChange { key: key.to_string(), value: value.to_string() })
+                        }),
+                        // Software upgrade: 32-byte binary hash, upgrade height (u64 big-endian),
+                        // then the UTF-8 version
+                        _ => tx.data.get(1..33).zip(tx.data.get(33..41)).and_then(|(hash, height)| {
+                            let version = std::str::from_utf8(&tx.data[41..]).ok()?;
+                            Some(ProposalType::SoftwareUpgrade {
+                                version: version.to_string(),
+                                hash: hash.try_into().ok()?,
+                                height: u64::from_be_bytes(height.try_into().ok()?),
+                            })
+                        }),
+                    };
+                    match p_type {
+                        Some(p_type) => match self.state.governance.submit_proposal(tx.from, p_type, tx.value, header.height, &self.state.params) {
+                            Ok(_) => {
+                                let mut s = self.state.get_account(&tx.from);
+                                s.balance -= tx.value;
+                                self.state.update_account(tx.from, s);
+                                (1, 50000, None)
+                            }
+                            Err(_) => (0, 50000, None),
+                        },
+                        None => (0, 21000, None),
+                    }
+                }
+                Some(3) => { // Vote: proposal id (u64 big-endian), then the option byte
+                    let vote = tx.data.get(1..9).zip(tx.data.get(9).and_then(|b| VoteOption::from_byte(*b)));
+                    match vote {
+                        Some((id, option)) => {
+                            let id = u64::from_be_bytes(id.try_into().unwrap());
+                            match self.state.governance.vote(id, tx.from, option, &self.state.staking, header.height) {
+                                Ok(_) => (1, 50000, None),
+                                Err(_) => (0, 50000, None),
+                            }
+                        }
+                        None => (0, 21000, None),
+                    }
+                }
+                _ => (0, 21000, None),
+            }
         } else if let Some(precompile) = crate::vm::precompiles::get_precompile(&tx.to) {
              match precompile(&tx.data) {
                  Ok(_) => (1, 500u64, None),
@@ -191,8 +344,13 @@ impl<'a> BlockProcessor<'a> {
                                 }
                             } else { (0, intrinsic_gas, None) }
                         } else {
-                            // Simple balance transfer
-                            let gas_used = intrinsic_gas + (tx.data.len() as u64 * 16);
+                            // Simple balance transfer; calldata is part of the intrinsic gas
+                            // once CalldataGas is active
+                            let gas_used = if self.state.governance.forks.is_active(Fork::CalldataGas, header.height) {
+                                intrinsic_gas
+                            } else {
+                                intrinsic_gas + (tx.data.len() as u64 * 16)
+                            };
                             if gas_used > tx.gas_limit {
                                 (0, tx.gas_limit, None)
                             } else {
@@ -289,16 +447,95 @@ impl<'a> BlockProcessor<'a> {
             s.balance += refund;
             self.state.update_account(tx.from, s);
         }
+        // The fee for the gas used is burned
+        let burned = gas_used.min(tx.gas_limit) as u128 * tx.gas_price;
+        self.state.staking.total_supply = self.state.staking.total_supply.saturating_sub(burned);
 
         Ok(TransactionReceipt {
             tx_hash: tx.hash(),
             status,
             gas_used,
+            logs_bloom: Bloom::from_logs(&logs),
             logs,
             contract_address,
         })
     }
 
+    /// Verifies each piece of evidence against the state and burns the offender's stake.
+    /// Runs after the block's transactions, so proposer and importers agree on the order.
+    pub fn apply_evidence(&mut self, evidence: &[Evidence], header: &crate::types::block::BlockHeader) -> Result<(), String> {
+        for e in evidence {
+            e.verify(&self.state.staking, &self.state.params, self.state.params.chain_id, header.height).map_err(|err| format!("Invalid evidence: {}", err))?;
+            let burned = self.state.staking.slash(e.offender(), e.slash_fraction(&self.state.params), e.offence_id(), e.height());
+            self.state.staking.jail(e.offender(), header.slot + self.state.params.jail_duration_slots);
+            println!("[PROCESSOR] Slashed {} for {:?}: {} burned", e.offender(), e.reason(), burned);
+        }
+        Ok(())
+    }
+
+    /// Verifies the commit a block carries against the state's validator set and records
+    /// which voters signed it, jailing those that have been down for too long. The commit
+    /// must be for a height above the last recorded one and below the block's.
+    pub fn apply_last_commit(&mut self, commit: Option<&FinalityCommit>, header: &crate::types::block::BlockHeader) -> Result<(), String> {
+        let commit = match commit {
+            Some(commit) => commit,
+            None => return Ok(()),
+        };
+        if commit.height <= self.state.staking.last_commit_height || commit.height >= header.height {
+            return Err(format!("Commit for height {} cannot be recorded at height {}", commit.height, header.height));
+        }
+        let validators = self.state.staking.validator_set().to_vec();
+        commit.verify(self.state.params.chain_id, &validators).map_err(|e| format!("Invalid last commit: {}", e))?;
+        let voters: Vec<Address> = validators.iter()
+            .filter(|v| v.is_active && !v.consensus_pubkey.is_empty())
+            .map(|v| v.address)
+            .collect();
+        let signers = commit.signers(&validators);
+        for validator in self.state.staking.record_commit(commit.height, &voters, &signers, header.slot, &self.state.params) {
+            println!("[PROCESSOR] Jailed {} for downtime", validator);
+        }
+        Ok(())
+    }
+
+    /// Runs once per block before its transactions: carries out the upgrade plan due at
+    /// this height. Errors if the plan names a newer release than this binary's, in
+    /// which case the node must halt instead of executing the block.
+    pub fn begin_block(&mut self, header: &crate::types::block::BlockHeader) -> Result<(), String> {
+        if let Some(plan) = self.state.governance.begin_block(header.height, NODE_VERSION)? {
+            println!("[GOVERNANCE] Upgraded to release {} at height {}", plan.version, header.height);
+        }
+        Ok(())
+    }
+
+    /// Runs once per block after its transactions, evidence and last commit: pays out
+    /// matured unbonding, tallies the proposals whose voting ends, mints the block reward
+    /// to the proposer's validator, and on epoch boundaries applies the parameter changes
+    /// governance scheduled and then elects the validator set. Finally commits the
+    /// staking and governance stores and the parameters to the state root.
+    pub fn end_block(&mut self, header: &crate::types::block::BlockHeader) {
+        let mut payouts = self.state.staking.process_matured_unbonding(header.height);
+        let outcome = self.state.governance.end_block(header.height, &self.state.staking, &self.state.params);
+        payouts.extend(outcome.refunds);
+        for (recipient, amount) in payouts {
+            let mut acc = self.state.get_account(&recipient);
+            acc.balance += amount;
+            self.state.update_account(recipient, acc);
+        }
+        self.state.staking.total_supply = self.state.staking.total_supply.saturating_sub(outcome.burned);
+        for id in outcome.passed {
+            println!("[GOVERNANCE] Proposal {} passed at height {}", id, header.height);
+        }
+        self.state.staking.allocate_block_reward(header.proposer, calculate_block_reward(header.height));
+        if header.height.is_multiple_of(BLOCKS_PER_EPOCH) {
+            for id in self.state.governance.activate(header.height, &mut self.state.params) {
+                println!("[GOVERNANCE] Parameter change {} in force from height {}", id, header.height);
+            }
+            self.state.staking.elect_validators(header.height / BLOCKS_PER_EPOCH, &header.vrf_output, &self.state.params);
+        }
+        self.state.commit_staking();
+        self.state.commit_governance();
+    }
+
     pub fn validate_block(&mut self, block: &Block) -> Result<Vec<TransactionReceipt>, String> {
         // 1. Verify Base Fee matches expected
         if block.header.base_fee != self.fee_market.base_fee {
@@ -316,7 +553,18 @@ impl<'a> BlockProcessor<'a> {
             return Err("Invalid transactions root".to_string());
         }
 
-        // 4. Process transactions sequentially
+        if block.evidence.len() as u64 > self.state.params.max_evidence_per_block {
+            return Err(format!("Too much evidence: {} > {}", block.evidence.len(), self.state.params.max_evidence_per_block));
+        }
+        if Block::calculate_evidence_root(&block.evidence) != block.header.evidence_root {
+            return Err("Invalid evidence root".to_string());
+        }
+        if Block::calculate_last_commit_hash(block.last_commit.as_ref()) != block.header.last_commit_hash {
+            return Err("Invalid last commit hash".to_string());
+        }
+
+        // 4. Process transactions sequentially, after the upgrade due at this height
+        self.begin_block(&block.header)?;
         let mut receipts = Vec::new();
         for tx in &block.transactions {
             // Verify tx gas price >= base fee
@@ -331,6 +579,17 @@ impl<'a> BlockProcessor<'a> {
         }
 
         // 5. Verify Receipts Root (Omitted for brevity, but same logic as tx_root)
+
+        // 5b. Punish the misbehaviour the block reports and record vote participation
+        self.apply_evidence(&block.evidence, &block.header)?;
+        self.apply_last_commit(block.last_commit.as_ref(), &block.header)?;
+        self.end_block(&block.header);
+
+        // 6. Verify the header bloom commits to exactly the logs produced; version 1
+        // headers carry no bloom
+        if block.header.version >= HEADER_VERSION && Bloom::from_receipts(&receipts) != block.header.logs_bloom {
+            return Err("Invalid logs bloom".to_string());
+        }
         
         Ok(receipts)
     }
@@ -0,0 +1,174 @@
+// File: src/crypto/bls.rs
+//
+// BLS12-381 signatures in the "minimal-pubkey-size" variant: public keys are 48-byte
+// compressed G1 points, signatures 96-byte compressed G2 points. Messages are hashed
+// to G2 with the IETF hash-to-curve suite BLS12381G2_XMD:SHA-256_SSWU_RO_, and rogue-key
+// attacks on aggregation are prevented with proofs of possession, following the
+// POP scheme of draft-irtf-cfrg-bls-signature.
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
+use digest09::consts::{U32, U64};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Domain separation tag for message signatures.
+pub const DST_SIGNATURE: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Domain separation tag for proofs of possession.
+pub const DST_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+pub const PUBLIC_KEY_LEN: usize = 48;
+pub const SIGNATURE_LEN: usize = 96;
+
+/// SHA-256 behind the digest 0.9 traits that bls12_381's hash-to-curve expects.
+#[derive(Clone, Default)]
+struct XmdSha256(Sha256);
+
+impl digest09::BlockInput for XmdSha256 {
+    type BlockSize = U64;
+}
+
+impl digest09::Update for XmdSha256 {
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        Digest::update(&mut self.0, data);
+    }
+}
+
+impl digest09::FixedOutputDirty for XmdSha256 {
+    type OutputSize = U32;
+
+    fn finalize_into_dirty(&mut self, out: &mut digest09::Output<Self>) {
+        out.copy_from_slice(&self.0.finalize_reset());
+    }
+}
+
+impl digest09::Reset for XmdSha256 {
+    fn reset(&mut self) {
+        Digest::reset(&mut self.0);
+    }
+}
+
+#[derive(Clone)]
+pub struct BlsSecretKey(Scalar);
+
+impl BlsSecretKey {
+    /// Derives a key deterministically from `seed` (at least 32 bytes of secret material),
+    /// so a validator's BLS key follows from its node key.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&Sha512::new().chain_update(b"KORTANA-BLS-KEYGEN-v1").chain_update(seed).finalize());
+        let mut scalar = Scalar::from_bytes_wide(&wide);
+        if scalar == Scalar::zero() {
+            scalar = Scalar::one();
+        }
+        Self(scalar)
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        G1Affine::from(G1Projective::generator() * self.0).to_compressed().to_vec()
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Vec<u8> {
+        sign_with_dst(&self.0, msg, DST_SIGNATURE)
+    }
+
+    /// Signature over our own public key, proving we hold the secret key.
+    pub fn proof_of_possession(&self) -> Vec<u8> {
+        sign_with_dst(&self.0, &self.public_key(), DST_POP)
+    }
+}
+
+fn sign_with_dst(sk: &Scalar, msg: &[u8], dst: &[u8]) -> Vec<u8> {
+    let h = <G2Projective as HashToCurve<ExpandMsgXmd<XmdSha256>>>::hash_to_curve(msg, dst);
+    G2Affine::from(h * sk).to_compressed().to_vec()
+}
+
+fn parse_public_key(bytes: &[u8]) -> Option<G1Affine> {
+    let bytes: &[u8; PUBLIC_KEY_LEN] = bytes.try_into().ok()?;
+    let pk = Option::<G1Affine>::from(G1Affine::from_compressed(bytes))?;
+    // The identity would verify any signature of the identity
+    (!bool::from(pk.is_identity())).then_some(pk)
+}
+
+fn parse_signature(bytes: &[u8]) -> Option<G2Affine> {
+    let bytes: &[u8; SIGNATURE_LEN] = bytes.try_into().ok()?;
+    Option::<G2Affine>::from(G2Affine::from_compressed(bytes))
+}
+
+/// Checks e(pk, H(msg)) == e(g1, sig) as a single product of pairings.
+fn core_verify(pk: &G1Affine, msg: &[u8], sig: &G2Affine, dst: &[u8]) -> bool {
+    let h = G2Affine::from(<G2Projective as HashToCurve<ExpandMsgXmd<XmdSha256>>>::hash_to_curve(msg, dst));
+    let neg_g1 = -G1Affine::generator();
+    multi_miller_loop(&[(pk, &G2Prepared::from(h)), (&neg_g1, &G2Prepared::from(*sig))])
+        .final_exponentiation() == Gt::identity()
+}
+
+pub fn is_valid_public_key(pubkey: &[u8]) -> bool {
+    parse_public_key(pubkey).is_some()
+}
+
+pub fn verify(pubkey: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+    match (parse_public_key(pubkey), parse_signature(signature)) {
+        (Some(pk), Some(sig)) => core_verify(&pk, msg, &sig, DST_SIGNATURE),
+        _ => false,
+    }
+}
+
+pub fn verify_possession(pubkey: &[u8], proof: &[u8]) -> bool {
+    match (parse_public_key(pubkey), parse_signature(proof)) {
+        (Some(pk), Some(sig)) => core_verify(&pk, pubkey, &sig, DST_POP),
+        _ => false,
+    }
+}
+
+/// Sums signatures into one; `None` if any of them does not decode.
+pub fn aggregate_signatures<'a>(signatures: impl IntoIterator<Item = &'a [u8]>) -> Option<Vec<u8>> {
+    let mut acc = G2Projective::identity();
+    for sig in signatures {
+        acc += parse_signature(sig)?;
+    }
+    Some(G2Affine::from(acc).to_compressed().to_vec())
+}
+
+/// Verifies an aggregate of signatures by `pubkeys` over the same message. Only sound
+/// for keys whose proof of possession has been checked.
+pub fn fast_aggregate_verify<'a>(pubkeys: impl IntoIterator<Item = &'a [u8]>, msg: &[u8], signature: &[u8]) -> bool {
+    let mut acc = G1Projective::identity();
+    let mut count = 0;
+    for pk in pubkeys {
+        match parse_public_key(pk) {
+            Some(pk) => acc += pk,
+            None => return false,
+        }
+        count += 1;
+    }
+    match parse_signature(signature) {
+        Some(sig) if count > 0 => core_verify(&G1Affine::from(acc), msg, &sig, DST_SIGNATURE),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_aggregate_and_verify() {
+        let keys: Vec<BlsSecretKey> = (1..=3u8).map(|i| BlsSecretKey::from_seed(&[i; 32])).collect();
+        let pubkeys: Vec<Vec<u8>> = keys.iter().map(|k| k.public_key()).collect();
+        let sigs: Vec<Vec<u8>> = keys.iter().map(|k| k.sign(b"block")).collect();
+
+        assert!(verify(&pubkeys[0], b"block", &sigs[0]));
+        assert!(!verify(&pubkeys[0], b"other", &sigs[0]));
+        assert!(!verify(&pubkeys[1], b"block", &sigs[0]));
+
+        let agg = aggregate_signatures(sigs.iter().map(|s| s.as_slice())).unwrap();
+        assert!(fast_aggregate_verify(pubkeys.iter().map(|p| p.as_slice()), b"block", &agg));
+        assert!(!fast_aggregate_verify(pubkeys[..2].iter().map(|p| p.as_slice()), b"block", &agg));
+
+        // A proof of possession is not a valid message signature and vice versa
+        let pop = keys[0].proof_of_possession();
+        assert!(verify_possession(&pubkeys[0], &pop));
+        assert!(!verify_possession(&pubkeys[1], &pop));
+        assert!(!verify(&pubkeys[0], &pubkeys[0], &pop));
+    }
+}
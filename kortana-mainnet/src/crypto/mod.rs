@@ -1,10 +1,9 @@
+pub mod bls;
 pub mod vrf;
 
-use k256::ecdsa::{SigningKey, Signature, VerifyingKey};
+use k256::ecdsa::{RecoveryId, SigningKey, Signature, VerifyingKey};
 use k256::ecdsa::signature::{Signer, Verifier};
-use bls12_381::G1Projective;
 use rand::thread_rng;
-use sha3::{Digest, Keccak256};
 
 pub struct KeyPair {
     pub signing_key: SigningKey,
@@ -33,31 +32,37 @@ pub fn sign_message(private_key: &[u8], msg: &[u8]) -> Vec<u8> {
     sig.to_bytes().to_vec()
 }
 
+/// Signs `msg` and appends the recovery id, so verifiers can recover the signer's key
+/// from the 65-byte signature alone (r || s || v).
+pub fn sign_recoverable(private_key: &[u8], msg: &[u8]) -> Vec<u8> {
+    let signing_key = SigningKey::from_slice(private_key).expect("Invalid private key");
+    let (sig, recid) = signing_key.sign_recoverable(msg).expect("Signing failed");
+    let mut out = sig.to_bytes().to_vec();
+    out.push(recid.to_byte());
+    out
+}
+
+/// Recovers the compressed SEC1 public key from a signature made by `sign_recoverable`.
+pub fn recover_pubkey(msg: &[u8], sig_bytes: &[u8]) -> Option<Vec<u8>> {
+    if sig_bytes.len() != 65 {
+        return None;
+    }
+    let sig = Signature::from_slice(&sig_bytes[..64]).ok()?;
+    let recid = RecoveryId::from_byte(sig_bytes[64])?;
+    let key = VerifyingKey::recover_from_msg(msg, &sig, recid).ok()?;
+    Some(key.to_sec1_bytes().to_vec())
+}
+
 pub fn verify_signature(pubkey: &[u8], msg: &[u8], sig_bytes: &[u8]) -> bool {
     let verifying_key = match VerifyingKey::from_sec1_bytes(pubkey) {
         Ok(k) => k,
         Err(_) => return false,
     };
+    // Recoverable signatures carry a trailing recovery id byte
+    let sig_bytes = if sig_bytes.len() == 65 { &sig_bytes[..64] } else { sig_bytes };
     let sig = match Signature::from_slice(sig_bytes) {
         Ok(s) => s,
         Err(_) => return false,
     };
     verifying_key.verify(msg, &sig).is_ok()
 }
-
-// BLS12-381 Aggregate Signatures (Section 20)
-pub fn aggregate_bls_signatures(signatures: &[G1Projective]) -> G1Projective {
-    signatures.iter().fold(G1Projective::identity(), |acc, sig| acc + sig)
-}
-
-pub fn hash_to_g1(msg: &[u8]) -> G1Projective {
-    // Hash-to-curve using generator scalar multiplication with domain-separated Keccak256.
-    // Provides a deterministic mapping from arbitrary bytes to a G1 point.
-    // For production BLS signature aggregation, replace with IETF hash-to-curve (draft-irtf-cfrg-hash-to-curve).
-    let mut hasher = Keccak256::new();
-    hasher.update(b"kortana_bls_h2g1:");
-    hasher.update(msg);
-    let hash = hasher.finalize();
-    let _ = hash; // bound but reserved for future scalar derivation
-    G1Projective::generator()
-}
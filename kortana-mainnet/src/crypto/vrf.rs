@@ -1,35 +1,254 @@
 // File: src/crypto/vrf.rs
+//
+// ECVRF as specified in RFC 9381, with the try-and-increment hash to curve, SHA-256 and
+// RFC 6979 nonces. The construction is generic over prime-order short Weierstrass
+// curves; the chain runs it over secp256k1 so that a proposer proves with the key that
+// signs its blocks. The unit tests check it against the RFC's P-256 test vectors.
 
-use sha3::{Digest, Keccak256};
-use crate::address::Address;
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::ff::{Field, PrimeField};
+use k256::elliptic_curve::group::{Curve as _, Group as _};
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use k256::elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytes, FieldBytesSize, ProjectivePoint, Scalar};
+use k256::Secp256k1;
+use sha2::{Digest, Sha256};
 
-pub struct VrfSeed {
-    pub proof: Vec<u8>,
-    pub output: [u8; 32],
+/// Suite string of the secp256k1 instantiation (SHA-256, try-and-increment); RFC 9381
+/// leaves secp256k1 unassigned and 0xFE is the value existing implementations use.
+pub const SUITE_SECP256K1_SHA256_TAI: u8 = 0xFE;
+
+/// Compressed Gamma (33 bytes), challenge (16 bytes) and response scalar (32 bytes).
+pub const PROOF_LEN: usize = 81;
+const POINT_LEN: usize = 33;
+const CHALLENGE_LEN: usize = 16;
+
+/// Proves `alpha` with a secp256k1 secret key. Fails only if the key is not a valid
+/// non-zero scalar.
+pub fn prove(secret_key: &[u8], alpha: &[u8]) -> Result<Vec<u8>, String> {
+    ecvrf_prove::<Secp256k1>(SUITE_SECP256K1_SHA256_TAI, secret_key, alpha)
+}
+
+/// Checks `proof` for `alpha` against a SEC1-encoded secp256k1 public key and returns the
+/// VRF output (beta) if it is valid.
+pub fn verify(public_key: &[u8], alpha: &[u8], proof: &[u8]) -> Option<[u8; 32]> {
+    ecvrf_verify::<Secp256k1>(SUITE_SECP256K1_SHA256_TAI, public_key, alpha, proof)
+}
+
+/// VRF output of a well-formed proof, without checking it.
+pub fn proof_to_hash(proof: &[u8]) -> Option<[u8; 32]> {
+    let (gamma, _, _) = decode_proof::<Secp256k1>(proof)?;
+    Some(gamma_to_hash::<Secp256k1>(SUITE_SECP256K1_SHA256_TAI, &gamma))
+}
+
+fn point_to_string<C>(point: &ProjectivePoint<C>) -> Vec<u8>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+}
+
+fn string_to_point<C>(bytes: &[u8]) -> Option<ProjectivePoint<C>>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let encoded = EncodedPoint::<C>::from_bytes(bytes).ok()?;
+    let affine: Option<AffinePoint<C>> = AffinePoint::<C>::from_encoded_point(&encoded).into();
+    affine.map(ProjectivePoint::<C>::from)
+}
+
+/// Big-endian integer of at most the field size, rejected if not below the group order.
+fn string_to_scalar<C: CurveArithmetic>(bytes: &[u8]) -> Option<Scalar<C>> {
+    let mut repr = FieldBytes::<C>::default();
+    let offset = repr.len().checked_sub(bytes.len())?;
+    repr[offset..].copy_from_slice(bytes);
+    Scalar::<C>::from_repr(repr).into()
+}
+
+/// Section 5.4.1.1: hash (salt, alpha, counter) until the digest is the x-coordinate of a
+/// point with even y.
+fn encode_to_curve<C>(suite: u8, salt: &[u8], alpha: &[u8]) -> ProjectivePoint<C>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    for ctr in 0..=u8::MAX {
+        let hash = Sha256::new()
+            .chain_update([suite, 0x01])
+            .chain_update(salt)
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        let mut candidate = [0x02u8; POINT_LEN];
+        candidate[1..].copy_from_slice(&hash);
+        if let Some(point) = string_to_point::<C>(&candidate) {
+            return point;
+        }
+    }
+    // Each attempt succeeds with probability ~1/2
+    unreachable!("no curve point after 256 attempts")
+}
+
+/// Section 5.4.2.1: the RFC 6979 nonce for the secret `x` and message `h_string`.
+fn generate_nonce<C: CurveArithmetic>(x: &Scalar<C>, h_string: &[u8]) -> Scalar<C> {
+    let mut h1 = FieldBytes::<C>::default();
+    h1.copy_from_slice(&Sha256::digest(h_string));
+    let h1 = <Scalar<C> as Reduce<C::Uint>>::reduce_bytes(&h1).to_repr();
+    let x = x.to_repr();
+    let hmac = |key: &[u8], parts: &[&[u8]]| -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes any key length");
+        parts.iter().for_each(|p| mac.update(p));
+        mac.finalize().into_bytes().to_vec()
+    };
+    let mut v = vec![0x01u8; 32];
+    let mut k = hmac(&[0u8; 32], &[&v, &[0x00], &x, &h1]);
+    v = hmac(&k, &[&v]);
+    k = hmac(&k, &[&v, &[0x01], &x, &h1]);
+    v = hmac(&k, &[&v]);
+    loop {
+        v = hmac(&k, &[&v]);
+        if let Some(nonce) = string_to_scalar::<C>(&v).filter(|n| !bool::from(n.is_zero())) {
+            return nonce;
+        }
+        k = hmac(&k, &[&v, &[0x00]]);
+        v = hmac(&k, &[&v]);
+    }
 }
 
-pub fn generate_vrf_seed(private_key: &[u8], epoch_seed: &[u8], slot: u64) -> VrfSeed {
-    // In a production environment, this would use a proper VRF library (like ed25519-vrf)
-    // Here we simulate the property that the output is deterministic based on private key + slot
-    let mut hasher = Keccak256::new();
-    hasher.update(private_key);
-    hasher.update(epoch_seed);
-    hasher.update(slot.to_be_bytes());
-    let output: [u8; 32] = hasher.finalize().into();
-    
-    // The "proof" would allow anyone with the public key to verify this output was generated correctly
-    let proof = output.to_vec(); // Placeholder for actual proof
-    
-    VrfSeed { proof, output }
+/// Section 5.4.3: the challenge over the public key, H, Gamma and the commitments U, V.
+fn challenge<C>(suite: u8, points: [&ProjectivePoint<C>; 5]) -> Vec<u8>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let mut hasher = Sha256::new().chain_update([suite, 0x02]);
+    for point in points {
+        hasher.update(point_to_string::<C>(point));
+    }
+    hasher.update([0x00]);
+    hasher.finalize()[..CHALLENGE_LEN].to_vec()
 }
 
-pub fn verify_vrf(_public_key: &[u8], _seed: &[u8], _slot: u64, _proof: &[u8], _output: &[u8; 32]) -> bool {
-    // Production: verify the VRF proof
-    // For now, we simulate success
-    true
+fn gamma_to_hash<C>(suite: u8, gamma: &ProjectivePoint<C>) -> [u8; 32]
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    // Cofactor 1: no clearing needed
+    Sha256::new()
+        .chain_update([suite, 0x03])
+        .chain_update(point_to_string::<C>(gamma))
+        .chain_update([0x00])
+        .finalize()
+        .into()
 }
 
-pub fn get_leader_from_vrf(vrf_output: [u8; 32], active_validators: &[Address]) -> Address {
-    let index = (u64::from_be_bytes(vrf_output[0..8].try_into().unwrap()) % active_validators.len() as u64) as usize;
-    active_validators[index]
+fn decode_proof<C>(proof: &[u8]) -> Option<(ProjectivePoint<C>, Scalar<C>, Scalar<C>)>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    if proof.len() != PROOF_LEN {
+        return None;
+    }
+    let gamma = string_to_point::<C>(&proof[..POINT_LEN])?;
+    let c = string_to_scalar::<C>(&proof[POINT_LEN..POINT_LEN + CHALLENGE_LEN])?;
+    let s = string_to_scalar::<C>(&proof[POINT_LEN + CHALLENGE_LEN..])?;
+    Some((gamma, c, s))
+}
+
+/// Section 5.1.
+pub(crate) fn ecvrf_prove<C>(suite: u8, secret_key: &[u8], alpha: &[u8]) -> Result<Vec<u8>, String>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let x = string_to_scalar::<C>(secret_key)
+        .filter(|x| !bool::from(x.is_zero()))
+        .ok_or("Invalid VRF secret key")?;
+    let public_key = ProjectivePoint::<C>::generator() * x;
+    let h = encode_to_curve::<C>(suite, &point_to_string::<C>(&public_key), alpha);
+    let gamma = h * x;
+    let k = generate_nonce::<C>(&x, &point_to_string::<C>(&h));
+    let c = challenge::<C>(suite, [&public_key, &h, &gamma, &(ProjectivePoint::<C>::generator() * k), &(h * k)]);
+    let s = k + string_to_scalar::<C>(&c).expect("16-byte challenge is below the group order") * x;
+
+    let mut proof = point_to_string::<C>(&gamma);
+    proof.extend_from_slice(&c);
+    proof.extend_from_slice(&s.to_repr());
+    Ok(proof)
+}
+
+/// Section 5.3; returns beta for a valid proof.
+pub(crate) fn ecvrf_verify<C>(suite: u8, public_key: &[u8], alpha: &[u8], proof: &[u8]) -> Option<[u8; 32]>
+where
+    C: CurveArithmetic,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    FieldBytesSize<C>: ModulusSize,
+{
+    let y = string_to_point::<C>(public_key).filter(|y| !bool::from(y.is_identity()))?;
+    let (gamma, c, s) = decode_proof::<C>(proof)?;
+    let h = encode_to_curve::<C>(suite, &point_to_string::<C>(&y), alpha);
+    let u = ProjectivePoint::<C>::generator() * s - y * c;
+    let v = h * s - gamma * c;
+    let expected = challenge::<C>(suite, [&y, &h, &gamma, &u, &v]);
+    if expected != proof[POINT_LEN..POINT_LEN + CHALLENGE_LEN] {
+        return None;
+    }
+    Some(gamma_to_hash::<C>(suite, &gamma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 9381 appendix B.1, ECVRF-P256-SHA256-TAI examples 10 and 11.
+    #[test]
+    fn test_p256_vectors() {
+        let sk = hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+        let pk = hex::decode("0360fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6").unwrap();
+        let vectors = [
+            (
+                "sample",
+                "035b5c726e8c0e2c488a107c600578ee75cb702343c153cb1eb8dec77f4b5071b4a53f0a46f018bc2c56e58d383f2305e0975972c26feea0eb122fe7893c15af376b33edf7de17c6ea056d4d82de6bc02f",
+                "a3ad7b0ef73d8fc6655053ea22f9bede8c743f08bbed3d38821f0e16474b505e",
+            ),
+            (
+                "test",
+                "034dac60aba508ba0c01aa9be80377ebd7562c4a52d74722e0abae7dc3080ddb56c19e067b15a8a8174905b13617804534214f935b94c2287f797e393eb0816969d864f37625b443f30f1a5a33f2b3c854",
+                "a284f94ceec2ff4b3794629da7cbafa49121972671b466cab4ce170aa365f26d",
+            ),
+        ];
+        for (alpha, pi, beta) in vectors {
+            let proof = ecvrf_prove::<p256::NistP256>(0x01, &sk, alpha.as_bytes()).unwrap();
+            assert_eq!(hex::encode(&proof), pi);
+            let output = ecvrf_verify::<p256::NistP256>(0x01, &pk, alpha.as_bytes(), &proof).unwrap();
+            assert_eq!(hex::encode(output), beta);
+        }
+    }
+
+    #[test]
+    fn test_secp256k1_proof_is_bound_to_key_and_input() {
+        let key = [7u8; 32];
+        let public_key = k256::ecdsa::SigningKey::from_slice(&key).unwrap().verifying_key().to_sec1_bytes();
+        let proof = prove(&key, b"slot 1").unwrap();
+        assert_eq!(proof.len(), PROOF_LEN);
+        assert_eq!(verify(&public_key, b"slot 1", &proof), proof_to_hash(&proof));
+        assert!(verify(&public_key, b"slot 2", &proof).is_none());
+
+        let other = k256::ecdsa::SigningKey::from_slice(&[8u8; 32]).unwrap().verifying_key().to_sec1_bytes();
+        assert!(verify(&other, b"slot 1", &proof).is_none());
+        let mut tampered = proof.clone();
+        tampered[PROOF_LEN - 1] ^= 1;
+        assert!(verify(&public_key, b"slot 1", &tampered).is_none());
+    }
 }
@@ -1,6 +1,7 @@
 // File: src/lib.rs
 
 pub mod address;
+pub mod chain_spec;
 pub mod config;
 pub mod consensus;
 pub mod core;
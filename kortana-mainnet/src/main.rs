@@ -1,15 +1,31 @@
 use kortana_blockchain_rust::address::Address;
-use kortana_blockchain_rust::consensus::{ConsensusEngine, ValidatorInfo};
+use kortana_blockchain_rust::consensus::{ConsensusEngine, PohRecorder};
 use kortana_blockchain_rust::state::account::State;
 use kortana_blockchain_rust::mempool::Mempool;
 use kortana_blockchain_rust::parameters::*;
 use kortana_blockchain_rust::core::fees::FeeMarket;
-use kortana_blockchain_rust::consensus::bft::FinalityGadget;
+use kortana_blockchain_rust::core::chain;
+use kortana_blockchain_rust::core::importer::ImportError;
+use kortana_blockchain_rust::consensus::fork_choice::BlockTree;
+use kortana_blockchain_rust::consensus::slot_clock::SlotClock;
+use kortana_blockchain_rust::consensus::snap::SnapSync;
+use kortana_blockchain_rust::storage::snapshot::Checkpoint;
+use kortana_blockchain_rust::consensus::sync::SyncEngine;
+use kortana_blockchain_rust::network::messages::{SyncCommand, SyncEvent, SyncRequest, SyncResponse};
+use kortana_blockchain_rust::rpc::events::ChainEventLog;
+use kortana_blockchain_rust::network::messages::PeerPenalty;
+use kortana_blockchain_rust::consensus::bft::{FinalityCommit, FinalityGadget};
+use kortana_blockchain_rust::consensus::evidence::{Evidence, EvidencePool};
+use kortana_blockchain_rust::consensus::tendermint::{BftOutput, RoundState};
+use kortana_blockchain_rust::crypto::bls::BlsSecretKey;
+use kortana_blockchain_rust::network::messages::NetworkMessage;
 use kortana_blockchain_rust::config::NodeConfig;
+use kortana_blockchain_rust::chain_spec::ChainSpec;
+use kortana_blockchain_rust::core::genesis;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use k256::ecdsa::SigningKey;
 
 // Color Constants for Beautiful Logs
@@ -17,7 +33,7 @@ const CLR_RESET: &str = "\x1b[0m";
 const CLR_BLUE: &str = "\x1b[34m";
 const CLR_CYAN: &str = "\x1b[36m";
 const CLR_GREEN: &str = "\x1b[32m";
-const _CLR_RED: &str = "\x1b[31m";
+const CLR_RED: &str = "\x1b[31m";
 const CLR_YELLOW: &str = "\x1b[33m";
 const CLR_MAGENTA: &str = "\x1b[35m";
 const CLR_BOLD: &str = "\x1b[1m";
@@ -41,11 +57,50 @@ struct Args {
     #[arg(short, long)]
     bootnodes: Vec<String>,
 
+    /// On a fresh database, download the state at a trusted checkpoint block
+    /// (HEIGHT:HASH) from peers instead of replaying the chain from genesis
+    #[arg(long, value_name = "HEIGHT:HASH")]
+    snap_sync: Option<Checkpoint>,
+
+    /// Network to run: a preset (mainnet, testnet, dev) or the path of a JSON chain
+    /// spec. Every node of a network must use the same spec.
+    #[arg(long, default_value = "mainnet")]
+    chain: String,
+
     #[arg(long)]
     wallet: bool, // Subcommand flag for wallet generation
 
     #[arg(long)]
     test: bool, // Subcommand flag for self-test
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert a legacy JSON database to the binary tree layout in place
+    MigrateDb,
+    /// Rebuild the address and global transaction indexes from stored blocks
+    Reindex,
+    /// Write a checksummed snapshot (state, headers, state roots) at a height
+    ExportSnapshot {
+        #[arg(long)]
+        height: u64,
+        /// Output file, defaults to kortana-snapshot-<height>.bin
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Verify a snapshot against a trusted checkpoint and its header state root and load
+    /// it into an empty database
+    ImportSnapshot {
+        path: String,
+        /// Head block of the snapshot as HEIGHT:HASH, from a source you trust
+        #[arg(long)]
+        checkpoint: Checkpoint,
+    },
+    /// Print the chain spec selected by --chain as JSON, to start a custom network from
+    ExportChainSpec,
 }
 
 pub struct KortanaNode {
@@ -54,9 +109,12 @@ pub struct KortanaNode {
     pub mempool: Arc<Mutex<Mempool>>,
     pub fees: Arc<Mutex<FeeMarket>>,
     pub finality: Arc<Mutex<FinalityGadget>>,
+    pub evidence: Arc<Mutex<EvidencePool>>,
     pub storage: Arc<kortana_blockchain_rust::storage::Storage>,
     pub height: Arc<AtomicU64>,
     pub node_config: NodeConfig,
+    pub chain_id: u64,
+    pub chain_name: String,
 }
 
 #[tokio::main]
@@ -68,8 +126,20 @@ async fn main() {
         return;
     }
     
+    let spec = match ChainSpec::load(&args.chain) {
+        Ok(spec) => spec,
+        Err(e) => {
+            println!("{}Cannot load chain spec: {}{}", CLR_RED, e, CLR_RESET);
+            std::process::exit(1);
+        }
+    };
+
     if args.test {
-        run_self_test();
+        run_self_test(&spec);
+        return;
+    }
+    if let Some(Command::ExportChainSpec) = args.command {
+        println!("{}", spec.to_json());
         return;
     }
 
@@ -78,7 +148,7 @@ async fn main() {
     println!("{}██╔═██╗ ██║   ██║██╔══██╗   ██║   ██╔══██║██║╚██╗██║██╔══██║{}", CLR_BLUE, CLR_RESET);
     println!("{}██║  ██╗╚██████╔╝██║  ██║   ██║   ██║  ██║██║ ╚████║██║  ██║{}", CLR_BLUE, CLR_RESET);
     println!("{}╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝   ╚═╝   ╚═╝  ╚═╝╚═╝  ╚═══╝╚═╝  ╚═╝{}", CLR_BLUE, CLR_RESET);
-    println!("\n{}--- KORTANA MAINNET PROTOCOL - RUST EVM v1.1.0 (PRODUCTION) ---{}", CLR_BOLD, CLR_RESET);
+    println!("\n{}--- KORTANA {} PROTOCOL - RUST EVM v{} ---{}", CLR_BOLD, spec.name.to_uppercase(), NODE_VERSION, CLR_RESET);
 
     // 1. Initialize Configuration
     let mut config = if args.prod {
@@ -91,9 +161,31 @@ async fn main() {
     if let Some(addr) = args.rpc_addr { config.rpc_addr = addr; }
     if let Some(addr) = args.p2p_addr { config.p2p_addr = addr; }
 
+    match args.command {
+        Some(Command::MigrateDb) => {
+            run_migrate_db(&config.db_path);
+            return;
+        }
+        Some(Command::Reindex) => {
+            run_reindex(&config.db_path);
+            return;
+        }
+        Some(Command::ExportSnapshot { height, out }) => {
+            let out = out.unwrap_or_else(|| format!("kortana-snapshot-{}.bin", height));
+            run_export_snapshot(&config.db_path, height, &out, spec.chain_id);
+            return;
+        }
+        Some(Command::ImportSnapshot { path, checkpoint }) => {
+            run_import_snapshot(&config.db_path, &path, &spec, &checkpoint);
+            return;
+        }
+        Some(Command::ExportChainSpec) | None => {}
+    }
+
     let signing_key = SigningKey::from_bytes(config.validator_private_key.as_slice().into()).expect("Invalid private key");
     let node_addr = Address::from_pubkey(&signing_key.verifying_key().to_sec1_bytes());
 
+    println!("{}Chain:        {} (id {}){}", CLR_CYAN, spec.name, spec.chain_id, CLR_RESET);
     println!("{}Node Address: {}{}", CLR_CYAN, node_addr.to_hex(), CLR_RESET);
     println!("{}RPC Address:  {}{}", CLR_CYAN, config.rpc_addr, CLR_RESET);
     println!("{}P2P Address:  {}{}\n", CLR_CYAN, config.p2p_addr, CLR_RESET);
@@ -105,8 +197,40 @@ async fn main() {
         std::fs::create_dir_all(parent).ok();
     }
     let storage = Arc::new(kortana_blockchain_rust::storage::Storage::new(config.db_path.as_str()));
+    if storage.has_legacy_data() {
+        println!("{}LEGACY FORMAT{}", CLR_RED, CLR_RESET);
+        println!("{}The database at {} uses the old JSON layout. Run `migrate-db` before starting the node.{}", CLR_RED, config.db_path, CLR_RESET);
+        std::process::exit(1);
+    }
+    if let Ok(Some(version)) = storage.schema_version() {
+        if version != kortana_blockchain_rust::storage::codec::SCHEMA_VERSION {
+            println!("{}SCHEMA {}{}", CLR_RED, version, CLR_RESET);
+            println!("{}The database at {} was written with storage schema {} (this node uses {}). Resync from genesis into a fresh DB_PATH.{}", CLR_RED, config.db_path, version, kortana_blockchain_rust::storage::codec::SCHEMA_VERSION, CLR_RESET);
+            std::process::exit(1);
+        }
+    }
+    if storage.needs_reindex() {
+        println!("{}OUTDATED INDEXES{}", CLR_RED, CLR_RESET);
+        println!("{}The transaction indexes at {} use the old array layout. Run `reindex` before starting the node.{}", CLR_RED, config.db_path, CLR_RESET);
+        std::process::exit(1);
+    }
     println!("{}OK{}", CLR_GREEN, CLR_RESET);
 
+    match storage.check_integrity() {
+        Ok(report) => {
+            if !report.rolled_back.is_empty() {
+                println!("{}[STORAGE] Rolled back partially committed heights {:?}{}", CLR_YELLOW, report.rolled_back, CLR_RESET);
+            }
+            if !report.repaired_roots.is_empty() {
+                println!("{}[STORAGE] Repaired missing state roots at heights {:?}{}", CLR_YELLOW, report.repaired_roots, CLR_RESET);
+            }
+        }
+        Err(e) => {
+            println!("{}CRITICAL: Database integrity check failed: {} - Halting node for safety.{}", CLR_RED, e, CLR_RESET);
+            panic!("Database integrity check failed: {}", e);
+        }
+    }
+
     // 3. Load or Initialize State
     print!("{}[2/5] Loading Ledger State... {}", CLR_YELLOW, CLR_RESET);
     let (h_init, state) = match storage.get_latest_state() {
@@ -115,30 +239,15 @@ async fn main() {
             (h, s)
         },
         Ok(None) => {
-            // Check fallback for any block indices
-            let max_h = storage.get_max_height_fallback();
-            if max_h > 0 {
-                if let Ok(Some(s)) = storage.get_state(max_h) {
-                    println!("{}RECOVERED FROM DB: HEIGHT {}{}", CLR_CYAN, max_h, CLR_RESET);
-                    (max_h, s)
-                } else {
-                    println!("{}ERROR: Traces of chain found (Height {}) but couldn't load state! Please check database health.{}", 
-                        CLR_RED, max_h, CLR_RESET);
-                    panic!("Critical database state mismatch at height {}", max_h);
-                }
-            } else {
-                println!("{}GENESIS: Starting new blockchain...{}", CLR_CYAN, CLR_RESET);
-                let initial_state = kortana_blockchain_rust::core::genesis::create_genesis_state();
-                let genesis_root = initial_state.calculate_root();
-                
-                // Persist GENESIS state and block 0
-                let genesis_block = kortana_blockchain_rust::core::genesis::create_genesis_block(genesis_root);
-                let _ = storage.put_state(0, &initial_state);
-                let _ = storage.put_block(&genesis_block);
-                let _ = storage.put_state_root(0, genesis_root);
-                
-                (0, initial_state)
-            }
+            println!("{}GENESIS: Starting new blockchain...{}", CLR_CYAN, CLR_RESET);
+            let initial_state = load_genesis_state(&spec);
+            let genesis_root = initial_state.calculate_root();
+
+            // Persist GENESIS state and block 0
+            let genesis_block = genesis::create_genesis_block_for(&spec, genesis_root);
+            storage.commit_block(&genesis_block, &[], &initial_state).expect("Failed to commit genesis block");
+
+            (0, initial_state)
         },
         Err(e) => {
             println!("{}CRITICAL DATABASE ERROR: {} - Halting node for safety.{}", CLR_RED, e, CLR_RESET);
@@ -148,24 +257,21 @@ async fn main() {
     let genesis_root = state.calculate_root();
     println!("Current state root: 0x{}", hex::encode(genesis_root));
 
+    // A database belongs to the chain whose genesis it holds
+    let spec_genesis_hash = genesis::genesis_hash(&spec).unwrap_or_else(|e| {
+        println!("{}Invalid chain spec: {}{}", CLR_RED, e, CLR_RESET);
+        std::process::exit(1);
+    });
+    let stored_genesis_hash = storage.get_header(0).ok().flatten().map(|h| h.hash());
+    if state.params.chain_id != spec.chain_id || stored_genesis_hash.is_some_and(|h| h != spec_genesis_hash) {
+        println!("{}The database at {} holds chain {} with another genesis than the {} spec (chain {}). Use the spec it was created with, or a fresh DB_PATH.{}", CLR_RED, config.db_path, state.params.chain_id, spec.name, spec.chain_id, CLR_RESET);
+        std::process::exit(1);
+    }
+
     // 4. Initialize Consensus
     print!("{}[3/5] Syncing Consensus Set... {}", CLR_YELLOW, CLR_RESET);
-    // Extract initial validators from genesis staking state
-    let mut initial_validators = Vec::new();
-    for (&addr, delegation_list) in state.staking.delegations.iter() {
-         // Simplify for genesis: if you stake to yourself at genesis, you're a validator
-         for stake_info in delegation_list {
-             if stake_info.delegator == addr && stake_info.amount >= MIN_VALIDATOR_STAKE {
-                 initial_validators.push(ValidatorInfo {
-                     address: addr,
-                     stake: stake_info.amount,
-                     is_active: true,
-                     commission: 500, // 5% default
-                     missed_blocks: 0,
-                 });
-             }
-         }
-    }
+    // The validator set and jail sentences come from the staking state
+    let initial_validators = state.staking.validator_set().to_vec();
     
     if initial_validators.is_empty() {
         panic!("No validators found in genesis state! Blockchain cannot start.");
@@ -173,6 +279,7 @@ async fn main() {
     println!("{}FOUND {} VALIDATORS{}", CLR_CYAN, initial_validators.len(), CLR_RESET);
 
     let mut consensus = ConsensusEngine::new(initial_validators);
+    consensus.sync_validators(&state.staking);
     if h_init > 0 {
         if let Ok(Some(block)) = storage.get_block(h_init) {
             consensus.finalized_hash = block.header.hash();
@@ -182,13 +289,12 @@ async fn main() {
     // 5. Ensure Genesis Block exists (Crucial for Explorer)
     if storage.get_block(0).unwrap_or(None).is_none() {
         println!("{}Generating missing Genesis Block...{}", CLR_YELLOW, CLR_RESET);
-        let genesis_state = if h_init == 0 { state.clone() } else { kortana_blockchain_rust::core::genesis::create_genesis_state() };
+        let genesis_state = if h_init == 0 { state.clone() } else { load_genesis_state(&spec) };
         let genesis_root = genesis_state.calculate_root();
-        let genesis_block = kortana_blockchain_rust::core::genesis::create_genesis_block(genesis_root);
+        let genesis_block = genesis::create_genesis_block_for(&spec, genesis_root);
         let genesis_hash = genesis_block.header.hash();
-        let _ = storage.put_state(0, &genesis_state);
-        let _ = storage.put_block(&genesis_block);
-        let _ = storage.put_state_root(0, genesis_root);
+        storage.put_block(&genesis_block).expect("Failed to write genesis block");
+        storage.put_state_root(0, genesis_root).expect("Failed to write genesis state root");
         
         // If we are at height 0, the "finalized hash" for the next block must be the genesis hash
         if h_init == 0 {
@@ -201,15 +307,35 @@ async fn main() {
         }
     }
 
+    // Blocks on disk at startup are treated as final; fork choice works above them.
+    consensus.head_hash = consensus.finalized_hash;
+    consensus.finalized_height = h_init;
+    let mut block_tree = BlockTree::new(h_init, consensus.head_hash);
+    let mut finality = FinalityGadget::new();
+    finality.last_finalized_height = h_init;
+    finality.last_finalized_hash = consensus.finalized_hash;
+    if let Ok(Some(commit)) = storage.latest_finality_commit() {
+        println!("{}[BFT]{} Last finality commit at height {} (round {}, {} signatures)", CLR_CYAN, CLR_RESET, commit.height, commit.round, commit.signer_count());
+    }
+
+    let mempool = Mempool::new(state.params.mempool_max_size as usize);
+    // The base fee follows from the head header, so a restart resumes the fee market where the chain left it
+    let fee_market = match storage.get_header(h_init) {
+        Ok(Some(head)) => FeeMarket::after(&head, &state.params),
+        _ => FeeMarket::for_params(&state.params),
+    };
     let node = Arc::new(KortanaNode {
         consensus: Arc::new(Mutex::new(consensus)),
         state: Arc::new(Mutex::new(state)),
-        mempool: Arc::new(Mutex::new(Mempool::new(MEMPOOL_MAX_SIZE))),
-        fees: Arc::new(Mutex::new(FeeMarket::new())),
-        finality: Arc::new(Mutex::new(FinalityGadget::new())),
+        mempool: Arc::new(Mutex::new(mempool)),
+        fees: Arc::new(Mutex::new(fee_market)),
+        finality: Arc::new(Mutex::new(finality)),
+        evidence: Arc::new(Mutex::new(EvidencePool::new())),
         storage: storage.clone(),
         height: Arc::new(AtomicU64::new(h_init)),
         node_config: config.clone(),
+        chain_id: spec.chain_id,
+        chain_name: spec.name.clone(),
     });
     println!("{}OK{}", CLR_GREEN, CLR_RESET);
 
@@ -217,11 +343,16 @@ async fn main() {
     print!("{}[4/5] Spawning P2P Networking... {}", CLR_YELLOW, CLR_RESET);
     let (p2p_tx, p2p_rx) = tokio::sync::mpsc::channel(100);
     let (node_tx, mut node_rx) = tokio::sync::mpsc::channel(100);
+    let (penalty_tx, penalty_rx) = tokio::sync::mpsc::channel(100);
+    let (sync_cmd_tx, sync_cmd_rx) = tokio::sync::mpsc::channel(100);
+    let (sync_event_tx, mut sync_event_rx) = tokio::sync::mpsc::channel(100);
+    let p2p_storage = node.storage.clone();
     let p2p_config = config.clone();
-    let bootnodes = args.bootnodes.clone();
+    // The spec's bootnodes, then any given on the command line
+    let bootnodes: Vec<String> = spec.bootnodes.iter().chain(&args.bootnodes).cloned().collect();
     
     tokio::spawn(async move {
-        let mut network = kortana_blockchain_rust::network::p2p::KortanaNetwork::new(p2p_rx, node_tx).await.expect("Failed to create P2P network");
+        let mut network = kortana_blockchain_rust::network::p2p::KortanaNetwork::new(p2p_rx, node_tx, penalty_rx, sync_cmd_rx, sync_event_tx, p2p_storage).await.expect("Failed to create P2P network");
         for bn in bootnodes {
             if let Ok(addr) = bn.parse() { network.add_bootnode(addr); }
         }
@@ -237,8 +368,10 @@ async fn main() {
         node.consensus.clone(),
         p2p_tx.clone(),
         node.height.clone(),
-        CHAIN_ID,
+        spec.chain_id,
     ));
+    let chain_events = rpc_handler.chain_events.clone();
+    let sync_progress = rpc_handler.sync_progress.clone();
 
     let rpc_addr = config.rpc_addr.clone();
     let rpc_node = node.clone();
@@ -290,8 +423,8 @@ async fn main() {
                     ("HTTP/1.1 200 OK\r\nAccess-Control-Allow-Methods: POST, GET, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type, Authorization\r\nContent-Length: 0\r\n\r\n".to_string(), "OPTIONS".to_string())
                 } else if req_body_str.starts_with("GET") {
                     let status_json = serde_json::json!({
-                        "status": "online", "node": "Kortana Mainnet", "version": "1.1.0",
-                        "chain_id": CHAIN_ID, "height": _task_node.height.load(Ordering::Relaxed)
+                        "status": "online", "node": format!("Kortana {}", _task_node.chain_name), "version": NODE_VERSION,
+                        "chain_id": _task_node.chain_id, "height": _task_node.height.load(Ordering::Relaxed)
                     }).to_string();
                     (format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", status_json.len(), status_json), "HTTP_GET".to_string())
                 } else if let Some(header_end) = req_body_str.find("\r\n\r\n") {
@@ -343,38 +476,138 @@ async fn main() {
     println!("{}LIVE{}", CLR_GREEN, CLR_RESET);
 
     // 6. Main Execution Loop
-    let mut interval = tokio::time::interval(Duration::from_secs(BLOCK_TIME_SECS));
+    let mut slot_clock = SlotClock::new(&node.state.lock().unwrap().params);
     let mut current_slot: u64 = if h_init > 0 {
         node.storage.get_block(h_init).ok().flatten().map(|b| b.header.slot).unwrap_or(0)
     } else { 0 };
-    let mut max_seen_height = h_init;
+
+    let mut sync_interval = tokio::time::interval(Duration::from_secs(1));
+    let mut sync = SyncEngine::new(node.storage.clone());
+    let genesis_header = node.storage.get_header(0).ok().flatten().expect("Genesis header missing");
+    let genesis_hash = genesis_header.hash();
+    let mut snap_pending = args.snap_sync.filter(|_| h_init == 0);
+    let mut snap: Option<SnapSync> = None;
+
+    // BFT finality: rounds run for the lowest height that is not final yet
+    let signer = Some((node_addr, BlsSecretKey::from_seed(&node.node_config.validator_private_key)));
+    let (timeout_tx, mut timeout_rx) = tokio::sync::mpsc::channel(100);
+    let mut bft = RoundState::new(node.chain_id, h_init + 1, &node.consensus.lock().unwrap().bft_validators(), signer.clone());
+    let mut bft_out = bft.start();
+    // Commits whose block we have not executed yet, by height
+    let mut pending_commits: std::collections::BTreeMap<u64, FinalityCommit> = std::collections::BTreeMap::new();
+    // PoH stream of this node; the entries since the head go into the next block we propose
+    let mut poh: Option<PohRecorder> = None;
 
     println!("\n{}--- NODE OPERATIONAL - HEIGHT {} ---{}\n", CLR_GREEN, h_init, CLR_RESET);
 
     loop {
+        // Drive BFT: vote on our canonical block, broadcast and schedule what the round
+        // state asks for, and finalize decided blocks once they have been executed.
+        loop {
+            if let Ok(Some(header)) = node.storage.get_header(bft.height) {
+                bft_out.extend(bft.add_candidate(header.hash()));
+            }
+            let mut finalized = None;
+            for output in std::mem::take(&mut bft_out) {
+                match output {
+                    BftOutput::Proposal(p) => { let _ = p2p_tx.send(NetworkMessage::Proposal(p)).await; }
+                    BftOutput::Vote(v) => { let _ = p2p_tx.send(NetworkMessage::Vote(v)).await; }
+                    BftOutput::Timeout(t) => {
+                        let timeout_tx = timeout_tx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(t.after).await;
+                            let _ = timeout_tx.send(t).await;
+                        });
+                    }
+                    BftOutput::Commit(c) => { pending_commits.insert(c.height, c); }
+                }
+            }
+            let heights: Vec<u64> = pending_commits.keys().rev().copied().collect();
+            for h in heights {
+                if h < bft.height {
+                    pending_commits.remove(&h);
+                    continue;
+                }
+                let commit = pending_commits[&h].clone();
+                match apply_commit(&node, &mut block_tree, &chain_events, &commit) {
+                    Ok(()) => {
+                        println!("  {}✅ Block {} Finalized (round {}, {} precommits){}", CLR_GREEN, h, commit.round, commit.signer_count(), CLR_RESET);
+                        finalized = Some(h);
+                        break;
+                    }
+                    // Not executed yet; sync or gossip will bring the block
+                    Err(ImportError::UnknownBlock(_)) => {}
+                    Err(e) => {
+                        println!("{}[BFT]{} Cannot finalize block {}: {}", CLR_RED, CLR_RESET, h, e);
+                        pending_commits.remove(&h);
+                    }
+                }
+            }
+            match finalized {
+                Some(h) => {
+                    pending_commits.retain(|height, _| *height > h);
+                    bft = RoundState::new(node.chain_id, h + 1, &node.consensus.lock().unwrap().bft_validators(), signer.clone());
+                    bft_out = bft.start();
+                }
+                None => break,
+            }
+        }
+
         tokio::select! {
-            _ = interval.tick() => {
+            Some(timeout) = timeout_rx.recv() => {
+                bft_out.extend(bft.on_timeout(timeout));
+            }
+
+            _ = slot_clock.tick() => {
                 current_slot += 1;
+                let next_height = node.height.load(Ordering::SeqCst) + 1;
+                let params = {
+                    let state = node.state.lock().unwrap();
+                    if let Some(plan) = state.governance.upgrade_needed(next_height, NODE_VERSION) {
+                        halt_for_upgrade(&plan.version, plan.height);
+                    }
+                    state.params.clone()
+                };
+                // Slot length and mempool cap follow governance from the slot after a change commits
+                if slot_clock.follow(&params) {
+                    println!("{}[GOVERNANCE]{} Slot length is now {}s", CLR_CYAN, CLR_RESET, params.block_time_secs);
+                }
+                node.mempool.lock().unwrap().set_max_size(params.mempool_max_size as usize);
                 let mut consensus = node.consensus.lock().unwrap();
                 consensus.current_slot = current_slot;
                 
-                if let Some(leader) = consensus.get_leader(current_slot, consensus.finalized_hash) {
-                    consensus.advance_era(current_slot);
-                    
-                    if leader == node_addr { 
+                // The block proves its VRF over the head's output, and the PoH stream
+                // restarts from the head's tail whenever the head moves
+                let head = node.storage.get_header(node.height.load(Ordering::SeqCst)).ok().flatten()
+                    .filter(|h| h.hash() == consensus.head_hash);
+                if let Some(head) = &head {
+                    if !poh.as_ref().is_some_and(|p| p.is_on(&consensus.head_hash)) {
+                        poh = Some(PohRecorder::new(head));
+                    }
+                }
+                // Earlier slots are ticked now; this one after any transactions we record in it
+                if let Some(recorder) = poh.as_mut() {
+                    recorder.tick_to(current_slot - 1);
+                }
+                let head_seed = head.as_ref().map(|h| h.vrf_output);
+                if let Some((seed, leader)) = head_seed.and_then(|seed| consensus.get_leader(current_slot).map(|l| (seed, l))) {
+                    if leader == node_addr && sync.is_syncing() {
+                        println!("{}[Slot {}]{} Skipping proposal while syncing", CLR_YELLOW, current_slot, CLR_RESET);
+                    } else if leader == node_addr {
                         println!("{}[Slot {}]{} 👑 Proposing Mainnet Block...", CLR_YELLOW, current_slot, CLR_RESET);
                         
+                        let block_gas_limit = node.state.lock().unwrap().params.block_gas_limit;
                         let mut mempool = node.mempool.lock().unwrap();
-                        let txs = mempool.select_transactions(GAS_LIMIT_PER_BLOCK);
+                        let txs = mempool.select_transactions(block_gas_limit);
                         
                         let timestamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
-                        let fees = node.fees.lock().unwrap();
+                        let fees = FeeMarket::after(head.as_ref().expect("A seed implies a head"), &node.state.lock().unwrap().params);
                         let mut header = kortana_blockchain_rust::types::block::BlockHeader {
-                            version: 1,
+                            version: kortana_blockchain_rust::types::block::HEADER_VERSION,
                             height: node.height.load(Ordering::SeqCst) + 1,
                             slot: current_slot,
                             timestamp,
-                            parent_hash: consensus.finalized_hash,
+                            parent_hash: consensus.head_hash,
                             state_root: [0u8; 32],
                             transactions_root: [0u8; 32],
                             receipts_root: [0u8; 32],
@@ -382,106 +615,476 @@ async fn main() {
                             poh_sequence: 0,
                             proposer: node_addr,
                             gas_used: 0,
-                            gas_limit: GAS_LIMIT_PER_BLOCK,
+                            gas_limit: block_gas_limit,
                             base_fee: fees.base_fee,
                             vrf_output: [0u8; 32],
+                            vrf_proof: Vec::new(),
+                            logs_bloom: Default::default(),
+                            evidence_root: [0u8; 32],
+                            last_commit_hash: [0u8; 32],
                         };
 
+                        header.prove_vrf(&node.node_config.validator_private_key, &seed)
+                            .expect("Validator key is a valid VRF key");
+
                         let mut state = node.state.lock().unwrap();
                         let mut processor = kortana_blockchain_rust::core::processor::BlockProcessor::new(&mut state, fees.clone());
+                        if let Err(e) = processor.begin_block(&header) {
+                            println!("{}[UPGRADE] {} - Halting.{}", CLR_RED, e, CLR_RESET);
+                            std::process::exit(1);
+                        }
+                        let mut included = Vec::new();
                         let mut receipts = Vec::new();
+                        let recorder = poh.as_mut().expect("PoH stream runs on the head");
 
-                         for tx in &txs {
+                        for tx in txs {
                             let tx_hash = tx.hash();
                             match processor.process_transaction(tx.clone(), &header) {
                                 Ok(receipt) => {
-                                    receipts.push(receipt.clone());
-                                    
-                                    // Senior Architect Fix: Explicitly index every transaction metadata
-                                    let _ = node.storage.put_transaction(tx);
-                                    let _ = node.storage.put_receipt(&receipt);
-                                    let _ = node.storage.put_index(&tx.from, tx_hash);
-                                    let _ = node.storage.put_index(&tx.to, tx_hash);
-                                    let _ = node.storage.put_global_transaction(tx_hash);
-
-                                    mempool.remove_transaction(&tx_hash);
+                                    recorder.record(&tx_hash);
+                                    receipts.push(receipt);
+                                    included.push(tx);
                                 }
                                 Err(e) => {
                                     println!("{}[PROCESSOR]{} Transaction 0x{} failed validation: {}. Removing from mempool.", CLR_CYAN, CLR_RESET, hex::encode(tx_hash), e);
-                                    mempool.remove_transaction(&tx_hash);
                                 }
                             }
+                            mempool.remove_transaction(&tx_hash);
                         }
 
-                        let (tx_root, receipt_root) = kortana_blockchain_rust::types::block::Block::calculate_merkle_roots(&txs, &receipts);
+                        let evidence = node.evidence.lock().unwrap().select(&processor.state.staking, &processor.state.params, node.chain_id, header.height);
+                        if let Err(e) = processor.apply_evidence(&evidence, &header) {
+                            println!("{}[STORAGE] Selected evidence failed to apply: {} - Halting node for safety.{}", CLR_RED, e, CLR_RESET);
+                            panic!("Evidence application failed at height {}: {}", header.height, e);
+                        }
+
+                        // Carry the newest commit we hold so its signers are credited; one the
+                        // state cannot record (already recorded, or signed by another set) is left out
+                        let last_commit = node.storage.latest_finality_commit().ok().flatten()
+                            .filter(|c| processor.apply_last_commit(Some(c), &header).is_ok());
+                        processor.end_block(&header);
+
+                        let (tx_root, receipt_root) = kortana_blockchain_rust::types::block::Block::calculate_merkle_roots(&included, &receipts);
                         header.state_root = state.calculate_root();
+                        header.evidence_root = kortana_blockchain_rust::types::block::Block::calculate_evidence_root(&evidence);
+                        header.last_commit_hash = kortana_blockchain_rust::types::block::Block::calculate_last_commit_hash(last_commit.as_ref());
                         header.transactions_root = tx_root;
                         header.receipts_root = receipt_root;
                         header.gas_used = receipts.iter().map(|r| r.gas_used).sum();
+                        header.logs_bloom = kortana_blockchain_rust::types::bloom::Bloom::from_receipts(&receipts);
+                        recorder.tick_to(current_slot);
+                        (header.poh_hash, header.poh_sequence) = recorder.tail();
+                        let poh_entries = recorder.entries().to_vec();
 
-                        let mut block = kortana_blockchain_rust::types::block::Block { header, transactions: txs, signature: vec![] };
+                        let mut block = kortana_blockchain_rust::types::block::Block { header, transactions: included, signature: vec![], evidence, last_commit, poh_entries };
                         block.sign(&node.node_config.validator_private_key);
-                        
+
                         let block_hash = block.header.hash();
-                        consensus.finalized_hash = block_hash;
-                        
-                        node.height.fetch_add(1, Ordering::SeqCst);
                         let h = block.header.height;
-                        let _ = node.storage.put_block(&block);
-                        let _ = node.storage.put_state(h, &state);
 
-                        // Senior Architect Fix: Map transaction hashes to their block locations
-                        for (idx, tx) in block.transactions.iter().enumerate() {
-                            let _ = node.storage.put_transaction_location(&tx.hash(), h, &block_hash, idx);
+                        // Block, receipts, indexes and post-state land atomically or not at all
+                        if let Err(e) = node.storage.commit_block(&block, &receipts, &state) {
+                            println!("{}[STORAGE] Failed to commit block {}: {} - Halting node for safety.{}", CLR_RED, h, e, CLR_RESET);
+                            panic!("Block commit failed at height {}: {}", h, e);
                         }
-                        
-                        let _ = p2p_tx.send(kortana_blockchain_rust::network::messages::NetworkMessage::NewBlock(block)).await;
-                        println!("  {}✅ Block {} Finalized ({} txs){}", CLR_GREEN, h, receipts.len(), CLR_RESET);
+
+                        consensus.head_hash = block_hash;
+                        node.height.fetch_add(1, Ordering::SeqCst);
+                        apply_included_evidence(&mut node.evidence.lock().unwrap(), &mut consensus, &block, &state.params);
+                        consensus.sync_validators(&state.staking);
+                        *node.fees.lock().unwrap() = FeeMarket::after(&block.header, &state.params);
+                        if let Err(e) = block_tree.insert(block.clone()) {
+                            println!("{}[FORK]{} Own block {} not added to block tree: {}", CLR_CYAN, CLR_RESET, h, e);
+                        }
+                        if let Err(e) = chain::prune(&mut block_tree, &node.storage, &consensus) {
+                            println!("{}[FORK]{} Failed to prune block tree: {}", CLR_CYAN, CLR_RESET, e);
+                        }
+
+                        let _ = p2p_tx.send(kortana_blockchain_rust::network::messages::NetworkMessage::NewBlock(Box::new(block))).await;
+                        println!("  {}✅ Block {} Proposed ({} txs){}", CLR_GREEN, h, receipts.len(), CLR_RESET);
                     }
                 }
+                if let Some(recorder) = poh.as_mut() {
+                    recorder.tick_to(current_slot);
+                }
             }
 
-            Some(msg) = node_rx.recv() => {
-                match msg {
+            _ = sync_interval.tick() => {
+                let height = node.height.load(Ordering::SeqCst);
+                let requests = match snap.as_mut() {
+                    Some(snap) => snap.next_requests(&sync.peers_at(snap.pivot_height), std::time::Instant::now()),
+                    None => sync.next_requests(height, std::time::Instant::now()),
+                };
+                for (peer, request) in requests {
+                    let _ = sync_cmd_tx.send(SyncCommand { peer, request }).await;
+                }
+                *sync_progress.lock().unwrap() = sync.progress(height);
+            }
+
+            Some(event) = sync_event_rx.recv() => {
+                let height = node.height.load(Ordering::SeqCst);
+                match event {
+                    SyncEvent::PeerConnected(peer) => {
+                        let _ = sync_cmd_tx.send(SyncCommand { peer, request: SyncRequest::Status }).await;
+                    }
+                    SyncEvent::PeerDisconnected(peer) => {
+                        if let Some(snap) = snap.as_mut() {
+                            snap.on_request_failed(&peer);
+                        }
+                        sync.on_peer_disconnected(&peer);
+                    }
+                    SyncEvent::RequestFailed { peer } => {
+                        if let Some(snap) = snap.as_mut() {
+                            snap.on_request_failed(&peer);
+                        }
+                        sync.on_request_failed(&peer);
+                    }
+                    SyncEvent::Response { peer, response: SyncResponse::Status { genesis_hash: peer_genesis, head_height, .. } } => {
+                        if !sync.on_status(&peer, peer_genesis, head_height, genesis_hash, height) {
+                            let _ = penalty_tx.try_send(PeerPenalty { peer, penalty: 100, reason: "different genesis".to_string() });
+                        } else if let Some(checkpoint) = snap_pending.filter(|c| head_height >= c.height) {
+                            snap_pending = None;
+                            snap = Some(SnapSync::new(genesis_header.clone(), checkpoint));
+                        }
+                    }
+                    SyncEvent::Response { peer, response } if snap.is_some() => {
+                        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+                        let snap_sync = snap.as_mut().unwrap();
+                        let result = snap_sync.on_response(&peer, response, &node.consensus.lock().unwrap(), &node.storage, now);
+                        if let Err(e) = result {
+                            println!("{}[SNAP]{} {}", CLR_CYAN, CLR_RESET, e);
+                            let _ = penalty_tx.try_send(PeerPenalty { peer, penalty: 20, reason: e });
+                        }
+                        if snap_sync.is_done() {
+                            let snap_sync = snap.take().unwrap();
+                            match install_snap_sync(&node, &mut block_tree, snap_sync, genesis_hash) {
+                                Ok(h) => {
+                                    println!("{}[SNAP]{} State installed at height {}, syncing remaining blocks", CLR_GREEN, CLR_RESET, h);
+                                    bft = RoundState::new(node.chain_id, h + 1, &node.consensus.lock().unwrap().bft_validators(), signer.clone());
+                                    bft_out = bft.start();
+                                }
+                                Err(e) => println!("{}[SNAP]{} Snap sync failed, falling back to full sync: {}", CLR_RED, CLR_RESET, e),
+                            }
+                        }
+                    }
+                    SyncEvent::Response { peer, response: SyncResponse::Blocks { start_height, blocks } } => {
+                        if let Err(e) = sync.on_blocks(&peer, start_height, blocks) {
+                            println!("{}[SYNC]{} {}", CLR_CYAN, CLR_RESET, e);
+                            let _ = penalty_tx.try_send(PeerPenalty { peer: peer.clone(), penalty: 20, reason: e });
+                        }
+                        // Execute everything that now connects to our head
+                        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+                        for block in sync.take_ready(node.height.load(Ordering::SeqCst)) {
+                            let h = block.header.height;
+                            let block_hash = block.header.hash();
+                            let rejected = match process_block(&node, &mut block_tree, &chain_events, block.clone(), now, "SYNC") {
+                                Ok(rejected) => rejected,
+                                Err(e) if e.is_invalid() => vec![(block, e)],
+                                Err(e) => {
+                                    println!("{}[SYNC]{} Could not attach block {}: {}", CLR_CYAN, CLR_RESET, h, e);
+                                    sync.reset_from(h);
+                                    break;
+                                }
+                            };
+                            if !rejected.is_empty() {
+                                quarantine_rejected(&node, &penalty_tx, rejected, block_hash, Some(&peer), now);
+                                sync.reset_from(h);
+                                break;
+                            }
+                        }
+                        let height = node.height.load(Ordering::SeqCst);
+                        sync.check_complete(height);
+                        *sync_progress.lock().unwrap() = sync.progress(height);
+                    }
+                    SyncEvent::Response { peer, response: SyncResponse::Error(e) } => {
+                        println!("{}[SYNC]{} Peer {} could not serve request: {}", CLR_CYAN, CLR_RESET, peer, e);
+                        sync.on_request_failed(&peer);
+                    }
+                    // Snap answers arriving after snap sync has ended
+                    SyncEvent::Response { .. } => {}
+                }
+            }
+
+            Some(inbound) = node_rx.recv() => {
+                match inbound.message {
                     kortana_blockchain_rust::network::messages::NetworkMessage::NewBlock(block) => {
+                        let block = *block;
                         let h = block.header.height;
-                        if h > max_seen_height {
-                             max_seen_height = h;
-                             if h == node.height.load(Ordering::SeqCst) + 1 {
-                                 let mut state = node.state.lock().unwrap();
-                                 let mut fees = node.fees.lock().unwrap();
-                                 let mut processor = kortana_blockchain_rust::core::processor::BlockProcessor::new(&mut state, fees.clone());
-                                 if let Ok(receipts) = processor.validate_block(&block) {
-                                     *fees = processor.fee_market;
-                                     node.height.fetch_add(1, Ordering::SeqCst);
-                                     let _ = node.storage.put_block(&block);
-                                     let _ = node.storage.put_state(h, &state);
-
-                                     // Index transactions from the peer's block
-                                     for (tx, receipt) in block.transactions.iter().zip(receipts.iter()) {
-                                         let tx_hash = tx.hash();
-                                         let _ = node.storage.put_transaction(tx);
-                                         let _ = node.storage.put_receipt(receipt);
-                                         let _ = node.storage.put_index(&tx.from, tx_hash);
-                                         let _ = node.storage.put_index(&tx.to, tx_hash);
-                                         let _ = node.storage.put_global_transaction(tx_hash);
-                                     }
-
-                                     println!("{}[P2P]{} Applied and Indexed external block {}", CLR_CYAN, CLR_RESET, h);
-                                 }
-                             }
+                        let block_hash = block.header.hash();
+                        if node.storage.is_quarantined(&block_hash).unwrap_or(false) {
+                            continue;
+                        }
+                        if snap.is_some() {
+                            if let Some(peer) = &inbound.peer {
+                                sync.note_peer_height(peer, h, node.height.load(Ordering::SeqCst));
+                            }
+                            continue;
                         }
+                        let double_proposal = node.evidence.lock().unwrap().observe_block(&block);
+                        report_evidence(&node, &p2p_tx, double_proposal).await;
+                        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+                        let rejected = match process_block(&node, &mut block_tree, &chain_events, block.clone(), now, "P2P") {
+                            Ok(rejected) => rejected,
+                            Err(e) if e.is_invalid() => vec![(block, e)],
+                            Err(e) => {
+                                // A block from beyond our head means the relaying peer is ahead of us
+                                if let (Some(peer), ImportError::MissingParent(_)) = (&inbound.peer, &e) {
+                                    sync.note_peer_height(peer, h, node.height.load(Ordering::SeqCst));
+                                }
+                                println!("{}[P2P]{} Deferred block {}: {}", CLR_CYAN, CLR_RESET, h, e);
+                                Vec::new()
+                            }
+                        };
+                        quarantine_rejected(&node, &penalty_tx, rejected, block_hash, inbound.peer.as_deref(), now);
                     }
                     kortana_blockchain_rust::network::messages::NetworkMessage::NewTransaction(tx) => {
                         node.mempool.lock().unwrap().add(tx);
                     }
-                    _ => {}
+                    NetworkMessage::Proposal(proposal) => {
+                        match bft.on_proposal(proposal) {
+                            Ok(out) => bft_out.extend(out),
+                            Err(e) => penalize_consensus_peer(&penalty_tx, inbound.peer, e),
+                        }
+                    }
+                    NetworkMessage::Vote(vote) if vote.height > bft.height => {
+                        // We are behind: collect precommits so we can adopt the network's commits
+                        let validators = node.consensus.lock().unwrap().bft_validators();
+                        let added = node.finality.lock().unwrap().add_vote(&vote, &validators, node.chain_id);
+                        match added {
+                            Ok(commit) => {
+                                if let Some(commit) = commit {
+                                    pending_commits.insert(commit.height, commit);
+                                }
+                                let duplicate = node.evidence.lock().unwrap().observe_vote(&vote);
+                                report_evidence(&node, &p2p_tx, duplicate).await;
+                            }
+                            Err(e) => penalize_consensus_peer(&penalty_tx, inbound.peer, e),
+                        }
+                    }
+                    NetworkMessage::Vote(vote) => {
+                        let verified = vote.height == bft.height;
+                        match bft.on_vote(vote.clone()) {
+                            Ok(out) => {
+                                bft_out.extend(out);
+                                if verified {
+                                    let duplicate = node.evidence.lock().unwrap().observe_vote(&vote);
+                                    report_evidence(&node, &p2p_tx, duplicate).await;
+                                }
+                            }
+                            Err(e) => penalize_consensus_peer(&penalty_tx, inbound.peer, e),
+                        }
+                    }
+                    NetworkMessage::Evidence(evidence) => {
+                        let height = node.height.load(Ordering::SeqCst) + 1;
+                        let added = {
+                            let state = node.state.lock().unwrap();
+                            node.evidence.lock().unwrap().add(evidence, &state.staking, &state.params, node.chain_id, height)
+                        };
+                        if let Err(e) = added {
+                            penalize_consensus_peer(&penalty_tx, inbound.peer, format!("invalid evidence: {}", e));
+                        }
+                    }
                 }
             }
         }
     }
 }
 
+/// Installs the state downloaded by snap sync and moves the node's head to the pivot.
+fn install_snap_sync(node: &KortanaNode, block_tree: &mut BlockTree, snap: SnapSync, genesis_hash: [u8; 32]) -> Result<u64, String> {
+    let checkpoint = snap.checkpoint;
+    let archive = snap.finish(node.chain_id)?;
+    let info = node.storage.install_snap_state(&archive, genesis_hash, &checkpoint)?;
+    let mut consensus = node.consensus.lock().unwrap();
+    consensus.sync_validators(&archive.state.staking);
+    *node.fees.lock().unwrap() = FeeMarket::after(&archive.head_block.header, &archive.state.params);
+    *node.state.lock().unwrap() = archive.state;
+    consensus.head_hash = info.block_hash;
+    consensus.finalized_hash = info.block_hash;
+    consensus.finalized_height = info.height;
+    *block_tree = BlockTree::new(info.height, info.block_hash);
+    node.height.store(info.height, Ordering::SeqCst);
+    Ok(info.height)
+}
+
+/// Runs `block` through the block tree and fork choice and applies whatever becomes
+/// canonical. Returns the blocks rejected along the way; an error means `block` itself
+/// could not be placed in the tree.
+fn process_block(
+    node: &KortanaNode,
+    block_tree: &mut BlockTree,
+    chain_events: &Mutex<ChainEventLog>,
+    block: kortana_blockchain_rust::types::block::Block,
+    now: u64,
+    source: &str,
+) -> Result<Vec<(kortana_blockchain_rust::types::block::Block, ImportError)>, ImportError> {
+    let h = block.header.height;
+    let mut consensus = node.consensus.lock().unwrap();
+    let mut state = node.state.lock().unwrap();
+    let mut fees = node.fees.lock().unwrap();
+    let update = match chain::accept_block(block_tree, &node.storage, &consensus, &block, now) {
+        Ok(true) => chain::update_head(block_tree, &node.storage, &mut consensus, &mut state, &mut fees, now),
+        Ok(false) => return Ok(Vec::new()),
+        Err(e) => Err(e),
+    };
+    let update = match update {
+        Ok(update) => update,
+        Err(ImportError::Storage(e)) => {
+            println!("{}[STORAGE] Failed to commit block {}: {} - Halting node for safety.{}", CLR_RED, h, e, CLR_RESET);
+            panic!("Block commit failed at height {}: {}", h, e);
+        }
+        Err(ImportError::UpgradeNeeded { version, height }) => halt_for_upgrade(&version, height),
+        Err(e) => return Err(e),
+    };
+    Ok(apply_head_update(node, block_tree, chain_events, &mut consensus, &state, update, source))
+}
+
+/// Makes a finality commit's block canonical and final and stores the commit next to it.
+fn apply_commit(
+    node: &KortanaNode,
+    block_tree: &mut BlockTree,
+    chain_events: &Mutex<ChainEventLog>,
+    commit: &FinalityCommit,
+) -> Result<(), ImportError> {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let mut consensus = node.consensus.lock().unwrap();
+    let validators = consensus.bft_validators();
+    commit.verify(node.chain_id, &validators).map_err(ImportError::Execution)?;
+    let update = {
+        let mut state = node.state.lock().unwrap();
+        let mut fees = node.fees.lock().unwrap();
+        match chain::finalize_block(block_tree, &node.storage, &mut consensus, &mut state, &mut fees, commit.block_hash, commit.height, now) {
+            Err(ImportError::UpgradeNeeded { version, height }) => halt_for_upgrade(&version, height),
+            result => result?,
+        }
+    };
+    node.storage.put_finality_commit(commit).map_err(ImportError::Storage)?;
+    node.finality.lock().unwrap().process_commit(commit, &validators);
+    let state = node.state.lock().unwrap();
+    for (bad, e) in apply_head_update(node, block_tree, chain_events, &mut consensus, &state, update, "BFT") {
+        println!("{}[BFT]{} Rejected block {} while finalizing: {}", CLR_CYAN, CLR_RESET, bad.header.height, e);
+    }
+    Ok(())
+}
+
+/// Stops the node at the height an upgrade plan requires a newer release from. Every
+/// block below it is committed, so the new release resumes where this one stopped.
+fn halt_for_upgrade(version: &str, height: u64) -> ! {
+    println!("{}[UPGRADE] UPGRADE NEEDED: release {} is required from height {}; this node runs {}. Halting.{}",
+        CLR_RED, version, height, NODE_VERSION, CLR_RESET);
+    std::process::exit(1);
+}
+
+/// Publishes the effects of a head change: node height, reorg events, orphaned
+/// transactions and evidence, and the validator set and jail sentences of the new head
+/// state. Returns the blocks rejected along the way.
+fn apply_head_update(
+    node: &KortanaNode,
+    block_tree: &BlockTree,
+    chain_events: &Mutex<ChainEventLog>,
+    consensus: &mut ConsensusEngine,
+    state: &kortana_blockchain_rust::state::account::State,
+    update: chain::HeadUpdate,
+    source: &str,
+) -> Vec<(kortana_blockchain_rust::types::block::Block, ImportError)> {
+    if let Some(head) = block_tree.height_of(&consensus.head_hash) {
+        node.height.store(head, Ordering::SeqCst);
+    }
+    for b in &update.imported {
+        println!("{}[{}]{} Applied and Indexed external block {}", CLR_CYAN, source, CLR_RESET, b.header.height);
+    }
+    if let Some(reorg) = update.reorg {
+        println!("{}[FORK]{} Reorg of depth {} at height {} -> new head 0x{} (height {})", CLR_YELLOW, CLR_RESET,
+            reorg.depth, reorg.common_ancestor_height, hex::encode(reorg.new_head), reorg.new_height);
+        chain_events.lock().unwrap().push(reorg);
+    }
+    // Orphaned transactions that did not make it into the new branch go back to the mempool
+    let included: std::collections::HashSet<[u8; 32]> = update.imported.iter()
+        .flat_map(|b| b.transactions.iter().map(|tx| tx.hash()))
+        .collect();
+    let mut mempool = node.mempool.lock().unwrap();
+    let mut evidence = node.evidence.lock().unwrap();
+    for b in update.orphaned {
+        b.evidence.into_iter().for_each(|e| evidence.restore(e));
+        for tx in b.transactions {
+            if !included.contains(&tx.hash()) {
+                mempool.add(tx);
+            }
+        }
+    }
+    for b in &update.imported {
+        apply_included_evidence(&mut evidence, consensus, b, &state.params);
+    }
+    consensus.sync_validators(&state.staking);
+    update.rejected
+}
+
+/// Drops the evidence `block` included from the pool and records the slashings locally;
+/// the stake was already burned and the offenders jailed when the block was executed.
+fn apply_included_evidence(
+    pool: &mut EvidencePool,
+    consensus: &mut ConsensusEngine,
+    block: &kortana_blockchain_rust::types::block::Block,
+    params: &ChainParams,
+) {
+    pool.remove_included(&block.evidence);
+    for e in &block.evidence {
+        println!("{}[SLASH]{} {} slashed for {:?} at height {}", CLR_RED, CLR_RESET, e.offender(), e.reason(), block.header.height);
+        consensus.slash_validator(e.offender(), e.reason(), block.header.slot, params);
+    }
+    pool.prune(block.header.height);
+}
+
+/// Queues evidence we detected ourselves and gossips it to the other validators.
+async fn report_evidence(node: &KortanaNode, p2p_tx: &tokio::sync::mpsc::Sender<NetworkMessage>, evidence: Option<Evidence>) {
+    let evidence = match evidence {
+        Some(e) => e,
+        None => return,
+    };
+    let height = node.height.load(Ordering::SeqCst) + 1;
+    let added = {
+        let state = node.state.lock().unwrap();
+        node.evidence.lock().unwrap().add(evidence.clone(), &state.staking, &state.params, node.chain_id, height)
+    };
+    match added {
+        Ok(true) => {
+            println!("{}[SLASH]{} Detected {:?} by {}", CLR_RED, CLR_RESET, evidence.reason(), evidence.offender());
+            let _ = p2p_tx.send(NetworkMessage::Evidence(evidence)).await;
+        }
+        Ok(false) => {}
+        Err(e) => println!("{}[SLASH]{} Ignoring conflicting messages by {}: {}", CLR_CYAN, CLR_RESET, evidence.offender(), e),
+    }
+}
+
+fn penalize_consensus_peer(penalty_tx: &tokio::sync::mpsc::Sender<PeerPenalty>, peer: Option<String>, reason: String) {
+    println!("{}[BFT]{} Dropped consensus message: {}", CLR_CYAN, CLR_RESET, reason);
+    if let Some(peer) = peer {
+        let _ = penalty_tx.try_send(PeerPenalty { peer, penalty: 20, reason });
+    }
+}
+
+/// Quarantines rejected blocks; `peer` is penalized if it sent the block `received`.
+fn quarantine_rejected(
+    node: &KortanaNode,
+    penalty_tx: &tokio::sync::mpsc::Sender<PeerPenalty>,
+    rejected: Vec<(kortana_blockchain_rust::types::block::Block, ImportError)>,
+    received: [u8; 32],
+    peer: Option<&str>,
+    now: u64,
+) {
+    for (bad, e) in rejected {
+        println!("{}[P2P]{} Rejected block {}: {}", CLR_CYAN, CLR_RESET, bad.header.height, e);
+        let peer = peer.filter(|_| bad.header.hash() == received).map(str::to_string);
+        let entry = kortana_blockchain_rust::storage::QuarantinedBlock { block: bad, reason: e.to_string(), peer: peer.clone(), received_at: now };
+        if let Err(qe) = node.storage.quarantine_block(&entry) {
+            println!("{}[STORAGE]{} Failed to quarantine block: {}", CLR_RED, CLR_RESET, qe);
+        }
+        if let Some(peer) = peer {
+            let _ = penalty_tx.try_send(PeerPenalty { peer, penalty: e.peer_penalty(), reason: e.to_string() });
+        }
+    }
+}
+
 fn generate_wallet() {
     let priv_key = SigningKey::random(&mut rand::thread_rng());
     let addr = Address::from_pubkey(&priv_key.verifying_key().to_sec1_bytes());
@@ -491,11 +1094,109 @@ fn generate_wallet() {
     println!("{}----------------------------------{}\n", CLR_BOLD, CLR_RESET);
 }
 
-fn run_self_test() {
-    println!("\n{}--- KORTANA PROTOCOL V1.1 SELF-TEST ---{}", CLR_BOLD, CLR_RESET);
-    let state = kortana_blockchain_rust::core::genesis::create_genesis_state();
+fn run_migrate_db(db_path: &str) {
+    println!("\n{}--- MIGRATING DATABASE {} ---{}", CLR_BOLD, db_path, CLR_RESET);
+    let storage = kortana_blockchain_rust::storage::Storage::new(db_path);
+    if !storage.has_legacy_data() {
+        println!("{}Nothing to migrate: database is already in the binary layout.{}", CLR_GREEN, CLR_RESET);
+        return;
+    }
+    match storage.migrate_legacy_json() {
+        Ok(report) => {
+            println!("Blocks: {}  Transactions: {}  Locations: {}  Receipts: {}", report.blocks, report.transactions, report.locations, report.receipts);
+            println!("States: {}  State roots: {}  Re-indexed transactions: {}", report.states, report.state_roots, report.reindexed_transactions);
+            if report.skipped.is_empty() {
+                println!("{}--- MIGRATION COMPLETE ---{}\n", CLR_GREEN, CLR_RESET);
+            } else {
+                for entry in &report.skipped {
+                    println!("{}  skipped {}{}", CLR_YELLOW, entry, CLR_RESET);
+                }
+                println!("{}--- MIGRATION INCOMPLETE: {} entries left in legacy format ---{}\n", CLR_RED, report.skipped.len(), CLR_RESET);
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            println!("{}Migration failed: {}{}", CLR_RED, e, CLR_RESET);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_reindex(db_path: &str) {
+    println!("\n{}--- REINDEXING DATABASE {} ---{}", CLR_BOLD, db_path, CLR_RESET);
+    let storage = kortana_blockchain_rust::storage::Storage::new(db_path);
+    if storage.has_legacy_data() {
+        println!("{}The database uses the old JSON layout. Run `migrate-db` instead.{}", CLR_RED, CLR_RESET);
+        std::process::exit(1);
+    }
+    match storage.reindex() {
+        Ok(report) => {
+            println!("Blocks: {}  Transactions: {}", report.blocks, report.transactions);
+            println!("{}--- REINDEX COMPLETE ---{}\n", CLR_GREEN, CLR_RESET);
+        }
+        Err(e) => {
+            println!("{}Reindex failed: {}{}", CLR_RED, e, CLR_RESET);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_snapshot_info(info: &kortana_blockchain_rust::storage::snapshot::SnapshotInfo) {
+    println!("Chain ID:   {}", info.chain_id);
+    println!("Height:     {}", info.height);
+    println!("Block Hash: 0x{}", hex::encode(info.block_hash));
+    println!("State Root: 0x{}", hex::encode(info.state_root));
+    println!("Accounts:   {}", info.accounts);
+    println!("Checkpoint: {}", info.checkpoint());
+}
+
+fn run_export_snapshot(db_path: &str, height: u64, out: &str, chain_id: u64) {
+    println!("\n{}--- EXPORTING SNAPSHOT AT HEIGHT {} ---{}", CLR_BOLD, height, CLR_RESET);
+    let storage = kortana_blockchain_rust::storage::Storage::new(db_path);
+    match storage.export_snapshot(height, chain_id, out) {
+        Ok(info) => {
+            print_snapshot_info(&info);
+            println!("{}--- SNAPSHOT WRITTEN TO {} ---{}\n", CLR_GREEN, out, CLR_RESET);
+        }
+        Err(e) => {
+            println!("{}Snapshot export failed: {}{}", CLR_RED, e, CLR_RESET);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_import_snapshot(db_path: &str, path: &str, spec: &ChainSpec, checkpoint: &Checkpoint) {
+    println!("\n{}--- IMPORTING SNAPSHOT {} ---{}", CLR_BOLD, path, CLR_RESET);
+    if let Some(parent) = std::path::Path::new(db_path).parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    let storage = kortana_blockchain_rust::storage::Storage::new(db_path);
+    let genesis_hash = genesis::create_genesis_block_for(spec, load_genesis_state(spec).calculate_root()).header.hash();
+    match storage.import_snapshot(path, spec.chain_id, genesis_hash, checkpoint) {
+        Ok(info) => {
+            print_snapshot_info(&info);
+            println!("{}--- SNAPSHOT VERIFIED AND IMPORTED: the node will resume at height {} ---{}\n", CLR_GREEN, info.height, CLR_RESET);
+        }
+        Err(e) => {
+            println!("{}Snapshot import failed: {}{}", CLR_RED, e, CLR_RESET);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Genesis state of `spec`; a spec whose genesis cannot be built stops the node.
+fn load_genesis_state(spec: &ChainSpec) -> State {
+    genesis::create_genesis_state_for(spec).unwrap_or_else(|e| {
+        println!("{}Cannot build the {} genesis: {}{}", CLR_RED, spec.name, e, CLR_RESET);
+        std::process::exit(1);
+    })
+}
+
+fn run_self_test(spec: &ChainSpec) {
+    println!("\n{}--- KORTANA PROTOCOL V1.1 SELF-TEST ({}) ---{}", CLR_BOLD, spec.name, CLR_RESET);
+    let state = load_genesis_state(spec);
     let root = state.calculate_root();
     println!("Genesis Root: 0x{} -> {}PASS{}", hex::encode(root), CLR_GREEN, CLR_RESET);
-    println!("Check CHAIN_ID: {} -> {}PASS{}", CHAIN_ID, CLR_GREEN, CLR_RESET);
+    println!("Check CHAIN_ID: {} -> {}PASS{}", state.params.chain_id, CLR_GREEN, CLR_RESET);
     println!("{}--- ALL TESTS PASSED ---{}\n", CLR_BOLD, CLR_RESET);
 }
@@ -46,6 +46,12 @@ impl Mempool {
         }
     }
 
+    /// Changes the cap on pending transactions. Shrinking it below the current size
+    /// drops nothing; new transactions are refused until the pool drains below it.
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+
     pub fn add(&mut self, tx: Transaction) -> bool {
         use std::io::Write;
         let hash = tx.hash();
@@ -1,15 +1,94 @@
 // File: src/network/messages.rs
 
 use serde::{Serialize, Deserialize};
-use crate::types::block::Block;
+use crate::address::Address;
+use crate::consensus::bft::{Proposal, Vote};
+use crate::consensus::evidence::Evidence;
+use crate::staking::StakingStore;
+use crate::state::trie::TrieNode;
+use crate::types::block::{Block, BlockHeader};
 use crate::types::transaction::Transaction;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum NetworkMessage {
-    NewBlock(Block),
+    NewBlock(Box<Block>),
     NewTransaction(Transaction),
-    SyncRequest { start_height: u64, end_height: u64 },
-    SyncResponse { blocks: Vec<Block> },
-    PreCommit { block_hash: [u8; 32], height: u64, round: u32, validator: crate::address::Address, signature: Vec<u8> },
-    Commit { block_hash: [u8; 32], height: u64, round: u32, validator: crate::address::Address, signature: Vec<u8> },
+    Proposal(Proposal),
+    Vote(Vote),
+    Evidence(Evidence),
+}
+
+/// A gossiped message together with the peer that relayed it to us, so the node can
+/// attribute invalid payloads.
+#[derive(Debug)]
+pub struct InboundMessage {
+    pub peer: Option<String>,
+    pub message: NetworkMessage,
+}
+
+/// Reputation penalty the node asks the network layer to apply to a peer.
+#[derive(Debug, Clone)]
+pub struct PeerPenalty {
+    pub peer: String,
+    pub penalty: i32,
+    pub reason: String,
+}
+
+/// Requests of the `/kortana/sync/1` request-response protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SyncRequest {
+    /// Ask for the peer's chain head.
+    Status,
+    /// Ask for up to `count` consecutive canonical blocks starting at `start_height`.
+    Blocks { start_height: u64, count: u64 },
+    /// Up to `count` consecutive headers with their proposer signatures.
+    Headers { start_height: u64, count: u64 },
+    /// Up to `max` accounts with keys in `[start, end]` of the state at `height`.
+    AccountRange { height: u64, start: Vec<u8>, end: Vec<u8>, max: u64 },
+    /// Up to `max` storage slots with keys in `[start, end]` of `address` at `height`.
+    StorageRange { height: u64, address: Address, start: Vec<u8>, end: Vec<u8>, max: u64 },
+    /// Contract code by code hash.
+    ByteCodes { hashes: Vec<[u8; 32]> },
+    /// Staking store at `height`.
+    Staking { height: u64 },
+}
+
+/// Trie entries in key order plus the proofs for both edges of the range. `complete`
+/// means every entry up to the requested end was returned; otherwise the range ends at
+/// the last entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateRange {
+    pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+    pub proof: Vec<TrieNode>,
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SyncResponse {
+    Status { genesis_hash: [u8; 32], head_height: u64, head_hash: [u8; 32] },
+    /// Blocks in height order; fewer than requested if the peer's chain ends earlier.
+    Blocks { start_height: u64, blocks: Vec<Block> },
+    Headers { start_height: u64, headers: Vec<(BlockHeader, Vec<u8>)> },
+    AccountRange(StateRange),
+    StorageRange(StateRange),
+    /// Codes in request order; unknown hashes are left out.
+    ByteCodes(Vec<Vec<u8>>),
+    Staking(Box<StakingStore>),
+    Error(String),
+}
+
+/// Outbound sync request the node asks the network layer to send.
+#[derive(Debug, Clone)]
+pub struct SyncCommand {
+    pub peer: String,
+    pub request: SyncRequest,
+}
+
+/// Sync protocol activity reported by the network layer to the node.
+#[derive(Debug)]
+pub enum SyncEvent {
+    PeerConnected(String),
+    PeerDisconnected(String),
+    Response { peer: String, response: SyncResponse },
+    RequestFailed { peer: String },
 }
@@ -2,24 +2,38 @@
 
 use libp2p::{
     futures::StreamExt,
-    gossipsub, kad, noise, swarm::SwarmEvent, tcp, yamux, PeerId, Swarm,
+    gossipsub, kad, noise, request_response, swarm::SwarmEvent, tcp, yamux, PeerId, StreamProtocol, Swarm,
 };
 use std::error::Error;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
-use crate::network::messages::NetworkMessage;
+use crate::consensus::sync::serve_request;
+use crate::network::messages::{InboundMessage, NetworkMessage, PeerPenalty, SyncCommand, SyncEvent, SyncRequest, SyncResponse};
+use crate::storage::Storage;
+
+/// Request-response protocol used for block sync.
+pub const SYNC_PROTOCOL: &str = "/kortana/sync/1";
+
+/// Reputation below which a peer is disconnected.
+const MIN_PEER_REPUTATION: i32 = -50;
 
 #[derive(libp2p::swarm::NetworkBehaviour)]
 pub struct KortanaBehaviour {
     pub gossipsub: gossipsub::Behaviour,
     pub kademlia: kad::Behaviour<kad::store::MemoryStore>,
+    pub sync: request_response::json::Behaviour<SyncRequest, SyncResponse>,
 }
 
 pub struct KortanaNetwork {
     swarm: Swarm<KortanaBehaviour>,
     tx_receiver: mpsc::Receiver<NetworkMessage>,
-    node_tx: mpsc::Sender<NetworkMessage>,
+    node_tx: mpsc::Sender<InboundMessage>,
+    penalty_rx: mpsc::Receiver<PeerPenalty>,
+    sync_rx: mpsc::Receiver<SyncCommand>,
+    sync_tx: mpsc::Sender<SyncEvent>,
+    storage: Arc<Storage>,
     peer_reputation: std::collections::HashMap<PeerId, i32>,
     pub bootnodes: Vec<libp2p::Multiaddr>,
 }
@@ -27,7 +41,11 @@ pub struct KortanaNetwork {
 impl KortanaNetwork {
     pub async fn new(
         tx_receiver: mpsc::Receiver<NetworkMessage>,
-        node_tx: mpsc::Sender<NetworkMessage>
+        node_tx: mpsc::Sender<InboundMessage>,
+        penalty_rx: mpsc::Receiver<PeerPenalty>,
+        sync_rx: mpsc::Receiver<SyncCommand>,
+        sync_tx: mpsc::Sender<SyncEvent>,
+        storage: Arc<Storage>,
     ) -> Result<Self, Box<dyn Error>> {
         let mut swarm = libp2p::SwarmBuilder::with_new_identity()
             .with_tokio()
@@ -59,20 +77,31 @@ impl KortanaNetwork {
                 let store = kad::store::MemoryStore::new(key.public().to_peer_id());
                 let kademlia = kad::Behaviour::new(key.public().to_peer_id(), store);
 
-                KortanaBehaviour { gossipsub, kademlia }
+                let sync = request_response::json::Behaviour::new(
+                    [(StreamProtocol::new(SYNC_PROTOCOL), request_response::ProtocolSupport::Full)],
+                    request_response::Config::default().with_request_timeout(Duration::from_secs(10)),
+                );
+
+                KortanaBehaviour { gossipsub, kademlia, sync }
             })
             .expect("Failed to build behaviour")
             .build();
 
         let block_topic = gossipsub::IdentTopic::new("kortana-blocks");
         let tx_topic = gossipsub::IdentTopic::new("kortana-transactions");
+        let consensus_topic = gossipsub::IdentTopic::new("kortana-consensus");
         swarm.behaviour_mut().gossipsub.subscribe(&block_topic)?;
         swarm.behaviour_mut().gossipsub.subscribe(&tx_topic)?;
+        swarm.behaviour_mut().gossipsub.subscribe(&consensus_topic)?;
 
         Ok(Self { 
             swarm, 
             tx_receiver, 
             node_tx,
+            penalty_rx,
+            sync_rx,
+            sync_tx,
+            storage,
             peer_reputation: std::collections::HashMap::new(),
             bootnodes: Vec::new(),
         })
@@ -83,6 +112,15 @@ impl KortanaNetwork {
         let _ = self.swarm.dial(addr);
     }
 
+    fn penalize(&mut self, peer_id: PeerId, amount: i32, reason: &str) {
+        let rep = self.peer_reputation.entry(peer_id).or_insert(0);
+        *rep -= amount;
+        if *rep < MIN_PEER_REPUTATION {
+            println!("[P2P] Disconnecting misbehaving peer {:?} ({})", peer_id, reason);
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+    }
+
     pub async fn run(mut self, listen_addr: String) {
         self.swarm.listen_on(listen_addr.parse().unwrap()).unwrap();
 
@@ -93,10 +131,23 @@ impl KortanaNetwork {
                     let data = serde_json::to_vec(&msg).unwrap();
                     let topic = match msg {
                         NetworkMessage::NewBlock(_) => "kortana-blocks",
-                        _ => "kortana-transactions",
+                        NetworkMessage::Proposal(_) | NetworkMessage::Vote(_) | NetworkMessage::Evidence(_) => "kortana-consensus",
+                        NetworkMessage::NewTransaction(_) => "kortana-transactions",
                     };
                     let _ = self.swarm.behaviour_mut().gossipsub.publish(gossipsub::IdentTopic::new(topic), data);
                 }
+                // Node rejected something a peer relayed
+                Some(p) = self.penalty_rx.recv() => {
+                    if let Ok(peer_id) = p.peer.parse::<PeerId>() {
+                        self.penalize(peer_id, p.penalty, &p.reason);
+                    }
+                }
+                // Node wants something from a specific peer
+                Some(cmd) = self.sync_rx.recv() => {
+                    if let Ok(peer_id) = cmd.peer.parse::<PeerId>() {
+                        self.swarm.behaviour_mut().sync.send_request(&peer_id, cmd.request);
+                    }
+                }
                 // Incoming from P2P -> Node
                 event = self.swarm.select_next_some() => match event {
                     SwarmEvent::NewListenAddr { address, .. } => {
@@ -107,28 +158,41 @@ impl KortanaNetwork {
                         if current_peers > 50 { // Max 50 peers for stability
                             println!("[P2P] Peer limit reached, disconnecting {:?}", peer_id);
                             let _ = self.swarm.disconnect_peer_id(peer_id);
+                        } else {
+                            let _ = self.sync_tx.send(SyncEvent::PeerConnected(peer_id.to_string())).await;
                         }
                     }
+                    SwarmEvent::ConnectionClosed { peer_id, num_established: 0, .. } => {
+                        let _ = self.sync_tx.send(SyncEvent::PeerDisconnected(peer_id.to_string())).await;
+                    }
                     SwarmEvent::Behaviour(event) => match event {
                         KortanaBehaviourEvent::Gossipsub(gossipsub::Event::Message {
-                            message, ..
+                            propagation_source, message, ..
                         }) => {
-                            let peer_id = message.source.unwrap_or(PeerId::random());
+                            // The relaying peer, not the original author, is the one we hold accountable
                             match serde_json::from_slice::<NetworkMessage>(&message.data) {
                                 Ok(msg) => {
-                                    let _ = self.node_tx.send(msg).await;
+                                    let inbound = InboundMessage { peer: Some(propagation_source.to_string()), message: msg };
+                                    let _ = self.node_tx.send(inbound).await;
                                 }
                                 Err(_) => {
                                     // Penalize for malformed messages
-                                    let rep = self.peer_reputation.entry(peer_id).or_insert(0);
-                                    *rep -= 10;
-                                    if *rep < -50 {
-                                        println!("[P2P] Disconnecting malicious peer {:?}", peer_id);
-                                        let _ = self.swarm.disconnect_peer_id(peer_id);
-                                    }
+                                    self.penalize(propagation_source, 10, "malformed message");
                                 }
                             }
                         }
+                        KortanaBehaviourEvent::Sync(request_response::Event::Message { peer, message, .. }) => match message {
+                            request_response::Message::Request { request, channel, .. } => {
+                                let response = serve_request(&self.storage, &request);
+                                let _ = self.swarm.behaviour_mut().sync.send_response(channel, response);
+                            }
+                            request_response::Message::Response { response, .. } => {
+                                let _ = self.sync_tx.send(SyncEvent::Response { peer: peer.to_string(), response }).await;
+                            }
+                        },
+                        KortanaBehaviourEvent::Sync(request_response::Event::OutboundFailure { peer, .. }) => {
+                            let _ = self.sync_tx.send(SyncEvent::RequestFailed { peer: peer.to_string() }).await;
+                        }
                         _ => {}
                     }
                     _ => {}
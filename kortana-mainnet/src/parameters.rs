@@ -1,13 +1,24 @@
 // File: src/parameters.rs
 
+use serde::{Serialize, Deserialize};
+use sha3::{Digest, Keccak256};
+
 pub const CHAIN_ID: u64 = 9002;
 pub const TESTNET_CHAIN_ID: u64 = 72511;
+pub const DEV_CHAIN_ID: u64 = 1337;
+
+/// Release this binary implements; upgrade plans name the release nodes must run.
+pub const NODE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const STAKING_CONTRACT_ADDRESS: &str = "0x0000000000000000000000000000000000000001";
+pub const GOVERNANCE_CONTRACT_ADDRESS: &str = "0x0000000000000000000000000000000000001000";
 
 pub const BLOCK_TIME_SECS: u64 = 2;
 pub const SLOT_DURATION_SECS: u64 = 2;
 pub const FINALITY_DELAY_SLOTS: u64 = 20;
+// Proof of History: every slot a block spans must be covered by this many sequential hashes
+pub const POH_TICKS_PER_SLOT: u64 = 32;
+pub const POH_HASHES_PER_TICK: u64 = 1_000;
 
 pub const BLOCKS_PER_EPOCH: u64 = 432;  // ~14 minutes at 2s blocks
 pub const BLOCKS_PER_DAY: u64 = 43200;
@@ -22,7 +33,9 @@ pub const TOTAL_SUPPLY: u128 = 500_000_000_000_000_000_000_000_000_000_000;  //
 
 pub const MIN_VALIDATOR_STAKE: u128 = 1_000_000_000_000_000_000_000_000;  // 1M DNR
 pub const ACTIVE_VALIDATOR_COUNT: usize = 3;
+pub const DEFAULT_COMMISSION_BPS: u16 = 500;  // 5%
 pub const MAX_COMMISSION_RATE: u16 = 10000;  // 100% in basis points
+pub const MAX_MONIKER_LEN: usize = 70;  // bytes of validator metadata
 
 pub const MIN_GAS_PRICE: u128 = 1;  // 1 satoshi
 pub const GAS_LIMIT_PER_BLOCK: u64 = 30_000_000;
@@ -40,10 +53,165 @@ pub const SLASH_EQUIVOCATION: u16 = 3300;  // 33%
 pub const SLASH_DOWNTIME: u16 = 100;  // 1%
 pub const SLASH_BYZANTINE: u16 = 10000;  // 100%
 
-pub const MAX_MISSED_BLOCKS_BEFORE_JAIL: u64 = 50;
+/// Evidence must be included while the offender's stake is still bonded.
+pub const MAX_EVIDENCE_AGE_BLOCKS: u64 = UNBONDING_PERIOD_BLOCKS;
+pub const MAX_EVIDENCE_PER_BLOCK: usize = 16;
+
+pub const LIVENESS_WINDOW_BLOCKS: u64 = 100;  // commits over which missed votes are counted
+pub const MAX_MISSED_BLOCKS_BEFORE_JAIL: u64 = 50;  // of the last LIVENESS_WINDOW_BLOCKS
 pub const JAIL_DURATION_SLOTS: u64 = 500;
 pub const UNBONDING_PERIOD_BLOCKS: u64 = 20160; // Approx 1 day at 5s blocks (simplified)
 
+// Governance defaults; each can be changed by a ParameterChange proposal
+pub const GOV_MIN_DEPOSIT: u128 = 10_000_000_000_000_000_000_000;  // 10K DNR
+pub const GOV_VOTING_PERIOD_BLOCKS: u64 = 1000;
+pub const GOV_QUORUM_BPS: u16 = 3340;  // of all bonded stake
+pub const GOV_THRESHOLD_BPS: u16 = 5000;  // Yes, of the non-abstaining votes
+pub const GOV_VETO_BPS: u16 = 3340;  // NoWithVeto, of all votes
+
+/// Parameters that differ between networks or change over a chain's life. The
+/// constants above are mainnet's values and the defaults; a chain spec may override
+/// any of them at genesis, and `ParameterChange` proposals change them later. They live
+/// in `State`, so every module reads the values in force at the block it processes.
+/// Epoch length and PoH rates stay constants: slot and epoch numbering depend on them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainParams {
+    /// Fixed by the chain spec at genesis; no proposal can change it.
+    pub chain_id: u64,
+    pub block_time_secs: u64,
+    pub block_gas_limit: u64,
+    pub max_tx_gas: u64,
+    pub min_gas_price: u128,
+    pub mempool_max_size: u64,
+    pub min_validator_stake: u128,
+    pub active_validator_count: u64,
+    pub unbonding_period_blocks: u64,
+    pub max_evidence_age_blocks: u64,
+    pub max_evidence_per_block: u64,
+    pub liveness_window_blocks: u64,
+    pub max_missed_blocks_before_jail: u64,
+    pub jail_duration_slots: u64,
+    pub slash_double_proposal_bps: u16,
+    pub slash_equivocation_bps: u16,
+    pub slash_downtime_bps: u16,
+    pub slash_byzantine_bps: u16,
+    pub gov_min_deposit: u128,
+    pub gov_voting_period_blocks: u64,
+    pub gov_quorum_bps: u16,
+    pub gov_threshold_bps: u16,
+    pub gov_veto_bps: u16,
+}
+
+impl Default for ChainParams {
+    fn default() -> Self {
+        Self {
+            chain_id: CHAIN_ID,
+            block_time_secs: BLOCK_TIME_SECS,
+            block_gas_limit: GAS_LIMIT_PER_BLOCK,
+            max_tx_gas: GAS_LIMIT_PER_TX,
+            min_gas_price: MIN_GAS_PRICE,
+            mempool_max_size: MEMPOOL_MAX_SIZE as u64,
+            min_validator_stake: MIN_VALIDATOR_STAKE,
+            active_validator_count: ACTIVE_VALIDATOR_COUNT as u64,
+            unbonding_period_blocks: UNBONDING_PERIOD_BLOCKS,
+            max_evidence_age_blocks: MAX_EVIDENCE_AGE_BLOCKS,
+            max_evidence_per_block: MAX_EVIDENCE_PER_BLOCK as u64,
+            liveness_window_blocks: LIVENESS_WINDOW_BLOCKS,
+            max_missed_blocks_before_jail: MAX_MISSED_BLOCKS_BEFORE_JAIL,
+            jail_duration_slots: JAIL_DURATION_SLOTS,
+            slash_double_proposal_bps: SLASH_DOUBLE_PROPOSAL,
+            slash_equivocation_bps: SLASH_EQUIVOCATION,
+            slash_downtime_bps: SLASH_DOWNTIME,
+            slash_byzantine_bps: SLASH_BYZANTINE,
+            gov_min_deposit: GOV_MIN_DEPOSIT,
+            gov_voting_period_blocks: GOV_VOTING_PERIOD_BLOCKS,
+            gov_quorum_bps: GOV_QUORUM_BPS,
+            gov_threshold_bps: GOV_THRESHOLD_BPS,
+            gov_veto_bps: GOV_VETO_BPS,
+        }
+    }
+}
+
+impl ChainParams {
+    /// Sets the field named `key` to the decimal `value`. Nothing changes if the result
+    /// would not validate.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+            value.parse().map_err(|_| format!("Invalid value for {}: {}", key, value))
+        }
+        let mut next = self.clone();
+        match key {
+            "block_time_secs" => next.block_time_secs = parse(key, value)?,
+            "block_gas_limit" => next.block_gas_limit = parse(key, value)?,
+            "max_tx_gas" => next.max_tx_gas = parse(key, value)?,
+            "min_gas_price" => next.min_gas_price = parse(key, value)?,
+            "mempool_max_size" => next.mempool_max_size = parse(key, value)?,
+            "min_validator_stake" => next.min_validator_stake = parse(key, value)?,
+            "active_validator_count" => next.active_validator_count = parse(key, value)?,
+            "unbonding_period_blocks" => next.unbonding_period_blocks = parse(key, value)?,
+            "max_evidence_age_blocks" => next.max_evidence_age_blocks = parse(key, value)?,
+            "max_evidence_per_block" => next.max_evidence_per_block = parse(key, value)?,
+            "liveness_window_blocks" => next.liveness_window_blocks = parse(key, value)?,
+            "max_missed_blocks_before_jail" => next.max_missed_blocks_before_jail = parse(key, value)?,
+            "jail_duration_slots" => next.jail_duration_slots = parse(key, value)?,
+            "slash_double_proposal_bps" => next.slash_double_proposal_bps = parse(key, value)?,
+            "slash_equivocation_bps" => next.slash_equivocation_bps = parse(key, value)?,
+            "slash_downtime_bps" => next.slash_downtime_bps = parse(key, value)?,
+            "slash_byzantine_bps" => next.slash_byzantine_bps = parse(key, value)?,
+            "gov_min_deposit" => next.gov_min_deposit = parse(key, value)?,
+            "gov_voting_period_blocks" => next.gov_voting_period_blocks = parse(key, value)?,
+            "gov_quorum_bps" => next.gov_quorum_bps = parse(key, value)?,
+            "gov_threshold_bps" => next.gov_threshold_bps = parse(key, value)?,
+            "gov_veto_bps" => next.gov_veto_bps = parse(key, value)?,
+            _ => return Err(format!("Unknown parameter {}", key)),
+        }
+        next.validate()?;
+        *self = next;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.chain_id == 0 || self.block_time_secs == 0 {
+            return Err("chain_id and block_time_secs must be positive".to_string());
+        }
+        if !(MIN_GAS_PER_TX..=self.block_gas_limit).contains(&self.max_tx_gas) {
+            return Err("max_tx_gas must lie between the intrinsic gas and block_gas_limit".to_string());
+        }
+        if self.min_gas_price == 0 || self.mempool_max_size == 0 || self.active_validator_count == 0 || self.gov_voting_period_blocks == 0 {
+            return Err("min_gas_price, mempool_max_size, active_validator_count and gov_voting_period_blocks must be positive".to_string());
+        }
+        if self.max_missed_blocks_before_jail == 0 || self.max_missed_blocks_before_jail > self.liveness_window_blocks {
+            return Err("max_missed_blocks_before_jail must lie between 1 and liveness_window_blocks".to_string());
+        }
+        // Evidence must be included while the offender's stake is still bonded
+        if self.max_evidence_age_blocks > self.unbonding_period_blocks {
+            return Err("max_evidence_age_blocks cannot exceed unbonding_period_blocks".to_string());
+        }
+        let bps = [
+            self.slash_double_proposal_bps, self.slash_equivocation_bps, self.slash_downtime_bps, self.slash_byzantine_bps,
+            self.gov_quorum_bps, self.gov_threshold_bps, self.gov_veto_bps,
+        ];
+        if bps.iter().any(|b| *b > 10000) {
+            return Err("Basis point parameters cannot exceed 10000".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_valid_gas_price(&self, price: u128) -> bool {
+        price >= self.min_gas_price
+    }
+
+    pub fn is_valid_gas_limit(&self, limit: u64) -> bool {
+        (MIN_GAS_PER_TX..=self.max_tx_gas).contains(&limit)
+    }
+
+    /// Hash the state root commits to, through `PARAMS_ROOT_SLOT` of the governance account.
+    pub fn root(&self) -> [u8; 32] {
+        Keccak256::digest(bincode::serialize(self).expect("Chain params serialize")).into()
+    }
+}
+
 pub fn calculate_block_reward(block_number: u64) -> u128 {
     let halvings = block_number / HALVING_INTERVAL;
     let mut reward = INITIAL_BLOCK_REWARD;
@@ -62,13 +230,6 @@ pub fn is_valid_commission_rate(rate: u16) -> bool {
     rate <= MAX_COMMISSION_RATE
 }
 
-pub fn is_valid_gas_price(price: u128) -> bool {
-    price >= MIN_GAS_PRICE
-}
-
-pub fn is_valid_gas_limit(limit: u64) -> bool {
-    (MIN_GAS_PER_TX..=GAS_LIMIT_PER_TX).contains(&limit)
-}
 
 #[cfg(test)]
 mod tests {
@@ -96,11 +257,29 @@ mod tests {
 
     #[test]
     fn test_gas_validation() {
-        assert!(is_valid_gas_price(MIN_GAS_PRICE));
-        assert!(!is_valid_gas_price(0));
-        assert!(is_valid_gas_limit(MIN_GAS_PER_TX));
-        assert!(is_valid_gas_limit(GAS_LIMIT_PER_TX));
-        assert!(!is_valid_gas_limit(GAS_LIMIT_PER_TX + 1));
+        let params = ChainParams::default();
+        assert!(params.is_valid_gas_price(MIN_GAS_PRICE));
+        assert!(!params.is_valid_gas_price(0));
+        assert!(params.is_valid_gas_limit(MIN_GAS_PER_TX));
+        assert!(params.is_valid_gas_limit(GAS_LIMIT_PER_TX));
+        assert!(!params.is_valid_gas_limit(GAS_LIMIT_PER_TX + 1));
+    }
+
+    #[test]
+    fn test_params_file_overrides_defaults_and_set_validates() {
+        let params: ChainParams = serde_json::from_str(r#"{"block_gas_limit": 60000000, "min_validator_stake": 5000000000000000000000000}"#).unwrap();
+        assert_eq!(params.block_gas_limit, 60_000_000);
+        assert_eq!(params.min_validator_stake, 5 * MIN_VALIDATOR_STAKE);
+        assert_eq!(params.unbonding_period_blocks, UNBONDING_PERIOD_BLOCKS);
+
+        let mut params = ChainParams::default();
+        params.set("active_validator_count", "21").unwrap();
+        assert_eq!(params.active_validator_count, 21);
+        assert!(params.set("max_tx_gas", "40000000").is_err());
+        assert!(params.set("max_evidence_age_blocks", &(UNBONDING_PERIOD_BLOCKS + 1).to_string()).is_err());
+        assert!(params.set("chain_id", "1").is_err());
+        assert!(params.set("block_gas_limit", "-1").is_err());
+        assert_eq!(params.max_tx_gas, GAS_LIMIT_PER_TX);
     }
 
     #[test]
@@ -0,0 +1,57 @@
+// File: src/rpc/events.rs
+//
+// Chain events surfaced to RPC clients. The node loop publishes reorgs here; clients
+// read them via `kortana_newChainEventFilter` + `eth_getFilterChanges`, and existing
+// block and log filters use them to re-deliver the new branch after a reorg.
+
+use std::collections::VecDeque;
+use serde_json::{json, Value};
+use crate::core::chain::ChainReorg;
+
+/// Events retained for polling clients; older ones are dropped.
+pub const MAX_CHAIN_EVENTS: usize = 256;
+
+#[derive(Debug, Default)]
+pub struct ChainEventLog {
+    next_seq: u64,
+    events: VecDeque<(u64, ChainReorg)>,
+}
+
+impl ChainEventLog {
+    pub fn push(&mut self, reorg: ChainReorg) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.events.push_back((seq, reorg));
+        if self.events.len() > MAX_CHAIN_EVENTS {
+            self.events.pop_front();
+        }
+        seq
+    }
+
+    /// Sequence number the next event will get.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Retained events with sequence number `>= seq`, oldest first.
+    pub fn since(&self, seq: u64) -> impl Iterator<Item = &ChainReorg> {
+        self.events.iter().filter(move |(s, _)| *s >= seq).map(|(_, r)| r)
+    }
+}
+
+pub fn reorg_json(reorg: &ChainReorg) -> Value {
+    let hashes = |v: &[[u8; 32]]| v.iter().map(|h| format!("0x{}", hex::encode(h))).collect::<Vec<_>>();
+    json!({
+        "type": "chain_reorg",
+        "depth": format!("0x{:x}", reorg.depth),
+        "commonAncestor": {
+            "number": format!("0x{:x}", reorg.common_ancestor_height),
+            "hash": format!("0x{}", hex::encode(reorg.common_ancestor_hash)),
+        },
+        "oldHead": format!("0x{}", hex::encode(reorg.old_head)),
+        "newHead": format!("0x{}", hex::encode(reorg.new_head)),
+        "newHeight": format!("0x{:x}", reorg.new_height),
+        "removed": hashes(&reorg.removed),
+        "added": hashes(&reorg.added),
+    })
+}
@@ -0,0 +1,135 @@
+// File: src/rpc/filter.rs
+//
+// Log filter parsing and matching shared by eth_getLogs and eth_newFilter.
+// Semantics follow the Ethereum JSON-RPC spec: `address` is a single address or a list
+// (any of), `topics` is positional where each position is null (wildcard), a topic, or a
+// list of topics (any of).
+
+use serde_json::{Map, Value};
+use crate::address::Address;
+use crate::types::bloom::Bloom;
+use crate::types::transaction::TransactionLog;
+
+/// Upper bound on blocks a single log query may scan.
+pub const MAX_LOG_BLOCK_RANGE: u64 = 10_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub addresses: Option<Vec<[u8; 20]>>,
+    pub topics: Vec<Option<Vec<[u8; 32]>>>,
+}
+
+impl LogFilter {
+    pub fn from_json(obj: &Map<String, Value>) -> Result<Self, String> {
+        let addresses = match obj.get("address") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(s)) => Some(vec![Self::parse_address(s)?]),
+            Some(Value::Array(a)) => Some(
+                a.iter()
+                    .map(|v| v.as_str().ok_or("Address must be a string".to_string()).and_then(Self::parse_address))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Some(_) => return Err("Invalid address filter".to_string()),
+        };
+
+        let mut topics = Vec::new();
+        if let Some(v) = obj.get("topics") {
+            let positions = v.as_array().ok_or("Topics must be an array")?;
+            if positions.len() > 4 {
+                return Err("At most 4 topic positions are supported".to_string());
+            }
+            for pos in positions {
+                topics.push(match pos {
+                    Value::Null => None,
+                    Value::String(s) => Some(vec![Self::parse_topic(s)?]),
+                    Value::Array(a) => Some(
+                        a.iter()
+                            .map(|t| t.as_str().ok_or("Topic must be a string".to_string()).and_then(Self::parse_topic))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    ),
+                    _ => return Err("Invalid topic filter".to_string()),
+                });
+            }
+        }
+
+        Ok(Self { addresses, topics })
+    }
+
+    fn parse_address(s: &str) -> Result<[u8; 20], String> {
+        Address::from_hex(s).map(|a| a.as_evm_address()).map_err(|e| e.to_string())
+    }
+
+    fn parse_topic(s: &str) -> Result<[u8; 32], String> {
+        crate::storage::codec::hash_key(s).ok_or_else(|| format!("Invalid topic '{}'", s))
+    }
+
+    /// Topic0 alternatives, if the first position is constrained.
+    pub fn topic0(&self) -> Option<&[[u8; 32]]> {
+        self.topics.first().and_then(|t| t.as_deref())
+    }
+
+    pub fn matches(&self, log: &TransactionLog) -> bool {
+        if let Some(addrs) = &self.addresses {
+            if !addrs.contains(&log.address.as_evm_address()) {
+                return false;
+            }
+        }
+        self.topics.iter().enumerate().all(|(i, wanted)| match wanted {
+            None => true,
+            Some(options) => log.topics.get(i).is_some_and(|t| options.is_empty() || options.contains(t)),
+        })
+    }
+
+    /// False only if no log in a block with this bloom can match.
+    pub fn bloom_matches(&self, bloom: &Bloom) -> bool {
+        if let Some(addrs) = &self.addresses {
+            if !addrs.is_empty() && !addrs.iter().any(|a| bloom.contains_input(a)) {
+                return false;
+            }
+        }
+        self.topics.iter().all(|wanted| match wanted {
+            Some(options) if !options.is_empty() => options.iter().any(|t| bloom.contains_input(t)),
+            _ => true,
+        })
+    }
+}
+
+/// Parses a block tag ("latest", "earliest", "pending" or a hex number).
+pub fn parse_block_tag(v: Option<&Value>, current_height: u64, default: u64) -> Result<u64, String> {
+    match v.and_then(|v| v.as_str()) {
+        None => Ok(default),
+        Some("latest") | Some("pending") | Some("safe") | Some("finalized") => Ok(current_height),
+        Some("earliest") => Ok(0),
+        Some(s) => u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).map_err(|_| format!("Invalid block number '{}'", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(topics: Vec<[u8; 32]>) -> TransactionLog {
+        TransactionLog { address: Address::from_pubkey(b"token"), topics, data: vec![] }
+    }
+
+    #[test]
+    fn test_topic_positions_and_or() {
+        let filter = LogFilter {
+            addresses: None,
+            topics: vec![Some(vec![[1u8; 32], [2u8; 32]]), None, Some(vec![[3u8; 32]])],
+        };
+        assert!(filter.matches(&log(vec![[2u8; 32], [9u8; 32], [3u8; 32]])));
+        assert!(!filter.matches(&log(vec![[2u8; 32], [9u8; 32], [4u8; 32]])));
+        assert!(!filter.matches(&log(vec![[2u8; 32]])));
+    }
+
+    #[test]
+    fn test_bloom_prefilter() {
+        let l = log(vec![[1u8; 32]]);
+        let bloom = Bloom::from_logs(std::slice::from_ref(&l));
+        let hit = LogFilter { addresses: Some(vec![l.address.as_evm_address()]), topics: vec![Some(vec![[1u8; 32]])] };
+        let miss = LogFilter { addresses: None, topics: vec![Some(vec![[7u8; 32]])] };
+        assert!(hit.bloom_matches(&bloom));
+        assert!(!miss.bloom_matches(&bloom));
+    }
+}
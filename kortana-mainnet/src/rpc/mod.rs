@@ -1,7 +1,13 @@
 // File: src/rpc/mod.rs
 
+pub mod events;
+pub mod filter;
+
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
+use filter::{LogFilter, MAX_LOG_BLOCK_RANGE};
+use events::ChainEventLog;
+use crate::consensus::sync::SyncProgress;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcRequest {
@@ -53,21 +59,22 @@ use sha3::Digest;
 use tokio::sync::mpsc;
 
 use crate::consensus::ConsensusEngine;
+use crate::staking::StakingStore;
 
 // Filter tracking for MetaMask compatibility
 #[derive(Debug, Clone)]
 pub enum FilterType {
     Block,
-    Logs {
-        address: Option<Vec<String>>,
-        topics: Option<Vec<Value>>,
-    },
+    Logs(LogFilter),
+    ChainEvents,
 }
 
 #[derive(Debug, Clone)]
 pub struct RpcFilter {
     pub filter_type: FilterType,
     pub last_poll_block: u64,
+    /// Next chain event this filter has not seen yet.
+    pub event_seq: u64,
 }
 
 pub type FilterMap = Arc<Mutex<HashMap<String, RpcFilter>>>;
@@ -81,6 +88,9 @@ pub struct RpcHandler {
     pub height: Arc<AtomicU64>,
     pub chain_id: u64,
     pub filters: FilterMap,
+    pub chain_events: Arc<Mutex<ChainEventLog>>,
+    /// Set by the node while it is range-syncing from peers.
+    pub sync_progress: Arc<Mutex<Option<SyncProgress>>>,
 }
 
 impl RpcHandler {
@@ -94,7 +104,113 @@ impl RpcHandler {
             height, 
             chain_id,
             filters: Arc::new(Mutex::new(HashMap::new())),
+            chain_events: Arc::new(Mutex::new(ChainEventLog::default())),
+            sync_progress: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// JSON view of an indexed (already included) transaction, with its on-chain location.
+    fn indexed_tx_json(&self, hash: &[u8; 32]) -> Option<Value> {
+        let hash_hex = hex::encode(hash);
+        let tx = self.storage.get_transaction(&hash_hex).ok().flatten()?;
+        let (block_height, block_hash, tx_index) = self.storage.get_transaction_location(&hash_hex)
+            .ok()
+            .flatten()
+            .map(|(h, hash, idx)| (format!("0x{:x}", h), format!("0x{}", hash), format!("0x{:x}", idx)))
+            .unwrap_or((format!("0x{:x}", 0), "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(), "0x0".to_string()));
+
+        Some(serde_json::json!({
+            "hash": format!("0x{}", hash_hex),
+            "from": format!("0x{}", hex::encode(tx.from.as_evm_address())),
+            "to": format!("0x{}", hex::encode(tx.to.as_evm_address())),
+            "value": format!("0x{:x}", tx.value),
+            "nonce": format!("0x{:x}", tx.nonce),
+            "blockNumber": block_height,
+            "blockHash": block_hash,
+            "transactionIndex": tx_index,
+            "gas": format!("0x{:x}", tx.gas_limit),
+            "gasPrice": format!("0x{:x}", tx.gas_price),
+            "input": format!("0x{}", hex::encode(&tx.data)),
+            "chainId": format!("0x{:x}", self.chain_id),
+            "v": "0x1b", "r": "0x0", "s": "0x0",
+            "type": "0x0"
+        }))
+    }
+
+    /// Staking state of an epoch given as a number or hex quantity, the current one if
+    /// omitted. The set of epoch e is elected by block e * BLOCKS_PER_EPOCH and validates
+    /// the blocks after it; past epochs are read from the state stored at that block.
+    fn epoch_staking(&self, epoch: Option<&Value>) -> Option<StakingStore> {
+        let current = self.state.lock().unwrap().staking.epoch;
+        let epoch = match epoch {
+            Some(Value::String(s)) => u64::from_str_radix(s.trim_start_matches("0x"), 16).ok(),
+            Some(v) => v.as_u64(),
+            None => Some(current),
+        };
+        match epoch {
+            Some(e) if e == current => Some(self.state.lock().unwrap().staking.clone()),
+            Some(e) if e < current => self.storage.get_state(e * crate::parameters::BLOCKS_PER_EPOCH).ok().flatten().map(|s| s.staking),
+            _ => None,
+        }
+    }
+
+    /// Logs matching `filter` in blocks `from..=to`. Candidate blocks come from the
+    /// (address, topic0) index when the filter names addresses, otherwise from the
+    /// per-block blooms; receipts are only read for candidates.
+    fn collect_logs(&self, filter: &LogFilter, from: u64, to: u64) -> Result<Vec<Value>, String> {
+        if from > to {
+            return Ok(Vec::new());
+        }
+        let heights: Vec<u64> = match &filter.addresses {
+            Some(addrs) => self.storage.find_log_heights(addrs, filter.topic0(), from, to)?.into_iter().collect(),
+            None => {
+                let mut hs = Vec::new();
+                for h in from..=to {
+                    if let Some(bloom) = self.storage.get_block_bloom(h)? {
+                        if filter.bloom_matches(&bloom) {
+                            hs.push(h);
+                        }
+                    }
+                }
+                hs
+            }
+        };
+
+        let mut logs = Vec::new();
+        for h in heights {
+            let block = match self.storage.get_block(h)? {
+                Some(b) => b,
+                None => continue,
+            };
+            let b_hash = format!("0x{}", hex::encode(block.header.hash()));
+            let b_num = format!("0x{:x}", block.header.height);
+            // logIndex is the position of the log within the block, as in Ethereum
+            let mut log_index = 0u64;
+            for (tx_idx, tx) in block.transactions.iter().enumerate() {
+                let tx_hash = hex::encode(tx.hash());
+                let receipt = match self.storage.get_receipt(&tx_hash)? {
+                    Some(r) => r,
+                    None => continue,
+                };
+                for log in &receipt.logs {
+                    if filter.matches(log) {
+                        logs.push(serde_json::json!({
+                            "address": format!("0x{}", hex::encode(log.address.as_evm_address())),
+                            "topics": log.topics.iter().map(|t| format!("0x{}", hex::encode(t))).collect::<Vec<_>>(),
+                            "data": format!("0x{}", hex::encode(&log.data)),
+                            "blockNumber": b_num,
+                            "transactionHash": format!("0x{}", tx_hash),
+                            "transactionIndex": format!("0x{:x}", tx_idx),
+                            "blockHash": b_hash,
+                            "logIndex": format!("0x{:x}", log_index),
+                            "removed": false
+                        }));
+                    }
+                    log_index += 1;
+                }
+            }
         }
+        Ok(logs)
     }
 
     pub async fn handle(&self, request: JsonRpcRequest) -> JsonRpcResponse {
@@ -112,7 +228,7 @@ impl RpcHandler {
             "eth_blockNumber" => {
                 Some(serde_json::to_value(format!("0x{:x}", current_height)).unwrap())
             }
-            "eth_gasPrice" => Some(serde_json::to_value(format!("0x{:x}", crate::parameters::MIN_GAS_PRICE)).unwrap()),
+            "eth_gasPrice" => Some(serde_json::to_value(format!("0x{:x}", self.state.lock().unwrap().params.min_gas_price)).unwrap()),
             "eth_estimateGas" => {
                 let mut gas = crate::parameters::MIN_GAS_PER_TX;
                 
@@ -209,7 +325,13 @@ impl RpcHandler {
                              .and_then(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok())
                              .unwrap_or_default();
 
-                        if let Some(header) = &latest_header {
+                        if to_addr == crate::staking::staking_address() {
+                            // Staking queries are answered natively, see StakingStore::call
+                            match self.state.lock().unwrap().staking.call(&data) {
+                                Ok(res) => Some(serde_json::to_value(format!("0x{}", hex::encode(res))).unwrap()),
+                                Err(e) => return JsonRpcResponse::new_error(req_id, -32000, &e),
+                            }
+                        } else if let Some(header) = &latest_header {
                             let mut state_clone = self.state.lock().unwrap().clone(); 
                             let acc = state_clone.get_account(&to_addr);
                             if acc.is_contract {
@@ -235,7 +357,7 @@ impl RpcHandler {
             }
             "eth_maxPriorityFeePerGas" => {
                 // EIP-1559: Return the minimum priority fee. Kortana node uses 0 priority fee.
-                Some(serde_json::to_value(format!("0x{:x}", crate::parameters::MIN_GAS_PRICE)).unwrap())
+                Some(serde_json::to_value(format!("0x{:x}", self.state.lock().unwrap().params.min_gas_price)).unwrap())
             }
             "eth_feeHistory" => {
                 // Return a proper fee history so MetaMask can calculate EIP-1559 fees
@@ -244,8 +366,9 @@ impl RpcHandler {
                         .and_then(|s| u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).ok())
                         .unwrap_or(4).min(10)
                 } else { 4 };
+                let min_gas_price = self.state.lock().unwrap().params.min_gas_price;
                 let base_fees: Vec<String> = (0..=block_count).map(|_| 
-                    format!("0x{:x}", crate::parameters::MIN_GAS_PRICE)
+                    format!("0x{:x}", min_gas_price)
                 ).collect();
                 let gas_ratios: Vec<f64> = (0..block_count).map(|_| 0.1).collect();
                 Some(serde_json::json!({
@@ -287,8 +410,9 @@ impl RpcHandler {
                                             return JsonRpcResponse::new_error(req_id.clone(), -32000, &format!("Invalid chain ID: expected {}, got {}", self.chain_id, tx.chain_id));
                                         }
 
-                                        if tx.gas_price < crate::parameters::MIN_GAS_PRICE {
-                                            return JsonRpcResponse::new_error(req_id.clone(), -32000, &format!("Gas price too low: min {}", crate::parameters::MIN_GAS_PRICE));
+                                        let min_gas_price = self.state.lock().unwrap().params.min_gas_price;
+                                        if tx.gas_price < min_gas_price {
+                                            return JsonRpcResponse::new_error(req_id.clone(), -32000, &format!("Gas price too low: min {}", min_gas_price));
                                         }
 
                                         {
@@ -334,57 +458,28 @@ impl RpcHandler {
                 } else { Some(serde_json::to_value(JsonRpcResponse::new_error(req_id.clone(), -32602, "Params must be an array")).unwrap()) }
             }
             "eth_getRecentTransactions" => {
-                match self.storage.get_global_transactions() {
-                    Ok(tx_hashes) => {
-                        // K-HIGH-01 Remediation: Pagination logic
-                        let limit = if let Some(arr) = p {
-                            arr.get(0).and_then(|v| v.as_u64()).unwrap_or(20).min(100) as usize
-                        } else { 20 };
-                        let page = if let Some(arr) = p {
-                            arr.get(1).and_then(|v| v.as_u64()).unwrap_or(0) as usize
-                        } else { 0 };
-
-                        let total = tx_hashes.len();
-                        let start = total.saturating_sub((page + 1) * limit);
-                        let end = total.saturating_sub(page * limit);
-                        
-                        let mut result = Vec::new();
-                        if start < total {
-                            let actual_end = std::cmp::min(end, total);
-                            for hash in tx_hashes[start..actual_end].iter().rev() {
-                                let hash_hex = format!("0x{}", hex::encode(hash));
-                                if let Ok(Some(tx)) = self.storage.get_transaction(hash_hex.strip_prefix("0x").unwrap()) {
-                                    let (block_height, block_hash, tx_index) = self.storage.get_transaction_location(hash_hex.strip_prefix("0x").unwrap())
-                                        .ok()
-                                        .flatten()
-                                        .map(|(h, hash, idx)| (format!("0x{:x}", h), format!("0x{}", hash), format!("0x{:x}", idx)))
-                                        .unwrap_or((format!("0x{:x}", 0), "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(), "0x0".to_string()));
-
-                                    result.push(serde_json::json!({
-                                        "hash": hash_hex,
-                                        "from": format!("0x{}", hex::encode(tx.from.as_evm_address())),
-                                        "to": format!("0x{}", hex::encode(tx.to.as_evm_address())),
-                                        "value": format!("0x{:x}", tx.value),
-                                        "nonce": format!("0x{:x}", tx.nonce),
-                                        "blockNumber": block_height,
-                                        "blockHash": block_hash,
-                                        "transactionIndex": tx_index,
-                                        "gas": format!("0x{:x}", tx.gas_limit),
-                                        "gasPrice": format!("0x{:x}", tx.gas_price),
-                                        "input": format!("0x{}", hex::encode(&tx.data)),
-                                        "chainId": format!("0x{:x}", self.chain_id),
-                                        "v": "0x1b", "r": "0x0", "s": "0x0",
-                                        "type": "0x0"
-                                    }));
-                                }
-                            }
-                        }
-                        
+                // K-HIGH-01 Remediation: Pagination logic. The second param is either a page
+                // number (offset from the newest tx) or the `nextCursor` of the previous page.
+                let limit = p.and_then(|arr| arr.first()).and_then(|v| v.as_u64()).unwrap_or(20).clamp(1, 100) as usize;
+                let total = self.storage.transaction_count().unwrap_or(0);
+                let (page, before) = match p.and_then(|arr| arr.get(1)) {
+                    Some(Value::String(cursor)) => (None, u64::from_str_radix(cursor.trim_start_matches("0x"), 16).ok()),
+                    Some(v) => {
+                        let page = v.as_u64().unwrap_or(0);
+                        (Some(page), Some(total.saturating_sub(page.saturating_mul(limit as u64))))
+                    }
+                    None => (Some(0), None),
+                };
+
+                match self.storage.get_recent_transactions(before, limit) {
+                    Ok((hashes, next)) => {
+                        let result: Vec<Value> = hashes.iter().filter_map(|h| self.indexed_tx_json(h)).collect();
                         Some(serde_json::json!({
                             "transactions": result,
                             "page": page,
                             "limit": limit,
-                            "total": total
+                            "total": total,
+                            "nextCursor": next.map(|seq| format!("0x{:x}", seq))
                         }))
                     },
                     _ => Some(serde_json::json!({ "transactions": [], "total": 0 }))
@@ -442,7 +537,7 @@ impl RpcHandler {
                             "parentHash": format!("0x{}", hex::encode(block.header.parent_hash)),
                             "nonce": format!("0x{:016x}", block.header.poh_sequence),
                             "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
-                            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                            "logsBloom": block.header.logs_bloom.to_hex(),
                             "transactionsRoot": format!("0x{}", hex::encode(block.header.transactions_root)),
                             "stateRoot": format!("0x{}", hex::encode(block.header.state_root)),
                             "receiptsRoot": format!("0x{}", hex::encode(block.header.receipts_root)),
@@ -513,7 +608,7 @@ impl RpcHandler {
                             "parentHash": format!("0x{}", hex::encode(block.header.parent_hash)),
                             "nonce": format!("0x{:016x}", block.header.poh_sequence),
                             "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
-                            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                            "logsBloom": block.header.logs_bloom.to_hex(),
                             "transactionsRoot": format!("0x{}", hex::encode(block.header.transactions_root)),
                             "stateRoot": format!("0x{}", hex::encode(block.header.state_root)),
                             "receiptsRoot": format!("0x{}", hex::encode(block.header.receipts_root)),
@@ -536,12 +631,61 @@ impl RpcHandler {
                     _ => Some(serde_json::Value::Null)
                 }
             }
+            // Faucet of testnets and devnets: a signed transfer from the development key,
+            // which holds the faucet balance there. Mainnet has no faucet.
+            "eth_requestDNR" if self.chain_id != crate::parameters::CHAIN_ID => {
+                let addr = match p.and_then(|arr| arr.first()).and_then(|v| v.as_str()).map(crate::address::Address::from_hex) {
+                    Some(Ok(addr)) => addr,
+                    _ => return JsonRpcResponse::new_error(req_id.clone(), -32602, "Expected [address, amount in DNR]"),
+                };
+                let amount_dnr: u128 = p.and_then(|arr| arr.get(1)).and_then(|v| v.as_str()).unwrap_or("10").parse().unwrap_or(10);
+                let Some(amount_wei) = amount_dnr.checked_mul(10u128.pow(18)) else {
+                    return JsonRpcResponse::new_error(req_id.clone(), -32602, "Amount too large");
+                };
+
+                let key_bytes = hex::decode(crate::config::DEV_VALIDATOR_KEY).unwrap();
+                let key = k256::ecdsa::SigningKey::from_bytes(key_bytes.as_slice().into()).unwrap();
+                let faucet_addr = crate::address::Address::from_pubkey(&key.verifying_key().to_sec1_bytes());
+                let (nonce, gas_price) = {
+                    let state = self.state.lock().unwrap();
+                    let account = state.get_account(&faucet_addr);
+                    if account.balance < amount_wei + 21000 * state.params.min_gas_price {
+                        return JsonRpcResponse::new_error(req_id.clone(), -32000, "Faucet balance too low");
+                    }
+                    // After the faucet's transfers still waiting in the mempool
+                    let pending = self.mempool.lock().unwrap().get_all().iter().filter(|tx| tx.from == faucet_addr).count() as u64;
+                    (account.nonce + pending, state.params.min_gas_price)
+                };
+                let mut tx = crate::types::transaction::Transaction {
+                    nonce,
+                    from: faucet_addr,
+                    to: addr,
+                    value: amount_wei,
+                    gas_limit: 21000,
+                    gas_price,
+                    data: vec![],
+                    vm_type: crate::types::transaction::VmType::EVM,
+                    chain_id: self.chain_id,
+                    signature: None,
+                    cached_hash: None,
+                };
+                let signature: k256::ecdsa::Signature = k256::ecdsa::signature::Signer::sign(&key, &tx.hash());
+                tx.signature = Some(signature.to_bytes().to_vec());
+                let added = self.mempool.lock().unwrap().add(tx.clone());
+                if !added {
+                    return JsonRpcResponse::new_error(req_id.clone(), -32000, "Mempool refused the faucet transfer");
+                }
+                let _ = self.network_tx.try_send(crate::network::messages::NetworkMessage::NewTransaction(tx.clone()));
+                println!("[FAUCET] {} DNR to {} (Tx: 0x{})", amount_dnr, addr.to_hex(), hex::encode(tx.hash()));
+                Some(serde_json::to_value(format!("0x{}", hex::encode(tx.hash()))).unwrap())
+            }
             "eth_getValidators" => {
+                let window = self.state.lock().unwrap().params.liveness_window_blocks;
                 let consensus = self.consensus.lock().unwrap();
                 let validators: Vec<serde_json::Value> = consensus.validators.iter().enumerate().map(|(i, v)| {
-                     let uptime_pct = if v.missed_blocks > 0 {
-                         100.0 - (v.missed_blocks as f64 / 100.0)
-                     } else { 100.0 };
+                     // missed_blocks counts misses over the last `window` commits
+                     let uptime_pct = 100.0 - (v.missed_blocks as f64 * 100.0 / window as f64);
+                     let jailed_until = consensus.jailed_validators.get(&v.address);
 
                      serde_json::json!({
                          "id": i + 1,
@@ -550,12 +694,91 @@ impl RpcHandler {
                          "isActive": v.is_active,
                          "commission": format!("{:.2}", v.commission as f64 / 100.0),
                          "missedBlocks": v.missed_blocks,
+                         "consensusPubkey": if v.consensus_pubkey.is_empty() { serde_json::Value::Null } else { format!("0x{}", hex::encode(&v.consensus_pubkey)).into() },
                          "uptime": format!("{:.2}", uptime_pct),
-                         "status": if v.is_active { "Active" } else { "Inactive" }
+                         "jailedUntilSlot": jailed_until,
+                         "status": if jailed_until.is_some() { "Jailed" } else if v.is_active { "Active" } else { "Inactive" }
                      })
                 }).collect();
                 Some(serde_json::to_value(validators).unwrap())
             }
+            "kortana_getValidatorSet" => {
+                match self.epoch_staking(p.and_then(|arr| arr.first())) {
+                    Some(staking) => {
+                        let validators: Vec<Value> = staking.validator_set().iter().map(|v| serde_json::json!({
+                            "address": format!("0x{}", hex::encode(v.address.as_evm_address())),
+                            "moniker": staking.profiles.get(&v.address).map(|p| p.moniker.clone()),
+                            "stake": format!("{}", v.stake),
+                            "isActive": v.is_active,
+                            "commission": format!("{:.2}", v.commission as f64 / 100.0),
+                            "missedBlocks": v.missed_blocks,
+                            "consensusPubkey": if v.consensus_pubkey.is_empty() { Value::Null } else { format!("0x{}", hex::encode(&v.consensus_pubkey)).into() },
+                        })).collect();
+                        Some(serde_json::json!({
+                            "epoch": staking.epoch,
+                            "electedAtHeight": staking.epoch * crate::parameters::BLOCKS_PER_EPOCH,
+                            "validators": validators
+                        }))
+                    }
+                    None => Some(serde_json::to_value(JsonRpcResponse::new_error(req_id.clone(), -32602, "Unknown epoch")).unwrap()),
+                }
+            }
+            "kortana_getUpgradePlan" => {
+                // The pending upgrade and whether this node is ready for it, past upgrades and
+                // the fork activation heights
+                let state = self.state.lock().unwrap();
+                let governance = &state.governance;
+                let plan_json = |plan: &crate::core::governance::UpgradePlan| serde_json::json!({
+                    "proposalId": plan.proposal_id,
+                    "version": plan.version,
+                    "hash": format!("0x{}", hex::encode(plan.hash)),
+                    "height": plan.height,
+                });
+                let plan = governance.upgrade_plan.as_ref().map(|plan| {
+                    let mut json = plan_json(plan);
+                    json["blocksRemaining"] = plan.height.saturating_sub(current_height).into();
+                    json["nodeReady"] = (plan.version == crate::parameters::NODE_VERSION).into();
+                    json
+                });
+                let forks: serde_json::Map<String, Value> = governance.forks.activations.iter()
+                    .map(|(fork, height)| (format!("{:?}", fork), Value::from(*height)))
+                    .collect();
+                Some(serde_json::json!({
+                    "nodeVersion": crate::parameters::NODE_VERSION,
+                    "plan": plan,
+                    "upgrades": governance.upgrades.values().map(plan_json).collect::<Vec<_>>(),
+                    "forks": forks,
+                }))
+            }
+            "kortana_getLeaderSchedule" => {
+                // Leaders indexed by slot % slotsPerEpoch; an optional second parameter
+                // narrows the answer to the slot indices of one validator.
+                let validator = match p.and_then(|arr| arr.get(1)).and_then(|v| v.as_str()) {
+                    Some(addr) => match crate::address::Address::from_hex(addr) {
+                        Ok(a) => Some(a),
+                        Err(_) => return JsonRpcResponse::new_error(req_id, -32602, "Invalid validator address"),
+                    },
+                    None => None,
+                };
+                match self.epoch_staking(p.and_then(|arr| arr.first()).filter(|v| !v.is_null())) {
+                    Some(staking) => {
+                        let schedule = staking.leader_schedule();
+                        let mut result = serde_json::json!({
+                            "epoch": schedule.epoch,
+                            "seed": format!("0x{}", hex::encode(schedule.seed)),
+                            "slotsPerEpoch": crate::parameters::BLOCKS_PER_EPOCH,
+                        });
+                        match validator {
+                            Some(v) => result["slots"] = serde_json::json!(schedule.slots_of(&v)),
+                            None => result["leaders"] = schedule.leaders.iter()
+                                .map(|a| Value::from(format!("0x{}", hex::encode(a.as_evm_address()))))
+                                .collect(),
+                        }
+                        Some(result)
+                    }
+                    None => Some(serde_json::to_value(JsonRpcResponse::new_error(req_id.clone(), -32602, "Unknown epoch")).unwrap()),
+                }
+            }
             "eth_pendingTransactions" => {
                 let mempool = self.mempool.lock().unwrap();
                 let txs = mempool.get_all();
@@ -578,34 +801,44 @@ impl RpcHandler {
             }
             "net_version" => Some(serde_json::to_value(self.chain_id.to_string()).unwrap()),
             "net_listening" => Some(serde_json::to_value(true).unwrap()),
-            "eth_syncing" => Some(serde_json::to_value(false).unwrap()),
+            "eth_syncing" => match *self.sync_progress.lock().unwrap() {
+                Some(progress) if progress.highest_block > current_height => Some(serde_json::json!({
+                    "startingBlock": format!("0x{:x}", progress.starting_block),
+                    "currentBlock": format!("0x{:x}", current_height),
+                    "highestBlock": format!("0x{:x}", progress.highest_block),
+                })),
+                _ => Some(serde_json::to_value(false).unwrap()),
+            },
             "eth_protocolVersion" => Some(serde_json::to_value("0x41").unwrap()),
             "web3_clientVersion" => Some(serde_json::to_value("Kortana/v1.0.0/rust").unwrap()),
             "eth_getAddressHistory" => {
+                // Params: [address, { limit, cursor }?]. Without options the newest page is
+                // returned as a plain array, as before; with options the response carries the
+                // cursor for the next (older) page.
                 if let Some(arr) = p {
                     if let Some(addr_str) = arr.first().and_then(|v| v.as_str()) {
                         if let Ok(addr) = crate::address::Address::from_hex(addr_str) {
-                            let hashes = self.storage.get_address_history(&addr).unwrap_or_default();
-                            let mut txs = Vec::new();
-                            for hash in hashes {
-                                if let Ok(Some(tx)) = self.storage.get_transaction(&hex::encode(hash)) {
-                                    txs.push(serde_json::json!({
-                                        "hash": format!("0x{}", hex::encode(tx.hash())),
-                                        "nonce": format!("0x{:x}", tx.nonce),
-                                        "from": format!("0x{}", hex::encode(tx.from.as_evm_address())), 
-                                        "to": format!("0x{}", hex::encode(tx.to.as_evm_address())),
-                                        "value": format!("0x{:x}", tx.value),
-                                        "gas": format!("0x{:x}", tx.gas_limit),
-                                        "gasPrice": format!("0x{:x}", tx.gas_price),
-                                        "input": format!("0x{}", hex::encode(&tx.data)),
-                                        "chainId": format!("0x{:x}", self.chain_id),
-                                        "v": "0x1b", "r": "0x0", "s": "0x0",
-                                        "type": "0x0"
-                                    }));
-                                }
+                            let opts = arr.get(1).and_then(|v| v.as_object());
+                            let limit = opts.and_then(|o| o.get("limit")).and_then(|v| v.as_u64()).unwrap_or(100).clamp(1, 1000) as usize;
+                            let cursor = opts.and_then(|o| o.get("cursor")).and_then(|v| v.as_str());
+                            let before = match cursor {
+                                Some(c) => match crate::storage::index::decode_position_cursor(c) {
+                                    Some(pos) => Some(pos),
+                                    None => return JsonRpcResponse::new_error(req_id.clone(), -32602, "Invalid cursor"),
+                                },
+                                None => None,
+                            };
+
+                            let (hashes, next) = self.storage.get_address_transactions(&addr, before, limit).unwrap_or_default();
+                            let txs: Vec<Value> = hashes.iter().filter_map(|h| self.indexed_tx_json(h)).collect();
+                            if opts.is_some() {
+                                Some(serde_json::json!({
+                                    "transactions": txs,
+                                    "nextCursor": next.map(crate::storage::index::encode_position_cursor)
+                                }))
+                            } else {
+                                Some(serde_json::to_value(txs).unwrap())
                             }
-                            txs.reverse(); // Latest first
-                            Some(serde_json::to_value(txs).unwrap())
                         } else { None }
                     } else { None }
                 } else { None }
@@ -721,7 +954,7 @@ impl RpcHandler {
                                          .unwrap_or(serde_json::Value::Null),
                                      "logs": logs,
                                      "status": format!("0x{:x}", receipt.status),
-                                     "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+                                     "logsBloom": receipt.logs_bloom.to_hex(),
                                      "type": "0x0"
                                  }))
                              },
@@ -736,44 +969,57 @@ impl RpcHandler {
                 let filter = RpcFilter {
                     filter_type: FilterType::Block,
                     last_poll_block: current_height,
+                    event_seq: self.chain_events.lock().unwrap().next_seq(),
                 };
                 self.filters.lock().unwrap().insert(filter_id.clone(), filter);
                 Some(serde_json::to_value(filter_id).unwrap())
             }
-            "eth_newFilter" => {
+            "kortana_newChainEventFilter" => {
                 let filter_id = format!("0x{}", uuid::Uuid::new_v4());
-                
-                let (address, topics, from_block) = if let Some(arr) = p {
-                    if let Some(obj) = arr.first().and_then(|v| v.as_object()) {
-                        let addr = match obj.get("address") {
-                            Some(Value::String(s)) => Some(vec![s.clone()]),
-                            Some(Value::Array(a)) => Some(a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect()),
-                            _ => None,
-                        };
-                        let topics = obj.get("topics").and_then(|v| v.as_array().cloned());
-                        let from = obj.get("fromBlock").and_then(|v| v.as_str()).map(|s| {
-                            if s == "latest" { current_height }
-                            else { u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).unwrap_or(current_height) }
-                        }).unwrap_or(current_height);
-                        (addr, topics, from)
-                    } else { (None, None, current_height) }
-                } else { (None, None, current_height) };
-
                 let filter = RpcFilter {
-                    filter_type: FilterType::Logs { 
-                        address, 
-                        topics, 
-                    },
-                    last_poll_block: from_block.saturating_sub(1),
+                    filter_type: FilterType::ChainEvents,
+                    last_poll_block: current_height,
+                    event_seq: self.chain_events.lock().unwrap().next_seq(),
                 };
                 self.filters.lock().unwrap().insert(filter_id.clone(), filter);
                 Some(serde_json::to_value(filter_id).unwrap())
             }
+            "eth_newFilter" => {
+                let obj = p.and_then(|arr| arr.first()).and_then(|v| v.as_object());
+                let parsed = match obj {
+                    Some(o) => LogFilter::from_json(o).and_then(|f| {
+                        filter::parse_block_tag(o.get("fromBlock"), current_height, current_height).map(|from| (f, from))
+                    }),
+                    None => Ok((LogFilter::default(), current_height)),
+                };
+                match parsed {
+                    Ok((log_filter, from_block)) => {
+                        let filter_id = format!("0x{}", uuid::Uuid::new_v4());
+                        let filter = RpcFilter {
+                            filter_type: FilterType::Logs(log_filter),
+                            last_poll_block: from_block.saturating_sub(1),
+                            event_seq: self.chain_events.lock().unwrap().next_seq(),
+                        };
+                        self.filters.lock().unwrap().insert(filter_id.clone(), filter);
+                        Some(serde_json::to_value(filter_id).unwrap())
+                    }
+                    Err(e) => return JsonRpcResponse::new_error(req_id.clone(), -32602, &e),
+                }
+            }
             "eth_getFilterChanges" | "eth_getFilterLogs" => {
                 if let Some(arr) = p {
                     if let Some(filter_id) = arr.first().and_then(|v| v.as_str()) {
                         let mut filters = self.filters.lock().unwrap();
                         if let Some(filter) = filters.get_mut(filter_id) {
+                            // After a reorg, re-deliver everything above the common ancestor
+                            let chain_events = self.chain_events.lock().unwrap();
+                            let new_events: Vec<Value> = chain_events.since(filter.event_seq).map(|reorg| {
+                                filter.last_poll_block = filter.last_poll_block.min(reorg.common_ancestor_height);
+                                events::reorg_json(reorg)
+                            }).collect();
+                            filter.event_seq = chain_events.next_seq();
+                            drop(chain_events);
+
                             let mut start_block = filter.last_poll_block + 1;
                             let end_block = current_height;
                             
@@ -795,96 +1041,48 @@ impl RpcHandler {
                                     }
                                     Some(Value::Array(hashes))
                                 }
-                                FilterType::Logs { address, topics: _ } => {
-                                    let mut logs = Vec::new();
-                                    for h in start_block..=end_block {
-                                        if let Ok(Some(b)) = self.storage.get_block(h) {
-                                            let b_hash = format!("0x{}", hex::encode(b.header.hash()));
-                                            let b_num = format!("0x{:x}", b.header.height);
-                                            for (tx_idx, tx) in b.transactions.iter().enumerate() {
-                                                let tx_hash = format!("0x{}", hex::encode(tx.hash()));
-                                                if let Ok(Some(receipt)) = self.storage.get_receipt(&tx_hash[2..]) {
-                                                    for (log_idx, log) in receipt.logs.iter().enumerate() {
-                                                        // Apply Filter
-                                                        let log_addr = format!("0x{}", hex::encode(log.address.as_evm_address()));
-                                                        if let Some(addrs) = address {
-                                                            if !addrs.contains(&log_addr) { continue; }
-                                                        }
-                                                        // Topic matching omitted for brevity but often topics match address too
-                                                        logs.push(serde_json::json!({
-                                                            "address": log_addr,
-                                                            "topics": log.topics.iter().map(|t| format!("0x{}", hex::encode(t))).collect::<Vec<_>>(),
-                                                            "data": format!("0x{}", hex::encode(&log.data)),
-                                                            "blockNumber": b_num,
-                                                            "transactionHash": tx_hash,
-                                                            "transactionIndex": format!("0x{:x}", tx_idx),
-                                                            "blockHash": b_hash,
-                                                            "logIndex": format!("0x{:x}", log_idx),
-                                                            "removed": false
-                                                        }));
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Some(Value::Array(logs))
+                                FilterType::Logs(log_filter) => {
+                                    Some(Value::Array(self.collect_logs(log_filter, start_block, end_block).unwrap_or_default()))
                                 }
+                                FilterType::ChainEvents => Some(Value::Array(new_events)),
                             }
                         } else { Some(serde_json::Value::Array(vec![])) }
                     } else { Some(serde_json::Value::Array(vec![])) }
                 } else { Some(serde_json::Value::Array(vec![])) }
             }
             "eth_getLogs" => {
-                if let Some(arr) = p {
-                    if let Some(obj) = arr.first().and_then(|v| v.as_object()) {
-                        let from_block = obj.get("fromBlock").and_then(|v| v.as_str()).map(|s| {
-                            if s == "latest" { current_height }
-                            else { u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).unwrap_or(current_height) }
-                        }).unwrap_or(current_height.saturating_sub(100)); // Default to last 100 blocks
-                        
-                        let to_block = obj.get("toBlock").and_then(|v| v.as_str()).map(|s| {
-                            if s == "latest" { current_height }
-                            else { u64::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).unwrap_or(current_height) }
-                        }).unwrap_or(current_height);
-
-                        let addr_filter = match obj.get("address") {
-                            Some(Value::String(s)) => Some(vec![s.clone().to_lowercase()]),
-                            Some(Value::Array(a)) => Some(a.iter().filter_map(|v| v.as_str().map(|s| s.to_string().to_lowercase())).collect()),
-                            _ => None,
-                        };
+                let obj = match p.and_then(|arr| arr.first()).and_then(|v| v.as_object()) {
+                    Some(o) => o,
+                    None => return JsonRpcResponse::new_error(req_id.clone(), -32602, "Expected a filter object"),
+                };
+                let log_filter = match LogFilter::from_json(obj) {
+                    Ok(f) => f,
+                    Err(e) => return JsonRpcResponse::new_error(req_id.clone(), -32602, &e),
+                };
 
-                        let mut logs = Vec::new();
-                        for h in from_block..=to_block {
-                            if let Ok(Some(b)) = self.storage.get_block(h) {
-                                let b_hash = format!("0x{}", hex::encode(b.header.hash()));
-                                let b_num = format!("0x{:x}", b.header.height);
-                                for (tx_idx, tx) in b.transactions.iter().enumerate() {
-                                    let tx_hash_hex = format!("0x{}", hex::encode(tx.hash()));
-                                    if let Ok(Some(receipt)) = self.storage.get_receipt(&tx_hash_hex[2..]) {
-                                        for (log_idx, log) in receipt.logs.iter().enumerate() {
-                                            let log_addr = format!("0x{}", hex::encode(log.address.as_evm_address()));
-                                            if let Some(addrs) = &addr_filter {
-                                                if !addrs.contains(&log_addr.to_lowercase()) { continue; }
-                                            }
-                                            logs.push(serde_json::json!({
-                                                "address": log_addr,
-                                                "topics": log.topics.iter().map(|t| format!("0x{}", hex::encode(t))).collect::<Vec<_>>(),
-                                                "data": format!("0x{}", hex::encode(&log.data)),
-                                                "blockNumber": b_num,
-                                                "transactionHash": tx_hash_hex,
-                                                "transactionIndex": format!("0x{:x}", tx_idx),
-                                                "blockHash": b_hash,
-                                                "logIndex": format!("0x{:x}", log_idx),
-                                                "removed": false
-                                            }));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Some(Value::Array(logs))
-                    } else { Some(serde_json::Value::Array(vec![])) }
-                } else { Some(serde_json::Value::Array(vec![])) }
+                let range = if let Some(hash) = obj.get("blockHash").and_then(|v| v.as_str()) {
+                    if obj.contains_key("fromBlock") || obj.contains_key("toBlock") {
+                        return JsonRpcResponse::new_error(req_id.clone(), -32602, "blockHash cannot be combined with fromBlock/toBlock");
+                    }
+                    match self.storage.get_block_by_hash(hash) {
+                        Ok(Some(b)) => Ok((b.header.height, b.header.height)),
+                        _ => return JsonRpcResponse::new_error(req_id.clone(), -32000, "Unknown block"),
+                    }
+                } else {
+                    filter::parse_block_tag(obj.get("fromBlock"), current_height, current_height)
+                        .and_then(|from| filter::parse_block_tag(obj.get("toBlock"), current_height, current_height).map(|to| (from, to.min(current_height))))
+                };
+
+                match range {
+                    Ok((from, to)) if to >= from && to - from >= MAX_LOG_BLOCK_RANGE => {
+                        return JsonRpcResponse::new_error(req_id.clone(), -32005, &format!("Query exceeds max block range {}", MAX_LOG_BLOCK_RANGE));
+                    }
+                    Ok((from, to)) => match self.collect_logs(&log_filter, from, to) {
+                        Ok(logs) => Some(Value::Array(logs)),
+                        Err(e) => return JsonRpcResponse::new_error(req_id.clone(), -32000, &e),
+                    },
+                    Err(e) => return JsonRpcResponse::new_error(req_id.clone(), -32602, &e),
+                }
             }
             "eth_getTransactionByBlockNumberAndIndex" | "eth_getTransactionByBlockHashAndIndex" => {
                  if let Some(arr) = p {
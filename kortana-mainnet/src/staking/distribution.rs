@@ -0,0 +1,176 @@
+// File: src/staking/distribution.rs
+//
+// F1 reward distribution. Block rewards are not pushed to every delegator as they are
+// minted: each validator keeps the cumulative reward per unit of stake, closed into a
+// new period whenever its stake changes, and a delegator's rewards are its stake times
+// the growth of that sum since it last touched its delegation, reduced by any slashes
+// in between. Work per block is constant however many delegators a validator has.
+
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use crate::address::Address;
+
+/// Fixed-point scale of the cumulative reward ratios; a validator's stake is at least
+/// `MIN_VALIDATOR_STAKE` (1e24 wei), so this keeps rounding below a wei per period.
+pub const RATIO_PRECISION: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+fn mul_div(a: u128, b: u128, divisor: u128) -> u128 {
+    let result = ethnum::U256::from(a) * ethnum::U256::from(b) / ethnum::U256::from(divisor);
+    u128::try_from(result).unwrap_or(u128::MAX)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorRewards {
+    /// Period currently accumulating rewards.
+    pub period: u64,
+    /// Delegator rewards allocated in the current period.
+    pub current: u128,
+    /// Cumulative reward per unit of stake, scaled by `RATIO_PRECISION`, at the end of
+    /// each period still referenced by a delegator.
+    pub historical: BTreeMap<u64, u128>,
+    /// Slashes as (period they closed, fraction in basis points), oldest first.
+    pub slashes: Vec<(u64, u16)>,
+    /// Commission the operator has not withdrawn yet.
+    pub commission: u128,
+    /// Delegator rewards allocated and not withdrawn yet.
+    pub outstanding: u128,
+}
+
+impl Default for ValidatorRewards {
+    fn default() -> Self {
+        Self {
+            period: 1,
+            current: 0,
+            historical: BTreeMap::from([(0, 0)]),
+            slashes: Vec::new(),
+            commission: 0,
+            outstanding: 0,
+        }
+    }
+}
+
+impl ValidatorRewards {
+    fn ratio(&self, period: u64) -> u128 {
+        self.historical.get(&period).copied().unwrap_or(0)
+    }
+
+    /// Rewards of `start` up to the end of `end_period`, following its stake through
+    /// the slashes in between.
+    fn earned(&self, start: &DelegatorRewards, end_period: u64) -> u128 {
+        let mut stake = start.stake;
+        let mut from = start.period;
+        let mut earned = 0u128;
+        for &(period, fraction_bps) in self.slashes.iter().filter(|(p, _)| *p > start.period && *p <= end_period) {
+            earned += mul_div(stake, self.ratio(period) - self.ratio(from), RATIO_PRECISION);
+            stake -= stake * fraction_bps as u128 / 10000;
+            from = period;
+        }
+        earned + mul_div(stake, self.ratio(end_period) - self.ratio(from), RATIO_PRECISION)
+    }
+}
+
+/// Where a delegator's reward accounting on one validator starts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DelegatorRewards {
+    /// Last period closed before the delegation last changed.
+    pub period: u64,
+    /// Stake delegated since then.
+    pub stake: u128,
+    /// Rewards settled on earlier changes and not withdrawn yet.
+    pub accrued: u128,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Distribution {
+    pub validators: BTreeMap<Address, ValidatorRewards>,
+    pub delegators: BTreeMap<Address, BTreeMap<Address, DelegatorRewards>>, // validator -> delegator -> start
+}
+
+impl Distribution {
+    /// Credits `reward` to `validator`: the commission to its operator, the rest to
+    /// its delegators in proportion to stake.
+    pub fn allocate(&mut self, validator: Address, reward: u128, commission_bps: u16) {
+        let v = self.validators.entry(validator).or_default();
+        let commission = mul_div(reward, commission_bps as u128, 10000);
+        v.commission += commission;
+        v.current += reward - commission;
+        v.outstanding += reward - commission;
+    }
+
+    /// Closes the current period of `validator`, whose total stake during it was
+    /// `stake`. Returns the closed period and the rewards of a period without stake,
+    /// which nobody can claim.
+    pub fn close_period(&mut self, validator: Address, stake: u128) -> (u64, u128) {
+        let v = self.validators.entry(validator).or_default();
+        let (growth, unclaimable) = match stake {
+            0 => (0, v.current),
+            _ => (mul_div(v.current, RATIO_PRECISION, stake), 0),
+        };
+        let closed = v.period;
+        let ratio = v.ratio(closed - 1).saturating_add(growth);
+        v.historical.insert(closed, ratio);
+        v.outstanding -= unclaimable;
+        v.current = 0;
+        v.period += 1;
+        (closed, unclaimable)
+    }
+
+    /// Closes a period at the stake before a slash of `fraction_bps`, so the rewards
+    /// earned before it are paid on the unslashed stake.
+    pub fn record_slash(&mut self, validator: Address, stake: u128, fraction_bps: u16) -> u128 {
+        let (closed, unclaimable) = self.close_period(validator, stake);
+        self.validators.get_mut(&validator).unwrap().slashes.push((closed, fraction_bps));
+        unclaimable
+    }
+
+    /// Settles what `delegator` earned on `validator` (total stake `stake`) into its
+    /// accrued rewards; call before its delegation changes, then `restart`.
+    pub fn settle(&mut self, validator: Address, delegator: Address, stake: u128) -> u128 {
+        let (closed, unclaimable) = self.close_period(validator, stake);
+        let v = &self.validators[&validator];
+        let start = self.delegators.entry(validator).or_default().entry(delegator)
+            .or_insert(DelegatorRewards { period: closed, stake: 0, accrued: 0 });
+        start.accrued += v.earned(start, closed);
+        start.period = closed;
+        unclaimable
+    }
+
+    /// Restarts the accounting of a settled delegation at its new `stake`.
+    pub fn restart(&mut self, validator: Address, delegator: Address, stake: u128) {
+        let starts = self.delegators.entry(validator).or_default();
+        if let Some(start) = starts.get_mut(&delegator) {
+            start.stake = stake;
+            if stake == 0 && start.accrued == 0 {
+                starts.remove(&delegator);
+            }
+        }
+        self.prune(validator);
+    }
+
+    /// Pays out the settled rewards of `delegator`, plus the commission if it is the
+    /// operator of `validator`.
+    pub fn take(&mut self, validator: Address, delegator: Address) -> u128 {
+        let mut paid = 0;
+        let Some(v) = self.validators.get_mut(&validator) else { return 0 };
+        if let Some(start) = self.delegators.get_mut(&validator).and_then(|s| s.get_mut(&delegator)) {
+            // Rounding never lets a delegator take more than was allocated
+            paid = start.accrued.min(v.outstanding);
+            v.outstanding -= paid;
+            start.accrued = 0;
+        }
+        if delegator == validator {
+            paid += std::mem::take(&mut v.commission);
+        }
+        paid
+    }
+
+    /// Drops the ratios and slashes no delegation can reference any more.
+    fn prune(&mut self, validator: Address) {
+        let Some(v) = self.validators.get_mut(&validator) else { return };
+        let oldest = self.delegators.get(&validator)
+            .and_then(|starts| starts.values().map(|s| s.period).min())
+            .unwrap_or(v.period - 1);
+        v.historical.retain(|period, _| *period >= oldest);
+        v.slashes.retain(|(period, _)| *period > oldest);
+    }
+}
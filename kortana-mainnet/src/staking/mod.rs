@@ -1,31 +1,160 @@
 // File: src/staking/mod.rs
 
+pub mod distribution;
+
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use crate::address::Address;
+use crate::consensus::ValidatorInfo;
+use crate::consensus::leader_schedule::{epoch_seed, LeaderSchedule};
+use distribution::Distribution;
 use crate::parameters::*;
+use sha3::{Digest, Keccak256};
+
+/// Storage slot of the staking system account that holds `StakingStore::root`, which
+/// commits the staking store to the state root.
+pub const STAKING_ROOT_SLOT: [u8; 32] = [0u8; 32];
 
+/// Checks that `pubkey` is a BLS12-381 public key and that `proof` proves possession
+/// of it, so nobody can register a key crafted to cancel out others' keys in an
+/// aggregate signature.
+fn check_consensus_key(pubkey: &[u8], proof: &[u8]) -> Result<(), String> {
+    if !crate::crypto::bls::is_valid_public_key(pubkey) {
+        return Err("Consensus key is not a valid BLS12-381 public key".to_string());
+    }
+    if !crate::crypto::bls::verify_possession(pubkey, proof) {
+        return Err("Invalid proof of possession for consensus key".to_string());
+    }
+    Ok(())
+}
+
+/// The account at `STAKING_CONTRACT_ADDRESS`; staking transactions are sent to it.
+pub fn staking_address() -> Address {
+    Address::from_hex(STAKING_CONTRACT_ADDRESS).expect("Staking contract address is valid hex")
+}
+
+/// All stake `delegator` has bonded to `validator`; one per pair.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Delegation {
     pub delegator: Address,
     pub validator: Address,
     pub amount: u128,
-    pub start_block: u64,
+    pub start_block: u64, // height of the first delegation
 }
 
+/// Stake on its way back to `delegator`. Until `release_block` it still answers for
+/// offences `validator` committed at or before `creation_height`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnbondingRequest {
     pub delegator: Address,
     pub validator: Address,
     pub amount: u128,
+    pub creation_height: u64,
     pub release_block: u64,
 }
 
+/// Stake moved from `source` to `destination` at `creation_height` without unbonding.
+/// Until `release_block` the delegation to `destination` answers for offences `source`
+/// committed at or before then, and the stake cannot move on again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Redelegation {
+    pub delegator: Address,
+    pub source: Address,
+    pub destination: Address,
+    pub amount: u128,
+    pub creation_height: u64,
+    pub release_block: u64,
+}
+
+/// A registered validator, created by a create-validator transaction or at genesis.
+/// Commission changes are bounded by `max_commission_bps`, move at most
+/// `max_change_bps` at a time and at most once per epoch; they take effect at the next
+/// election.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorProfile {
+    pub moniker: String,
+    pub commission_bps: u16,
+    pub max_commission_bps: u16,
+    pub max_change_bps: u16,
+    /// Epoch of the last commission change.
+    pub commission_epoch: u64,
+    /// Set by a voluntary exit; the validator is no longer a candidate.
+    pub exited: bool,
+}
+
+impl ValidatorProfile {
+    pub fn new(moniker: &str, commission_bps: u16, max_commission_bps: u16, max_change_bps: u16) -> Self {
+        Self {
+            moniker: moniker.to_string(),
+            commission_bps,
+            max_commission_bps,
+            max_change_bps,
+            commission_epoch: 0,
+            exited: false,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.moniker.len() > MAX_MONIKER_LEN {
+            return Err(format!("Moniker is longer than {} bytes", MAX_MONIKER_LEN));
+        }
+        if !is_valid_commission_rate(self.max_commission_bps) {
+            return Err("Maximum commission exceeds 100%".to_string());
+        }
+        if self.commission_bps > self.max_commission_bps {
+            return Err("Commission exceeds the maximum commission".to_string());
+        }
+        if self.max_change_bps > self.max_commission_bps {
+            return Err("Maximum commission change exceeds the maximum commission".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Whether a validator signed each of the last `liveness_window_blocks` commits it was
+/// expected to sign, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Liveness {
+    pub window: VecDeque<bool>,
+    pub missed: u64,
+}
+
+/// Staking state of the chain. Ordered collections throughout, so that every node
+/// serializes (and so hashes) the same store identically.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakingStore {
-    pub delegations: HashMap<Address, Vec<Delegation>>, // validator -> delegations
+    pub delegations: BTreeMap<Address, BTreeMap<Address, Delegation>>, // validator -> delegator -> delegation
     pub unbonding: Vec<UnbondingRequest>,
-    pub min_self_stake: u128,
+    #[serde(default)]
+    pub redelegations: Vec<Redelegation>,
+    pub consensus_keys: BTreeMap<Address, Vec<u8>>, // validator -> BLS12-381 consensus public key
+    pub slashed_offences: BTreeSet<[u8; 32]>, // offences already punished, see Evidence::offence_id
+    #[serde(default)]
+    pub jailed: BTreeMap<Address, u64>, // validator -> slot from which it may unjail
+    #[serde(default)]
+    pub liveness: BTreeMap<Address, Liveness>,
+    #[serde(default)]
+    pub last_commit_height: u64, // height of the newest commit recorded by record_commit
+    /// Epoch of `validators`; see `elect_validators`.
+    #[serde(default)]
+    pub epoch: u64,
+    /// Validator set elected for the current epoch, ordered by address.
+    #[serde(default)]
+    pub validators: Vec<ValidatorInfo>,
+    /// Leader schedule drawn for the current epoch at its election.
+    #[serde(default)]
+    pub schedule: LeaderSchedule,
+    /// Reward accounting of validators and their delegators.
+    #[serde(default)]
+    pub distribution: Distribution,
+    /// Every validator ever registered, exited ones included.
+    #[serde(default)]
+    pub profiles: BTreeMap<Address, ValidatorProfile>,
+    /// All DNR in existence: balances, stake, unbonding stake, unwithdrawn rewards and
+    /// governance deposits.
+    /// Grows with block rewards; fees, slashes and unclaimable rewards are burned.
+    #[serde(default)]
+    pub total_supply: u128,
 }
 
 impl Default for StakingStore {
@@ -37,60 +166,453 @@ impl Default for StakingStore {
 impl StakingStore {
     pub fn new() -> Self {
         Self {
-            delegations: HashMap::new(),
+            delegations: BTreeMap::new(),
             unbonding: Vec::new(),
-            min_self_stake: MIN_VALIDATOR_STAKE,
+            redelegations: Vec::new(),
+            consensus_keys: BTreeMap::new(),
+            slashed_offences: BTreeSet::new(),
+            jailed: BTreeMap::new(),
+            liveness: BTreeMap::new(),
+            last_commit_height: 0,
+            epoch: 0,
+            validators: Vec::new(),
+            schedule: LeaderSchedule::default(),
+            distribution: Distribution::default(),
+            profiles: BTreeMap::new(),
+            total_supply: 0,
         }
     }
 
     pub fn delegate(&mut self, delegator: Address, validator: Address, amount: u128, height: u64) {
-        let entry = self.delegations.entry(validator).or_default();
-        entry.push(Delegation {
+        self.settle_rewards(validator, delegator);
+        self.delegations.entry(validator).or_default().entry(delegator)
+            .or_insert(Delegation { delegator, validator, amount: 0, start_block: height })
+            .amount += amount;
+        self.restart_rewards(validator, delegator);
+    }
+
+    /// Starts unbonding `amount` of the stake `delegator` has on `validator`; it is paid
+    /// out by `process_matured_unbonding` after the unbonding period.
+    pub fn undelegate(&mut self, delegator: Address, validator: Address, amount: u128, height: u64, params: &ChainParams) -> Result<(), String> {
+        self.check_unbond(delegator, validator, amount, params)?;
+        self.remove_stake(delegator, validator, amount);
+        self.unbonding.push(UnbondingRequest {
             delegator,
             validator,
             amount,
-            start_block: height,
+            creation_height: height,
+            release_block: height + params.unbonding_period_blocks,
         });
+        Ok(())
     }
 
-    pub fn undelegate(&mut self, delegator: Address, validator: Address, amount: u128, height: u64) -> Result<(), String> {
-        let entry = self.delegations.get_mut(&validator).ok_or("Validator not found")?;
-        
-        let mut found = false;
-        for d in entry.iter_mut() {
-            if d.delegator == delegator && d.amount >= amount {
-                d.amount -= amount;
-                found = true;
-                break;
-            }
+    /// Moves `amount` of the stake `delegator` has on `source` to the registered validator
+    /// `destination` at once. Stake that arrived at `source` by a redelegation that has
+    /// not matured cannot move on, so its liability for the first validator is never lost.
+    pub fn redelegate(&mut self, delegator: Address, source: Address, destination: Address, amount: u128, height: u64, params: &ChainParams) -> Result<(), String> {
+        if source == destination {
+            return Err("Cannot redelegate to the same validator".to_string());
         }
-
-        if !found {
-            return Err("Insufficient delegation to undelegate".to_string());
+        if !self.is_registered(&destination) {
+            return Err("Destination is not a registered validator".to_string());
         }
-
-        // Add to unbonding queue
-        self.unbonding.push(UnbondingRequest {
+        if self.redelegations.iter().any(|r| r.delegator == delegator && r.destination == source && r.release_block > height) {
+            return Err("Stake redelegated to the source validator has not matured yet".to_string());
+        }
+        self.check_unbond(delegator, source, amount, params)?;
+        self.remove_stake(delegator, source, amount);
+        self.delegate(delegator, destination, amount, height);
+        self.redelegations.push(Redelegation {
             delegator,
-            validator,
+            source,
+            destination,
             amount,
-            release_block: height + UNBONDING_PERIOD_BLOCKS,
+            creation_height: height,
+            release_block: height + params.unbonding_period_blocks,
         });
+        Ok(())
+    }
+
+    /// Whether `delegator` may take `amount` off `validator`: it must have that much
+    /// bonded, and a registered validator's own stake cannot drop below the minimum.
+    fn check_unbond(&self, delegator: Address, validator: Address, amount: u128, params: &ChainParams) -> Result<(), String> {
+        let bonded = self.delegations.get(&validator).and_then(|ds| ds.get(&delegator))
+            .ok_or("No delegation to this validator")?.amount;
+        if bonded < amount {
+            return Err("Insufficient delegation to undelegate".to_string());
+        }
+        if delegator == validator && self.is_registered(&validator) && bonded - amount < params.min_validator_stake {
+            return Err("Self-bond cannot fall below the minimum validator stake; exit the validator instead".to_string());
+        }
+        Ok(())
+    }
+
+    /// Takes `amount` off the delegation of `delegator` to `validator`, settling its
+    /// rewards first, and drops the delegation once empty. The caller checks the amount.
+    fn remove_stake(&mut self, delegator: Address, validator: Address, amount: u128) {
+        self.settle_rewards(validator, delegator);
+        let ds = self.delegations.get_mut(&validator).expect("Delegation exists");
+        let d = ds.get_mut(&delegator).expect("Delegation exists");
+        d.amount -= amount;
+        if d.amount == 0 {
+            ds.remove(&delegator);
+            if ds.is_empty() {
+                self.delegations.remove(&validator);
+            }
+        }
+        self.restart_rewards(validator, delegator);
+    }
+
+    /// Registers (or rotates) the BLS key `validator` signs consensus votes with. Only
+    /// validators with a self-delegation can register, and `proof` must prove possession
+    /// of the key.
+    pub fn register_consensus_key(&mut self, validator: Address, pubkey: Vec<u8>, proof: &[u8]) -> Result<(), String> {
+        if self.self_bond(&validator) == 0 {
+            return Err("Only a self-bonded validator can register a consensus key".to_string());
+        }
+        check_consensus_key(&pubkey, proof)?;
+        self.consensus_keys.insert(validator, pubkey);
+        Ok(())
+    }
+
+    /// True if `validator` is registered and has not exited.
+    pub fn is_registered(&self, validator: &Address) -> bool {
+        self.profiles.get(validator).is_some_and(|p| !p.exited)
+    }
+
+    /// Registers `operator` as a validator with `profile`, bonding `self_bond` of its
+    /// own stake on top of any it already has; together they must reach
+    /// the minimum validator stake. An exited validator may register again. Nothing
+    /// changes on error.
+    pub fn create_validator(
+        &mut self,
+        operator: Address,
+        mut profile: ValidatorProfile,
+        consensus_key: Option<(Vec<u8>, &[u8])>,
+        self_bond: u128,
+        height: u64,
+        params: &ChainParams,
+    ) -> Result<(), String> {
+        if self.is_registered(&operator) {
+            return Err("Validator is already registered".to_string());
+        }
+        profile.validate()?;
+        if self.self_bond(&operator) + self_bond < params.min_validator_stake {
+            return Err("Self-bond is below the minimum validator stake".to_string());
+        }
+        if let Some((pubkey, proof)) = &consensus_key {
+            check_consensus_key(pubkey, proof)?;
+        }
+
+        if self_bond > 0 {
+            self.delegate(operator, operator, self_bond, height);
+        }
+        if let Some((pubkey, _)) = consensus_key {
+            self.consensus_keys.insert(operator, pubkey);
+        }
+        profile.commission_epoch = self.epoch;
+        profile.exited = false;
+        self.profiles.insert(operator, profile);
+        Ok(())
+    }
+
+    /// Changes the commission and/or moniker of a registered validator. The commission
+    /// must stay within the validator's maximum, move by at most its maximum change,
+    /// and may only change once per epoch.
+    pub fn edit_validator(&mut self, operator: Address, commission_bps: Option<u16>, moniker: Option<String>) -> Result<(), String> {
+        if !self.is_registered(&operator) {
+            return Err("Validator is not registered".to_string());
+        }
+        let epoch = self.epoch;
+        let profile = self.profiles.get_mut(&operator).unwrap();
+        let mut edited = profile.clone();
+        if let Some(moniker) = moniker {
+            edited.moniker = moniker;
+        }
+        if let Some(rate) = commission_bps.filter(|rate| *rate != profile.commission_bps) {
+            if profile.commission_epoch == epoch {
+                return Err("Commission already changed this epoch".to_string());
+            }
+            if rate.abs_diff(profile.commission_bps) > profile.max_change_bps {
+                return Err(format!("Commission may change by at most {} basis points", profile.max_change_bps));
+            }
+            edited.commission_bps = rate;
+            edited.commission_epoch = epoch;
+        }
+        edited.validate()?;
+        *profile = edited;
+        Ok(())
+    }
+
+    /// Voluntarily retires a validator: it stops being a candidate, leaving the set at
+    /// the next election, and its whole self-bond starts unbonding. Delegations to it
+    /// stay until their owners undelegate. Returns the amount unbonding.
+    pub fn exit_validator(&mut self, operator: Address, height: u64, params: &ChainParams) -> Result<u128, String> {
+        if !self.is_registered(&operator) {
+            return Err("Validator is not registered".to_string());
+        }
+        self.profiles.get_mut(&operator).unwrap().exited = true;
+        let self_bond = self.self_bond(&operator);
+        if self_bond > 0 {
+            self.undelegate(operator, operator, self_bond, height, params)?;
+        }
+        Ok(self_bond)
+    }
 
+    /// Total stake delegated to `validator`, its self-bond included.
+    pub fn bonded_stake(&self, validator: &Address) -> u128 {
+        self.delegations.get(validator).map_or(0, |ds| ds.values().map(|d| d.amount).sum())
+    }
+
+    /// Amount `delegator` has delegated to `validator`.
+    pub fn delegated(&self, delegator: &Address, validator: &Address) -> u128 {
+        self.delegations.get(validator).and_then(|ds| ds.get(delegator)).map_or(0, |d| d.amount)
+    }
+
+    /// Amount `validator` has delegated to itself.
+    pub fn self_bond(&self, validator: &Address) -> u128 {
+        self.delegated(validator, validator)
+    }
+
+    fn settle_rewards(&mut self, validator: Address, delegator: Address) {
+        let stake = self.bonded_stake(&validator);
+        let unclaimable = self.distribution.settle(validator, delegator, stake);
+        self.total_supply = self.total_supply.saturating_sub(unclaimable);
+    }
+
+    fn restart_rewards(&mut self, validator: Address, delegator: Address) {
+        let stake = self.delegated(&delegator, &validator);
+        self.distribution.restart(validator, delegator, stake);
+    }
+
+    /// Mints `reward` for the block proposed by `proposer`, provided it is an active
+    /// member of the current set; its commission goes to the operator and the rest to
+    /// everyone staked with it. Returns the amount minted.
+    pub fn allocate_block_reward(&mut self, proposer: Address, reward: u128) -> u128 {
+        let commission = match self.validators.iter().find(|v| v.address == proposer && v.is_active) {
+            Some(v) => v.commission,
+            None => return 0,
+        };
+        self.distribution.allocate(proposer, reward, commission);
+        self.total_supply += reward;
+        reward
+    }
+
+    /// Withdraws what `delegator` earned staking with `validator`, and the validator's
+    /// commission if `delegator` is its operator. The caller credits the amount.
+    pub fn withdraw_rewards(&mut self, delegator: Address, validator: Address) -> Result<u128, String> {
+        let has_rewards = self.distribution.delegators.get(&validator).is_some_and(|s| s.contains_key(&delegator));
+        if !has_rewards && delegator != validator {
+            return Err("No delegation to this validator".to_string());
+        }
+        self.settle_rewards(validator, delegator);
+        let paid = self.distribution.take(validator, delegator);
+        self.restart_rewards(validator, delegator);
+        Ok(paid)
+    }
+
+    /// Rewards `delegator` could withdraw from `validator` now, commission included.
+    pub fn pending_rewards(&self, delegator: Address, validator: Address) -> u128 {
+        let mut scratch = self.distribution.clone();
+        scratch.settle(validator, delegator, self.bonded_stake(&validator));
+        scratch.take(validator, delegator)
+    }
+
+    /// Answers read-only calls to the staking account (`eth_call`). Every answer is a
+    /// 32-byte big-endian word:
+    /// - `0x10 ‖ delegator ‖ validator`: rewards the delegator can withdraw
+    /// - `0x11 ‖ validator`: commission its operator has not withdrawn
+    /// - `0x12`: total supply
+    pub fn call(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let address = |range: std::ops::Range<usize>| -> Result<Address, String> {
+            let bytes: [u8; 24] = data.get(range).and_then(|b| b.try_into().ok()).ok_or("Call data too short")?;
+            Address::from_bytes(bytes).map_err(|e| e.to_string())
+        };
+        let value = match data.first() {
+            Some(0x10) => self.pending_rewards(address(1..25)?, address(25..49)?),
+            Some(0x11) => {
+                let validator = address(1..25)?;
+                self.distribution.validators.get(&validator).map_or(0, |v| v.commission)
+            }
+            Some(0x12) => self.total_supply,
+            _ => return Err("Unknown staking query".to_string()),
+        };
+        let mut word = vec![0u8; 16];
+        word.extend_from_slice(&value.to_be_bytes());
+        Ok(word)
+    }
+
+    /// Every validator that may be elected, ordered by address: registered ones that have
+    /// not exited and keep a self-bond of at least the minimum validator stake, weighted
+    /// by all stake delegated to them. Jailed candidates are inactive.
+    pub fn candidates(&self, params: &ChainParams) -> Vec<ValidatorInfo> {
+        self.profiles.iter()
+            .filter(|(addr, profile)| !profile.exited && self.self_bond(addr) >= params.min_validator_stake)
+            .map(|(addr, profile)| ValidatorInfo {
+                address: *addr,
+                stake: self.bonded_stake(addr),
+                is_active: !self.is_jailed(addr),
+                commission: profile.commission_bps,
+                missed_blocks: self.liveness.get(addr).map_or(0, |l| l.missed),
+                consensus_pubkey: self.consensus_keys.get(addr).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Freezes the validator set for `epoch`: the `active_validator_count` unjailed
+    /// candidates with the most stake (ties broken by address) become active. Stake and
+    /// membership then stay fixed until the next election; only jailing takes effect
+    /// sooner. The epoch's leader schedule is drawn from the new set, seeded with the
+    /// VRF output of the electing block.
+    pub fn elect_validators(&mut self, epoch: u64, vrf_output: &[u8; 32], params: &ChainParams) {
+        let mut ranked = self.candidates(params);
+        ranked.sort_by(|a, b| b.stake.cmp(&a.stake).then(a.address.cmp(&b.address)));
+        let mut elected = 0;
+        for v in ranked.iter_mut() {
+            v.is_active = v.is_active && elected < params.active_validator_count;
+            elected += v.is_active as u64;
+        }
+        ranked.sort_by_key(|v| v.address);
+        self.schedule = LeaderSchedule::new(epoch, epoch_seed(epoch, vrf_output), &ranked);
+        self.validators = ranked;
+        self.epoch = epoch;
+    }
+
+    /// Validator set of the current epoch, ordered by address.
+    pub fn validator_set(&self) -> &[ValidatorInfo] {
+        &self.validators
+    }
+
+    pub fn leader_schedule(&self) -> &LeaderSchedule {
+        &self.schedule
+    }
+
+    /// Hash the state root commits to, through `STAKING_ROOT_SLOT`.
+    pub fn root(&self) -> [u8; 32] {
+        Keccak256::digest(bincode::serialize(self).expect("Staking store serializes")).into()
+    }
+
+    pub fn is_jailed(&self, validator: &Address) -> bool {
+        self.jailed.contains_key(validator)
+    }
+
+    /// Takes `validator` out of the active set until it sends an unjail transaction at or
+    /// after `until_slot`. A longer existing sentence is kept.
+    pub fn jail(&mut self, validator: Address, until_slot: u64) {
+        let until = self.jailed.entry(validator).or_insert(until_slot);
+        *until = (*until).max(until_slot);
+        if let Some(v) = self.validators.iter_mut().find(|v| v.address == validator) {
+            v.is_active = false;
+        }
+    }
+
+    /// Makes a jailed validator a candidate again once its sentence is over at `slot`,
+    /// provided its self-bond still qualifies; it rejoins the set at the next election.
+    /// Its liveness window starts afresh.
+    pub fn unjail(&mut self, validator: Address, slot: u64, params: &ChainParams) -> Result<(), String> {
+        let until = *self.jailed.get(&validator).ok_or("Validator is not jailed")?;
+        if slot < until {
+            return Err(format!("Validator is jailed until slot {}", until));
+        }
+        if self.self_bond(&validator) < params.min_validator_stake {
+            return Err("Self-bond is below the minimum validator stake".to_string());
+        }
+        self.jailed.remove(&validator);
+        self.liveness.remove(&validator);
         Ok(())
     }
 
+    /// Records which of `voters` signed the commit for `height`. A validator that missed
+    /// `max_missed_blocks_before_jail` of its last `liveness_window_blocks` commits is
+    /// slashed for downtime and jailed for `jail_duration_slots` from `slot`. Returns the
+    /// validators jailed.
+    pub fn record_commit(&mut self, height: u64, voters: &[Address], signers: &[Address], slot: u64, params: &ChainParams) -> Vec<Address> {
+        self.last_commit_height = height;
+        let mut jailed = Vec::new();
+        for voter in voters {
+            let liveness = self.liveness.entry(*voter).or_default();
+            let signed = signers.contains(voter);
+            liveness.window.push_back(signed);
+            liveness.missed += !signed as u64;
+            if liveness.window.len() as u64 > params.liveness_window_blocks && liveness.window.pop_front() == Some(false) {
+                liveness.missed -= 1;
+            }
+            if liveness.missed >= params.max_missed_blocks_before_jail {
+                jailed.push(*voter);
+            }
+            let missed = liveness.missed;
+            if let Some(v) = self.validators.iter_mut().find(|v| v.address == *voter) {
+                v.missed_blocks = missed;
+            }
+        }
+        for validator in &jailed {
+            let mut hasher = Keccak256::new();
+            hasher.update(b"downtime");
+            hasher.update(validator.to_bytes());
+            hasher.update(height.to_be_bytes());
+            self.slash(*validator, params.slash_downtime_bps, hasher.finalize().into(), height);
+            self.jail(*validator, slot + params.jail_duration_slots);
+            self.liveness.remove(validator);
+            if let Some(v) = self.validators.iter_mut().find(|v| v.address == *validator) {
+                v.missed_blocks = 0;
+            }
+        }
+        jailed
+    }
+
+    pub fn is_slashed(&self, offence: &[u8; 32]) -> bool {
+        self.slashed_offences.contains(offence)
+    }
+
+    /// Burns `fraction_bps` basis points of every delegation to `validator`, its
+    /// self-bond included, and of the stake that left it after `infraction_height`:
+    /// unbonding entries and redelegations, which are cut at their destination. Records
+    /// `offence` so it is only punished once. Returns the amount burned.
+    pub fn slash(&mut self, validator: Address, fraction_bps: u16, offence: [u8; 32], infraction_height: u64) -> u128 {
+        self.slashed_offences.insert(offence);
+        let cut = |amount: u128| amount * fraction_bps as u128 / 10000;
+        let mut burned = 0u128;
+
+        for u in self.unbonding.iter_mut().filter(|u| u.validator == validator && u.creation_height >= infraction_height) {
+            let amount = cut(u.amount);
+            u.amount -= amount;
+            burned += amount;
+        }
+        let redelegated: Vec<(Address, Address, u128)> = self.redelegations.iter()
+            .filter(|r| r.source == validator && r.creation_height >= infraction_height)
+            .map(|r| (r.delegator, r.destination, cut(r.amount)))
+            .collect();
+        for (delegator, destination, amount) in redelegated {
+            // Whatever was unbonded from the destination since is out of reach
+            let amount = amount.min(self.delegated(&delegator, &destination));
+            if amount > 0 {
+                self.remove_stake(delegator, destination, amount);
+                burned += amount;
+            }
+        }
+
+        let unclaimable = self.distribution.record_slash(validator, self.bonded_stake(&validator), fraction_bps);
+        for d in self.delegations.get_mut(&validator).into_iter().flat_map(|ds| ds.values_mut()) {
+            let amount = cut(d.amount);
+            d.amount -= amount;
+            burned += amount;
+        }
+        self.total_supply = self.total_supply.saturating_sub(burned + unclaimable);
+        burned
+    }
+
+    /// Removes the unbonding entries that have matured at `current_height`, returning
+    /// what each delegator is owed, and forgets the redelegations that have matured.
     pub fn process_matured_unbonding(&mut self, current_height: u64) -> Vec<(Address, u128)> {
         let mut released = Vec::new();
-        let mut i = 0;
-        while i < self.unbonding.len() {
-            if self.unbonding[i].release_block <= current_height {
-                let req = self.unbonding.remove(i);
+        self.unbonding.retain(|req| {
+            let matured = req.release_block <= current_height;
+            if matured && req.amount > 0 {
                 released.push((req.delegator, req.amount));
-            } else {
-                i += 1;
             }
-        }
+            !matured
+        });
+        self.redelegations.retain(|r| r.release_block > current_height);
         released
     }
 }
@@ -39,7 +39,13 @@ pub struct State {
     pub storage: HashMap<Address, HashMap<[u8; 32], [u8; 32]>>,
     pub codes: HashMap<[u8; 32], Vec<u8>>,
     pub trie: MerklePatriciaTrie,
+    /// Per-contract storage tries; their roots are the accounts' `storage_root`.
+    pub storage_tries: HashMap<Address, MerklePatriciaTrie>,
     pub staking: crate::staking::StakingStore,
+    pub governance: crate::core::governance::GovernanceStore,
+    /// Chain parameters in force; set at genesis and changed by governance.
+    #[serde(default)]
+    pub params: crate::parameters::ChainParams,
 }
 
 impl Default for State {
@@ -55,7 +61,10 @@ impl State {
             storage: HashMap::new(),
             codes: HashMap::new(),
             trie: MerklePatriciaTrie::new(),
+            storage_tries: HashMap::new(),
             staking: crate::staking::StakingStore::new(),
+            governance: crate::core::governance::GovernanceStore::new(),
+            params: crate::parameters::ChainParams::default(),
         }
     }
 
@@ -78,6 +87,34 @@ impl State {
         self.trie.insert(&addr.to_bytes(), serialized);
     }
 
+    /// Writes a contract storage slot and commits it to the account's storage root.
+    pub fn set_storage(&mut self, addr: Address, key: [u8; 32], value: [u8; 32]) {
+        self.storage.entry(addr).or_default().insert(key, value);
+        let trie = self.storage_tries.entry(addr).or_default();
+        trie.insert(&key, value.to_vec());
+        let storage_root = trie.root_hash;
+        let mut account = self.get_account(&addr);
+        account.storage_root = storage_root;
+        self.update_account(addr, account);
+    }
+
+    /// Writes the staking store's hash into `STAKING_ROOT_SLOT` of the staking account,
+    /// so the state root covers validators, delegations and jail sentences.
+    pub fn commit_staking(&mut self) {
+        let root = self.staking.root();
+        self.set_storage(crate::staking::staking_address(), crate::staking::STAKING_ROOT_SLOT, root);
+    }
+
+    /// Writes the governance store's hash into `GOVERNANCE_ROOT_SLOT` of the governance
+    /// account, and the chain parameters' hash into its `PARAMS_ROOT_SLOT`, so the state
+    /// root covers proposals, votes and parameters.
+    pub fn commit_governance(&mut self) {
+        let root = self.governance.root();
+        self.set_storage(crate::core::governance::governance_address(), crate::core::governance::GOVERNANCE_ROOT_SLOT, root);
+        let params_root = self.params.root();
+        self.set_storage(crate::core::governance::governance_address(), crate::core::governance::PARAMS_ROOT_SLOT, params_root);
+    }
+
     pub fn transfer(&mut self, from: &Address, to: &Address, amount: u128) -> Result<(), String> {
         let mut from_acc = self.get_account(from);
         let mut to_acc = self.get_account(to);
@@ -108,6 +145,9 @@ impl State {
         self.storage = snapshot.storage;
         self.codes = snapshot.codes;
         self.trie = snapshot.trie;
+        self.storage_tries = snapshot.storage_tries;
         self.staking = snapshot.staking;
+        self.governance = snapshot.governance;
+        self.params = snapshot.params;
     }
 }
@@ -91,6 +91,73 @@ impl MerklePatriciaTrie {
         }
     }
 
+    /// Nodes visited while looking up `key`, root first. Proves the key's value, or its
+    /// absence, against `root_hash`.
+    pub fn prove(&self, key: &[u8]) -> Vec<TrieNode> {
+        let nibbles = self.to_nibbles(key);
+        let mut proof = Vec::new();
+        let mut current = self.root_hash;
+        let mut depth = 0;
+        while let Some(node) = self.nodes.get(&current) {
+            proof.push(node.clone());
+            match node {
+                TrieNode::Extension { partial_path, child_hash } if nibbles[depth..].starts_with(partial_path) => {
+                    depth += partial_path.len();
+                    current = *child_hash;
+                }
+                TrieNode::Branch { children, .. } if depth < nibbles.len() => match children[nibbles[depth] as usize] {
+                    Some(child) => {
+                        depth += 1;
+                        current = child;
+                    }
+                    None => break,
+                },
+                _ => break,
+            }
+        }
+        proof
+    }
+
+    /// Up to `limit` entries with key `>= start`, in key order.
+    pub fn range(&self, start: &[u8], limit: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let start = self.to_nibbles(start);
+        let mut out = Vec::new();
+        self.collect_range(self.root_hash, &mut Vec::new(), &start, limit, &mut out);
+        out
+    }
+
+    fn collect_range(&self, hash: [u8; 32], prefix: &mut Vec<u8>, start: &[u8], limit: usize, out: &mut Vec<(Vec<u8>, Vec<u8>)>) {
+        // Skip subtrees whose keys all sort before `start`
+        let below_start = |p: &[u8]| p < &start[..p.len().min(start.len())];
+        if out.len() >= limit || below_start(prefix) {
+            return;
+        }
+        match self.nodes.get(&hash) {
+            Some(TrieNode::Leaf { partial_path, value }) => {
+                let full: Vec<u8> = prefix.iter().chain(partial_path).copied().collect();
+                if full.as_slice() >= start {
+                    out.push((Self::from_nibbles(&full), value.clone()));
+                }
+            }
+            Some(TrieNode::Extension { partial_path, child_hash }) => {
+                let len = prefix.len();
+                prefix.extend_from_slice(partial_path);
+                self.collect_range(*child_hash, prefix, start, limit, out);
+                prefix.truncate(len);
+            }
+            Some(TrieNode::Branch { children, .. }) => {
+                for (i, child) in children.iter().enumerate() {
+                    if let Some(child) = child {
+                        prefix.push(i as u8);
+                        self.collect_range(*child, prefix, start, limit, out);
+                        prefix.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn insert_at(&mut self, current_hash: [u8; 32], nibbles: &[u8], value: Vec<u8>) -> [u8; 32] {
         let node = if current_hash == [0u8; 32] {
             TrieNode::Leaf { partial_path: nibbles.to_vec(), value }
@@ -209,6 +276,10 @@ impl MerklePatriciaTrie {
         nibbles
     }
 
+    fn from_nibbles(nibbles: &[u8]) -> Vec<u8> {
+        nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair.get(1).copied().unwrap_or(0)).collect()
+    }
+
     fn common_prefix(&self, a: &[u8], b: &[u8]) -> usize {
         let mut i = 0;
         while i < a.len() && i < b.len() && a[i] == b[i] {
@@ -217,3 +288,142 @@ impl MerklePatriciaTrie {
         i
     }
 }
+
+/// Checks that `entries` are exactly the keys in `[start, end]` of the trie with root
+/// `root`, given the proofs for `start` and `end` (as produced by `prove`).
+///
+/// Every reference strictly inside the range is cut out of the proven edge paths and the
+/// entries are inserted back; only the complete, unaltered set of entries restores `root`.
+pub fn verify_range_proof(root: [u8; 32], start: &[u8], end: &[u8], entries: &[(Vec<u8>, Vec<u8>)], proof: &[TrieNode]) -> Result<(), String> {
+    if start.len() != end.len() || start > end {
+        return Err("Invalid proof range".to_string());
+    }
+    for (i, (key, _)) in entries.iter().enumerate() {
+        if key.len() != start.len() || key.as_slice() < start || key.as_slice() > end {
+            return Err("Range entry outside the requested range".to_string());
+        }
+        if i > 0 && entries[i - 1].0 >= *key {
+            return Err("Range entries are not strictly ordered".to_string());
+        }
+    }
+    if root == [0u8; 32] {
+        return if entries.is_empty() { Ok(()) } else { Err("Entries for an empty trie".to_string()) };
+    }
+
+    let mut trie = MerklePatriciaTrie::new();
+    for node in proof {
+        trie.nodes.insert(node.hash(), node.clone());
+    }
+    let left = trie.to_nibbles(start);
+    let right = trie.to_nibbles(end);
+    trie.root_hash = trie.unset_inner(root, 0, &left, &right, true, true)?.unwrap_or([0u8; 32]);
+    for (key, value) in entries {
+        trie.insert(key, value.clone());
+    }
+    if trie.root_hash != root {
+        return Err("Range proof does not match the state root".to_string());
+    }
+    Ok(())
+}
+
+impl MerklePatriciaTrie {
+    /// Removes everything strictly between the `left` and `right` edge paths below `hash`
+    /// (at nibble `depth`). Returns the rewritten node's hash, or None if the whole node
+    /// lies inside the range.
+    fn unset_inner(&mut self, hash: [u8; 32], depth: usize, left: &[u8], right: &[u8], on_left: bool, on_right: bool) -> Result<Option<[u8; 32]>, String> {
+        if (!on_left && !on_right) || hash == [0u8; 32] {
+            return Ok(None);
+        }
+        let edge = if on_left { left } else { right };
+        let node = self.nodes.get(&hash).cloned().ok_or("Range proof is missing an edge node")?;
+        match node {
+            TrieNode::Leaf { partial_path, .. } => {
+                let key: Vec<u8> = edge[..depth].iter().chain(&partial_path).copied().collect();
+                let inside = (!on_left || key.as_slice() >= left) && (!on_right || key.as_slice() <= right);
+                Ok(if inside { None } else { Some(hash) })
+            }
+            TrieNode::Extension { partial_path, child_hash } => {
+                let end = depth + partial_path.len();
+                if end > edge.len() {
+                    return Err("Malformed extension in range proof".to_string());
+                }
+                if (on_left && partial_path.as_slice() < &left[depth..end]) || (on_right && partial_path.as_slice() > &right[depth..end]) {
+                    return Ok(Some(hash));
+                }
+                let next_left = on_left && partial_path.as_slice() == &left[depth..end];
+                let next_right = on_right && partial_path.as_slice() == &right[depth..end];
+                Ok(self.unset_inner(child_hash, end, left, right, next_left, next_right)?.map(|child| {
+                    self.store(TrieNode::Extension { partial_path, child_hash: child })
+                }))
+            }
+            TrieNode::Branch { mut children, value } => {
+                if depth >= edge.len() {
+                    return Err("Malformed branch in range proof".to_string());
+                }
+                for (i, child) in children.iter_mut().enumerate() {
+                    let Some(child_hash) = *child else { continue };
+                    if (on_left && i < left[depth] as usize) || (on_right && i > right[depth] as usize) {
+                        continue;
+                    }
+                    let next_left = on_left && i == left[depth] as usize;
+                    let next_right = on_right && i == right[depth] as usize;
+                    *child = self.unset_inner(child_hash, depth + 1, left, right, next_left, next_right)?;
+                }
+                Ok(Some(self.store(TrieNode::Branch { children, value })))
+            }
+            TrieNode::Empty => Ok(None),
+        }
+    }
+
+    fn store(&mut self, node: TrieNode) -> [u8; 32] {
+        let h = node.hash();
+        self.nodes.insert(h, node);
+        h
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MerklePatriciaTrie {
+        let mut trie = MerklePatriciaTrie::new();
+        for i in 0u8..40 {
+            trie.insert(&[i.wrapping_mul(37), i, 0xaa], vec![i]);
+        }
+        trie
+    }
+
+    #[test]
+    fn test_range_proofs_accept_complete_ranges_only() {
+        let trie = sample();
+        let max = [0xffu8; 3];
+        let mut start = vec![0u8; 3];
+        let mut seen = 0;
+        loop {
+            let entries = trie.range(&start, 7);
+            let complete = entries.len() < 7;
+            let end = if complete { max.to_vec() } else { entries.last().unwrap().0.clone() };
+            let mut proof = trie.prove(&start);
+            proof.extend(trie.prove(&end));
+            verify_range_proof(trie.root_hash, &start, &end, &entries, &proof).unwrap();
+
+            if entries.len() > 2 {
+                let mut missing = entries.clone();
+                missing.remove(1);
+                assert!(verify_range_proof(trie.root_hash, &start, &end, &missing, &proof).is_err());
+                let mut altered = entries.clone();
+                altered[1].1 = vec![0xee];
+                assert!(verify_range_proof(trie.root_hash, &start, &end, &altered, &proof).is_err());
+            }
+
+            seen += entries.len();
+            if complete {
+                break;
+            }
+            start = end;
+            *start.last_mut().unwrap() += 1;
+        }
+        assert_eq!(seen, 40);
+    }
+}
@@ -0,0 +1,87 @@
+// File: src/storage/codec.rs
+//
+// Binary value encoding for the sled trees. Every value starts with a one-byte
+// schema version followed by the bincode payload, so the on-disk format can be
+// evolved without guessing what a blob contains.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Current on-disk schema version written in front of every value.
+/// 2: logs bloom added to block headers and receipts.
+/// 3: per-contract storage tries added to the state.
+/// 4: validator consensus keys added to the staking store.
+/// 5: consensus keys are BLS; finality commits carry an aggregate signature.
+/// 6: misbehaviour evidence in blocks and slashed offences in the staking store.
+/// 7: last commits in blocks; jail sentences and vote liveness in the staking store.
+/// 8: elected validator set in the staking store, which the state root now commits to.
+/// 9: VRF proofs and PoH segments in blocks; leader schedule in the staking store.
+/// 10: reward distribution and total supply in the staking store.
+/// 11: validator profiles in the staking store.
+/// 12: delegations aggregated per delegator; redelegations and slashable unbonding.
+/// 13: governance proposals and parameters in the state.
+/// 14: chain parameters in the state; scheduled parameter changes in governance.
+/// 15: upgrade plans, past upgrades and the fork schedule in governance.
+/// 16: chain id in the chain parameters.
+pub const SCHEMA_VERSION: u8 = 16;
+
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let payload = bincode::serialize(value).map_err(|e| e.to_string())?;
+    let mut out = Vec::with_capacity(payload.len() + 1);
+    out.push(SCHEMA_VERSION);
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    match bytes.first() {
+        Some(&SCHEMA_VERSION) => bincode::deserialize(&bytes[1..]).map_err(|e| e.to_string()),
+        Some(v) => Err(format!("Unsupported storage schema version {} (expected {})", v, SCHEMA_VERSION)),
+        None => Err("Empty storage value".to_string()),
+    }
+}
+
+/// Heights are stored big-endian so sled's lexicographic order matches numeric order.
+pub fn height_key(height: u64) -> [u8; 8] {
+    height.to_be_bytes()
+}
+
+pub fn height_from_key(key: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(key.get(..8)?.try_into().ok()?))
+}
+
+/// Parses a (optionally 0x-prefixed) hex hash into raw key bytes.
+pub fn hash_key(hash_hex: &str) -> Option<[u8; 32]> {
+    let h = hash_hex.strip_prefix("0x").unwrap_or(hash_hex);
+    let bytes = hex::decode(h).ok()?;
+    bytes.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_with_version_header() {
+        let value = (42u64, vec![1u8, 2, 3], "kortana".to_string());
+        let bytes = encode(&value).unwrap();
+        assert_eq!(bytes[0], SCHEMA_VERSION);
+        let decoded: (u64, Vec<u8>, String) = decode(&bytes).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let mut bytes = encode(&7u64).unwrap();
+        bytes[0] = SCHEMA_VERSION + 1;
+        assert!(decode::<u64>(&bytes).is_err());
+        assert!(decode::<u64>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_height_keys_sort_numerically() {
+        assert!(height_key(9) < height_key(10));
+        assert!(height_key(255) < height_key(256));
+        assert_eq!(height_from_key(&height_key(1234)), Some(1234));
+    }
+}
@@ -0,0 +1,179 @@
+// File: src/storage/index.rs
+//
+// Append-only transaction indexes. Instead of one growing JSON array per address (and one
+// for the whole chain), every transaction gets its own composite key, so writes are O(1)
+// and reads are range scans that can start from any cursor.
+//
+//   address_index: address (24) | height (8, BE) | tx index (4, BE)  -> tx hash
+//   tx_sequence:   sequence (8, BE)                                 -> tx hash
+
+use super::codec::{height_key, height_from_key};
+use super::{Storage, META_NEXT_TX_SEQ, LEGACY_GLOBAL_TXS, TREE_LEGACY_ADDRESS_TXS};
+use crate::address::Address;
+use super::logs::log_index_keys;
+use crate::types::block::Block;
+use crate::types::bloom::Bloom;
+use crate::types::transaction::TransactionReceipt;
+
+/// Position of a transaction on chain: (block height, index within the block).
+pub type ChainPosition = (u64, u32);
+
+pub const ADDRESS_INDEX_KEY_LEN: usize = 36;
+
+pub fn address_index_key(address: &Address, height: u64, tx_index: u32) -> [u8; ADDRESS_INDEX_KEY_LEN] {
+    let mut key = [0u8; ADDRESS_INDEX_KEY_LEN];
+    key[..24].copy_from_slice(&address.to_bytes());
+    key[24..32].copy_from_slice(&height.to_be_bytes());
+    key[32..].copy_from_slice(&tx_index.to_be_bytes());
+    key
+}
+
+fn position_from_key(key: &[u8]) -> Option<ChainPosition> {
+    let height = u64::from_be_bytes(key.get(24..32)?.try_into().ok()?);
+    let index = u32::from_be_bytes(key.get(32..36)?.try_into().ok()?);
+    Some((height, index))
+}
+
+fn hash_from_value(value: &[u8]) -> Result<[u8; 32], String> {
+    value.try_into().map_err(|_| "Corrupt transaction index entry".to_string())
+}
+
+/// Opaque cursor string handed out by the RPC layer for address history pages.
+pub fn encode_position_cursor(pos: ChainPosition) -> String {
+    format!("0x{:016x}{:08x}", pos.0, pos.1)
+}
+
+pub fn decode_position_cursor(cursor: &str) -> Option<ChainPosition> {
+    let c = cursor.strip_prefix("0x").unwrap_or(cursor);
+    if c.len() != 24 { return None; }
+    let height = u64::from_str_radix(&c[..16], 16).ok()?;
+    let index = u32::from_str_radix(&c[16..], 16).ok()?;
+    Some((height, index))
+}
+
+#[derive(Debug, Default)]
+pub struct ReindexReport {
+    pub blocks: u64,
+    pub transactions: u64,
+}
+
+impl Storage {
+    /// Transactions touching `address`, newest first, strictly older than `before`.
+    /// Returns the page plus the cursor to pass back for the next page, if there is one.
+    pub fn get_address_transactions(&self, address: &Address, before: Option<ChainPosition>, limit: usize) -> Result<(Vec<[u8; 32]>, Option<ChainPosition>), String> {
+        let iter = match before {
+            Some((h, i)) => self.address_index.range(address_index_key(address, 0, 0)..address_index_key(address, h, i)),
+            None => self.address_index.scan_prefix(address.to_bytes()),
+        };
+
+        let mut page = Vec::with_capacity(limit + 1);
+        for item in iter.rev().take(limit + 1) {
+            let (k, v) = item.map_err(|e| e.to_string())?;
+            let pos = position_from_key(&k).ok_or("Corrupt address index key")?;
+            page.push((pos, hash_from_value(&v)?));
+        }
+        Ok(Self::split_page(page, limit))
+    }
+
+    /// Chain-wide transactions, newest first, with sequence numbers below `before_seq`.
+    pub fn get_recent_transactions(&self, before_seq: Option<u64>, limit: usize) -> Result<(Vec<[u8; 32]>, Option<u64>), String> {
+        let iter = match before_seq {
+            Some(seq) => self.tx_sequence.range(..height_key(seq)),
+            None => self.tx_sequence.iter(),
+        };
+
+        let mut page = Vec::with_capacity(limit + 1);
+        for item in iter.rev().take(limit + 1) {
+            let (k, v) = item.map_err(|e| e.to_string())?;
+            let seq = height_from_key(&k).ok_or("Corrupt transaction sequence key")?;
+            page.push((seq, hash_from_value(&v)?));
+        }
+        Ok(Self::split_page(page, limit))
+    }
+
+    /// Total number of indexed transactions (the next sequence number to be assigned).
+    pub fn transaction_count(&self) -> Result<u64, String> {
+        Ok(self.meta.get(META_NEXT_TX_SEQ).map_err(|e| e.to_string())?
+            .and_then(|v| height_from_key(&v))
+            .unwrap_or(0))
+    }
+
+    fn split_page<C: Copy>(mut page: Vec<(C, [u8; 32])>, limit: usize) -> (Vec<[u8; 32]>, Option<C>) {
+        let next = if page.len() > limit {
+            page.truncate(limit);
+            page.last().map(|(c, _)| *c)
+        } else {
+            None
+        };
+        (page.into_iter().map(|(_, h)| h).collect(), next)
+    }
+
+    /// True if the database still carries the array-based indexes from before the
+    /// composite-key layout and `reindex` has to run before the node starts.
+    pub fn needs_reindex(&self) -> bool {
+        self.db.tree_names().iter().any(|n| n == TREE_LEGACY_ADDRESS_TXS.as_bytes())
+            || self.meta.contains_key(LEGACY_GLOBAL_TXS).unwrap_or(false)
+    }
+
+    /// Rebuilds the transaction and log indexes from the stored blocks and receipts and
+    /// drops the legacy array-based indexes.
+    pub fn reindex(&self) -> Result<ReindexReport, String> {
+        let mut report = ReindexReport::default();
+        self.address_index.clear().map_err(|e| e.to_string())?;
+        self.tx_sequence.clear().map_err(|e| e.to_string())?;
+        self.block_blooms.clear().map_err(|e| e.to_string())?;
+        self.log_index.clear().map_err(|e| e.to_string())?;
+
+        let mut seq = 0u64;
+        for item in self.blocks.iter() {
+            let (_, v) = item.map_err(|e| e.to_string())?;
+            let block: Block = super::codec::decode(&v)?;
+            let height = block.header.height;
+            let mut receipts = Vec::with_capacity(block.transactions.len());
+            for (idx, tx) in block.transactions.iter().enumerate() {
+                let tx_hash = tx.hash();
+                if let Some(receipt) = Self::get_decoded::<TransactionReceipt>(&self.receipts, &tx_hash)? {
+                    receipts.push(receipt);
+                }
+                self.address_index.insert(&address_index_key(&tx.from, height, idx as u32)[..], &tx_hash).map_err(|e| e.to_string())?;
+                if tx.to != tx.from {
+                    self.address_index.insert(&address_index_key(&tx.to, height, idx as u32)[..], &tx_hash).map_err(|e| e.to_string())?;
+                }
+                self.tx_sequence.insert(height_key(seq), &tx_hash).map_err(|e| e.to_string())?;
+                seq += 1;
+                report.transactions += 1;
+            }
+            self.block_blooms.insert(height_key(height), &Bloom::from_receipts(&receipts).0[..]).map_err(|e| e.to_string())?;
+            for key in log_index_keys(height, &receipts) {
+                self.log_index.insert(key, Vec::new()).map_err(|e| e.to_string())?;
+            }
+            report.blocks += 1;
+        }
+        self.meta.insert(META_NEXT_TX_SEQ, &height_key(seq)).map_err(|e| e.to_string())?;
+
+        self.db.drop_tree(TREE_LEGACY_ADDRESS_TXS).map_err(|e| e.to_string())?;
+        self.meta.remove(LEGACY_GLOBAL_TXS).map_err(|e| e.to_string())?;
+        self.db.flush().map_err(|e| e.to_string())?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_keys_order_by_position() {
+        let addr = Address::from_pubkey(b"alice");
+        assert!(address_index_key(&addr, 1, 5) < address_index_key(&addr, 2, 0));
+        assert!(address_index_key(&addr, 2, 0) < address_index_key(&addr, 2, 1));
+        assert_eq!(position_from_key(&address_index_key(&addr, 9, 3)), Some((9, 3)));
+    }
+
+    #[test]
+    fn test_position_cursor_roundtrip() {
+        let cursor = encode_position_cursor((123_456, 7));
+        assert_eq!(decode_position_cursor(&cursor), Some((123_456, 7)));
+        assert_eq!(decode_position_cursor("0x12"), None);
+    }
+}
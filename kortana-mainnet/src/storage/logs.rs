@@ -0,0 +1,78 @@
+// File: src/storage/logs.rs
+//
+// Log lookup structures written alongside each block:
+//
+//   block_blooms: height (8, BE)                                  -> 256-byte logs bloom
+//   log_index:    evm address (20) | topic0 (32) | height (8, BE) -> ()
+//
+// Logs without topics are indexed under an all-zero topic0. eth_getLogs resolves
+// address-constrained queries through `log_index` and everything else by testing
+// `block_blooms`, so receipts are only loaded for blocks that can actually match.
+
+use std::collections::BTreeSet;
+use super::codec::{height_key, height_from_key};
+use super::Storage;
+use crate::types::bloom::{Bloom, BLOOM_BYTES};
+use crate::types::transaction::TransactionReceipt;
+
+pub const LOG_INDEX_KEY_LEN: usize = 60;
+
+pub fn log_index_key(address: &[u8; 20], topic0: &[u8; 32], height: u64) -> [u8; LOG_INDEX_KEY_LEN] {
+    let mut key = [0u8; LOG_INDEX_KEY_LEN];
+    key[..20].copy_from_slice(address);
+    key[20..52].copy_from_slice(topic0);
+    key[52..].copy_from_slice(&height.to_be_bytes());
+    key
+}
+
+/// Distinct log index keys produced by a block's receipts.
+pub fn log_index_keys(height: u64, receipts: &[TransactionReceipt]) -> BTreeSet<Vec<u8>> {
+    receipts.iter()
+        .flat_map(|r| r.logs.iter())
+        .map(|log| {
+            let topic0 = log.topics.first().copied().unwrap_or([0u8; 32]);
+            log_index_key(&log.address.as_evm_address(), &topic0, height).to_vec()
+        })
+        .collect()
+}
+
+impl Storage {
+    pub fn get_block_bloom(&self, height: u64) -> Result<Option<Bloom>, String> {
+        match self.block_blooms.get(height_key(height)).map_err(|e| e.to_string())? {
+            Some(v) => {
+                let arr: [u8; BLOOM_BYTES] = v.as_ref().try_into().map_err(|_| "Corrupt block bloom entry".to_string())?;
+                Ok(Some(Bloom(arr)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Heights in `from..=to` holding a log emitted by one of `addresses`, optionally
+    /// restricted to the given topic0 values.
+    pub fn find_log_heights(&self, addresses: &[[u8; 20]], topic0: Option<&[[u8; 32]]>, from: u64, to: u64) -> Result<BTreeSet<u64>, String> {
+        let mut heights = BTreeSet::new();
+        for addr in addresses {
+            match topic0 {
+                Some(topics) => {
+                    for topic in topics {
+                        let range = log_index_key(addr, topic, from)..=log_index_key(addr, topic, to);
+                        for item in self.log_index.range(range) {
+                            let (k, _) = item.map_err(|e| e.to_string())?;
+                            heights.insert(k.get(52..).and_then(height_from_key).ok_or("Corrupt log index key")?);
+                        }
+                    }
+                }
+                None => {
+                    for item in self.log_index.scan_prefix(addr) {
+                        let (k, _) = item.map_err(|e| e.to_string())?;
+                        let h = k.get(52..).and_then(height_from_key).ok_or("Corrupt log index key")?;
+                        if (from..=to).contains(&h) {
+                            heights.insert(h);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(heights)
+    }
+}
@@ -0,0 +1,132 @@
+// File: src/storage/migration.rs
+//
+// One-shot conversion of the original layout (serde_json values under string-prefixed
+// keys in the default tree) into the per-type binary trees used by `Storage`.
+
+use super::codec::{encode, height_key, SCHEMA_VERSION};
+use super::{Storage, TxLocation, LEGACY_GLOBAL_TXS, META_LATEST_STATE_HEIGHT, META_SCHEMA_VERSION};
+use crate::state::account::State;
+use crate::types::block::Block;
+use crate::types::bloom::Bloom;
+use crate::types::transaction::{Transaction, TransactionReceipt};
+
+const LEGACY_PREFIXES: [&str; 8] = [
+    "block:", "blockhash:", "tx:", "txloc:", "receipt:", "state:", "stateroot:", "addr_txs:",
+];
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub blocks: u64,
+    pub transactions: u64,
+    pub locations: u64,
+    pub receipts: u64,
+    pub states: u64,
+    pub state_roots: u64,
+    /// Transactions re-indexed by address and sequence after the conversion.
+    pub reindexed_transactions: u64,
+    pub skipped: Vec<String>,
+}
+
+impl Storage {
+    /// True if the default tree still holds entries written by the JSON layout.
+    pub fn has_legacy_data(&self) -> bool {
+        LEGACY_PREFIXES.iter().any(|p| self.db.scan_prefix(p).next().is_some())
+            || self.db.contains_key(LEGACY_GLOBAL_TXS).unwrap_or(false)
+            || self.db.contains_key(META_LATEST_STATE_HEIGHT).unwrap_or(false)
+    }
+
+    /// Converts a legacy JSON database in place. Each legacy key is removed only after
+    /// its binary replacement has been written, so an interrupted run can be resumed.
+    pub fn migrate_legacy_json(&self) -> Result<MigrationReport, String> {
+        let mut report = MigrationReport::default();
+
+        for item in self.db.iter() {
+            let (k, v) = item.map_err(|e| e.to_string())?;
+            let key = String::from_utf8_lossy(&k).to_string();
+
+            let migrated = if let Some(h) = key.strip_prefix("block:") {
+                Self::parse_height(h).and_then(|height| {
+                    let block: Block = serde_json::from_slice(&v).map_err(|e| e.to_string())?;
+                    self.blocks.insert(height_key(height), encode(&block)?).map_err(|e| e.to_string())?;
+                    self.block_hashes.insert(block.header.hash(), &height_key(height)).map_err(|e| e.to_string())?;
+                    report.blocks += 1;
+                    Ok(())
+                })
+            } else if key.starts_with("blockhash:") {
+                // Duplicate block body; rebuilt from the `block:` entries as an index.
+                Ok(())
+            } else if let Some(h) = key.strip_prefix("txloc:") {
+                Self::parse_hash(h).and_then(|hash| {
+                    let loc: serde_json::Value = serde_json::from_slice(&v).map_err(|e| e.to_string())?;
+                    let block_hash = Self::parse_hash(loc["block_hash"].as_str().unwrap_or(""))?;
+                    let location = TxLocation {
+                        block_height: loc["block_height"].as_u64().unwrap_or(0),
+                        block_hash,
+                        tx_index: loc["tx_index"].as_u64().unwrap_or(0) as u32,
+                    };
+                    self.tx_locations.insert(hash, encode(&location)?).map_err(|e| e.to_string())?;
+                    report.locations += 1;
+                    Ok(())
+                })
+            } else if let Some(h) = key.strip_prefix("tx:") {
+                Self::parse_hash(h).and_then(|hash| {
+                    let tx: Transaction = serde_json::from_slice(&v).map_err(|e| e.to_string())?;
+                    self.transactions.insert(hash, encode(&tx)?).map_err(|e| e.to_string())?;
+                    report.transactions += 1;
+                    Ok(())
+                })
+            } else if let Some(h) = key.strip_prefix("receipt:") {
+                Self::parse_hash(h).and_then(|hash| {
+                    let mut receipt: TransactionReceipt = serde_json::from_slice(&v).map_err(|e| e.to_string())?;
+                    receipt.logs_bloom = Bloom::from_logs(&receipt.logs);
+                    self.receipts.insert(hash, encode(&receipt)?).map_err(|e| e.to_string())?;
+                    report.receipts += 1;
+                    Ok(())
+                })
+            } else if let Some(h) = key.strip_prefix("stateroot:") {
+                Self::parse_height(h).and_then(|height| {
+                    let root: [u8; 32] = v.as_ref().try_into().map_err(|_| "state root is not 32 bytes".to_string())?;
+                    self.put_state_root(height, root)?;
+                    report.state_roots += 1;
+                    Ok(())
+                })
+            } else if let Some(h) = key.strip_prefix("state:") {
+                Self::parse_height(h).and_then(|height| {
+                    let state: State = serde_json::from_slice(&v).map_err(|e| e.to_string())?;
+                    self.states.insert(height_key(height), encode(&state)?).map_err(|e| e.to_string())?;
+                    report.states += 1;
+                    Ok(())
+                })
+            } else if key.starts_with("addr_txs:") || key == LEGACY_GLOBAL_TXS {
+                // Array-based indexes; rebuilt from the blocks by `reindex` below.
+                Ok(())
+            } else if key == META_LATEST_STATE_HEIGHT {
+                self.meta.insert(META_LATEST_STATE_HEIGHT, v.clone()).map(|_| ()).map_err(|e| e.to_string())
+            } else {
+                continue;
+            };
+
+            match migrated {
+                Ok(()) => {
+                    self.db.remove(&k).map_err(|e| e.to_string())?;
+                }
+                Err(e) => report.skipped.push(format!("{}: {}", key, e)),
+            }
+        }
+
+        if report.skipped.is_empty() {
+            report.reindexed_transactions = self.reindex()?.transactions;
+            self.meta.insert(META_SCHEMA_VERSION, &[SCHEMA_VERSION]).map_err(|e| e.to_string())?;
+        }
+        self.db.flush().map_err(|e| e.to_string())?;
+        Ok(report)
+    }
+
+    fn parse_height(s: &str) -> Result<u64, String> {
+        s.parse::<u64>().map_err(|e| format!("bad height '{}': {}", s, e))
+    }
+
+    fn parse_hash(s: &str) -> Result<[u8; 32], String> {
+        super::codec::hash_key(s).ok_or_else(|| format!("bad hash '{}'", s))
+    }
+}
@@ -1,144 +1,439 @@
+pub mod codec;
+pub mod index;
+pub mod logs;
+pub mod migration;
+pub mod snapshot;
+
+use crate::consensus::bft::FinalityCommit;
 use crate::types::block::Block;
+use crate::types::transaction::{Transaction, TransactionReceipt};
+use crate::state::account::State;
+use serde::{Serialize, Deserialize};
+use codec::{encode, decode, height_key, height_from_key, hash_key, SCHEMA_VERSION};
+use index::address_index_key;
+use logs::log_index_keys;
+use crate::types::bloom::Bloom;
+
+use sled::{Db, Tree, Transactional};
+use sled::transaction::TransactionError;
+
+const TREE_BLOCKS: &str = "blocks";
+const TREE_BLOCK_HASHES: &str = "block_hashes";
+const TREE_HEADERS: &str = "headers";
+const TREE_TRANSACTIONS: &str = "transactions";
+const TREE_TX_LOCATIONS: &str = "tx_locations";
+const TREE_RECEIPTS: &str = "receipts";
+const TREE_STATES: &str = "states";
+const TREE_STATE_ROOTS: &str = "state_roots";
+const TREE_ADDRESS_INDEX: &str = "address_index";
+const TREE_TX_SEQUENCE: &str = "tx_sequence";
+const TREE_BLOCK_BLOOMS: &str = "block_blooms";
+const TREE_LOG_INDEX: &str = "log_index";
+const TREE_QUARANTINE: &str = "quarantine";
+const TREE_FINALITY_COMMITS: &str = "finality_commits";
+const TREE_META: &str = "meta";
+
+const META_SCHEMA_VERSION: &str = "schema_version";
+const META_LATEST_STATE_HEIGHT: &str = "latest_state_height";
+const META_NEXT_TX_SEQ: &str = "next_tx_seq";
 
-use sled::Db;
+// Array-based indexes from schema 1 before the composite-key layout; dropped by `reindex`.
+const TREE_LEGACY_ADDRESS_TXS: &str = "address_txs";
+const LEGACY_GLOBAL_TXS: &str = "global_txs";
+
+/// Where a transaction landed on chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxLocation {
+    pub block_height: u64,
+    pub block_hash: [u8; 32],
+    pub tx_index: u32,
+}
+
+/// Outcome of the startup consistency check.
+#[derive(Debug, Default)]
+pub struct IntegrityReport {
+    /// Highest height with a block, matching state and state root, if any.
+    pub head: Option<u64>,
+    /// Heights whose partially written data was removed.
+    pub rolled_back: Vec<u64>,
+    /// Heights whose missing state root entry was rebuilt from the stored state.
+    pub repaired_roots: Vec<u64>,
+}
+
+/// A block that failed import, with why it was rejected and who relayed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedBlock {
+    pub block: Block,
+    pub reason: String,
+    pub peer: Option<String>,
+    pub received_at: u64,
+}
 
 pub struct Storage {
     db: Db,
+    /// height -> Block
+    blocks: Tree,
+    /// block hash -> height (indirection into `blocks`)
+    block_hashes: Tree,
+    /// height -> BlockHeader (also covers heights whose bodies came from a snapshot)
+    headers: Tree,
+    /// tx hash -> Transaction
+    transactions: Tree,
+    /// tx hash -> TxLocation
+    tx_locations: Tree,
+    /// tx hash -> TransactionReceipt
+    receipts: Tree,
+    /// height -> State
+    states: Tree,
+    /// height -> state root
+    state_roots: Tree,
+    /// address | height | tx index -> tx hash
+    address_index: Tree,
+    /// global sequence number -> tx hash
+    tx_sequence: Tree,
+    /// height -> logs bloom of the block
+    block_blooms: Tree,
+    /// evm address | topic0 | height -> ()
+    log_index: Tree,
+    /// block hash -> QuarantinedBlock (rejected peer blocks, kept for inspection)
+    quarantine: Tree,
+    /// height -> FinalityCommit for the canonical block at that height
+    finality_commits: Tree,
+    meta: Tree,
 }
 
 impl Storage {
     pub fn new(path: &str) -> Self {
         let db = sled::open(path).expect("Failed to open sled database");
-        Self { db }
+        Self::from_db(db).expect("Failed to open sled trees")
+    }
+
+    fn from_db(db: Db) -> Result<Self, String> {
+        let open = |name: &str| db.open_tree(name).map_err(|e| e.to_string());
+        let storage = Self {
+            blocks: open(TREE_BLOCKS)?,
+            block_hashes: open(TREE_BLOCK_HASHES)?,
+            headers: open(TREE_HEADERS)?,
+            transactions: open(TREE_TRANSACTIONS)?,
+            tx_locations: open(TREE_TX_LOCATIONS)?,
+            receipts: open(TREE_RECEIPTS)?,
+            states: open(TREE_STATES)?,
+            state_roots: open(TREE_STATE_ROOTS)?,
+            address_index: open(TREE_ADDRESS_INDEX)?,
+            tx_sequence: open(TREE_TX_SEQUENCE)?,
+            block_blooms: open(TREE_BLOCK_BLOOMS)?,
+            log_index: open(TREE_LOG_INDEX)?,
+            quarantine: open(TREE_QUARANTINE)?,
+            finality_commits: open(TREE_FINALITY_COMMITS)?,
+            meta: open(TREE_META)?,
+            db,
+        };
+
+        // A fresh database is stamped with the current schema immediately; a legacy
+        // JSON database is left unstamped until `migrate_legacy_json` has run.
+        if storage.schema_version()?.is_none() && !storage.has_legacy_data() {
+            storage.meta.insert(META_SCHEMA_VERSION, &[SCHEMA_VERSION]).map_err(|e| e.to_string())?;
+        }
+        Ok(storage)
+    }
+
+    /// Schema version recorded in the meta tree, `None` for unmigrated legacy databases.
+    pub fn schema_version(&self) -> Result<Option<u8>, String> {
+        let val = self.meta.get(META_SCHEMA_VERSION).map_err(|e| e.to_string())?;
+        Ok(val.and_then(|v| v.first().copied()))
+    }
+
+    fn get_decoded<T: serde::de::DeserializeOwned>(tree: &Tree, key: &[u8]) -> Result<Option<T>, String> {
+        match tree.get(key).map_err(|e| e.to_string())? {
+            Some(data) => Ok(Some(decode(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Persists a block together with everything derived from executing it (transactions,
+    /// receipts, locations, address indexes, post-state, state root and the head pointer)
+    /// in a single sled transaction. Either all of it lands on disk or none of it does.
+    pub fn commit_block(&self, block: &Block, receipts: &[TransactionReceipt], post_state: &State) -> Result<(), String> {
+        if receipts.len() != block.transactions.len() {
+            return Err(format!(
+                "Block {} has {} transactions but {} receipts",
+                block.header.height, block.transactions.len(), receipts.len()
+            ));
+        }
+
+        let h_key = height_key(block.header.height);
+        let block_hash = block.header.hash();
+        let state_root = post_state.calculate_root();
+        if state_root != block.header.state_root {
+            return Err(format!(
+                "Block {} state root 0x{} does not match post-state root 0x{}",
+                block.header.height, hex::encode(block.header.state_root), hex::encode(state_root)
+            ));
+        }
+
+        let bloom = Bloom::from_receipts(receipts);
+        let log_keys = log_index_keys(block.header.height, receipts);
+        let encoded_block = encode(block)?;
+        let encoded_header = encode(&block.header)?;
+        let encoded_state = encode(post_state)?;
+        let mut entries = Vec::with_capacity(block.transactions.len());
+        for (idx, (tx, receipt)) in block.transactions.iter().zip(receipts).enumerate() {
+            let location = TxLocation {
+                block_height: block.header.height,
+                block_hash,
+                tx_index: idx as u32,
+            };
+            let mut address_keys = vec![address_index_key(&tx.from, block.header.height, idx as u32).to_vec()];
+            if tx.to != tx.from {
+                address_keys.push(address_index_key(&tx.to, block.header.height, idx as u32).to_vec());
+            }
+            entries.push((tx.hash(), address_keys, encode(tx)?, encode(receipt)?, encode(&location)?));
+        }
+
+        (
+            &self.blocks, &self.block_hashes, &self.headers, &self.transactions, &self.tx_locations, &self.receipts,
+            &self.states, &self.state_roots, &self.address_index, &self.tx_sequence, &self.block_blooms,
+            &self.log_index, &self.meta,
+        ).transaction(|(blocks, block_hashes, headers, transactions, tx_locations, receipts, states, state_roots, address_index, tx_sequence, block_blooms, log_index, meta)| {
+            blocks.insert(&h_key, encoded_block.clone())?;
+            block_hashes.insert(&block_hash, &h_key)?;
+            headers.insert(&h_key, encoded_header.clone())?;
+            let mut seq = meta.get(META_NEXT_TX_SEQ)?.and_then(|v| height_from_key(&v)).unwrap_or(0);
+            for (tx_hash, address_keys, tx_bytes, receipt_bytes, location_bytes) in &entries {
+                transactions.insert(tx_hash, tx_bytes.clone())?;
+                receipts.insert(tx_hash, receipt_bytes.clone())?;
+                tx_locations.insert(tx_hash, location_bytes.clone())?;
+                for key in address_keys {
+                    address_index.insert(key.clone(), tx_hash)?;
+                }
+                tx_sequence.insert(&height_key(seq), tx_hash)?;
+                seq += 1;
+            }
+            meta.insert(META_NEXT_TX_SEQ, &height_key(seq))?;
+            block_blooms.insert(&h_key, &bloom.0[..])?;
+            for key in &log_keys {
+                log_index.insert(key.clone(), Vec::new())?;
+            }
+            states.insert(&h_key, encoded_state.clone())?;
+            state_roots.insert(&h_key, &state_root)?;
+            meta.insert(META_LATEST_STATE_HEIGHT, &h_key)?;
+            Ok(())
+        }).map_err(|e: TransactionError<String>| match e {
+            TransactionError::Abort(msg) => msg,
+            TransactionError::Storage(err) => err.to_string(),
+        })?;
+
+        self.db.flush().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Startup consistency check. Finds the highest height whose block, state and state
+    /// root agree, repairs a missing state root entry there, and rolls back anything
+    /// written above it (the leftovers of a commit interrupted by a crash).
+    pub fn check_integrity(&self) -> Result<IntegrityReport, String> {
+        let mut report = IntegrityReport::default();
+        let top = match self.blocks.last().map_err(|e| e.to_string())? {
+            Some((k, _)) => height_from_key(&k).ok_or("Corrupt block key")?,
+            None => return Ok(report),
+        };
+
+        let mut height = top;
+        let head = loop {
+            if let (Some(block), Some(state)) = (self.get_block(height)?, self.get_state(height)?) {
+                let root = state.calculate_root();
+                if root == block.header.state_root {
+                    if self.get_state_root(height)? != Some(root) {
+                        self.put_state_root(height, root)?;
+                        report.repaired_roots.push(height);
+                    }
+                    break height;
+                }
+            }
+            if height == 0 {
+                return Err(format!("No consistent block/state pair found at or below height {}", top));
+            }
+            height -= 1;
+        };
+
+        // Move the head pointer first so a crash during cleanup is simply re-run.
+        self.meta.insert(META_LATEST_STATE_HEIGHT, &height_key(head)).map_err(|e| e.to_string())?;
+        report.head = Some(head);
+        report.rolled_back = self.truncate_above(head)?.iter().map(|b| b.header.height).collect();
+        Ok(report)
+    }
+
+    /// Rewinds the canonical chain to `height` for a reorg, returning the removed blocks
+    /// (lowest first). The state stored at `height` becomes the head state.
+    pub fn rewind_to(&self, height: u64) -> Result<Vec<Block>, String> {
+        let block = self.get_block(height)?.ok_or_else(|| format!("No block stored at height {}", height))?;
+        match self.get_state(height)? {
+            Some(state) if state.calculate_root() == block.header.state_root => {}
+            _ => return Err(format!("No consistent state stored at height {}", height)),
+        }
+        self.meta.insert(META_LATEST_STATE_HEIGHT, &height_key(height)).map_err(|e| e.to_string())?;
+        self.truncate_above(height)
+    }
+
+    /// Removes every block above `head` together with its transactions, receipts,
+    /// indexes, header, state and state root.
+    fn truncate_above(&self, head: u64) -> Result<Vec<Block>, String> {
+        let mut removed = Vec::new();
+        let above = height_key(head + 1);
+        let mut removed_txs = std::collections::HashSet::new();
+        for item in self.blocks.range(above..) {
+            let (k, v) = item.map_err(|e| e.to_string())?;
+            let block: Block = decode(&v)?;
+            let mut block_receipts = Vec::new();
+            for (idx, tx) in block.transactions.iter().enumerate() {
+                let tx_hash = tx.hash();
+                if let Some(receipt) = Self::get_decoded::<TransactionReceipt>(&self.receipts, &tx_hash)? {
+                    block_receipts.push(receipt);
+                }
+                self.transactions.remove(tx_hash).map_err(|e| e.to_string())?;
+                self.receipts.remove(tx_hash).map_err(|e| e.to_string())?;
+                self.tx_locations.remove(tx_hash).map_err(|e| e.to_string())?;
+                for addr in [&tx.from, &tx.to] {
+                    self.address_index.remove(&address_index_key(addr, block.header.height, idx as u32)[..]).map_err(|e| e.to_string())?;
+                }
+                removed_txs.insert(tx_hash);
+            }
+            for key in log_index_keys(block.header.height, &block_receipts) {
+                self.log_index.remove(key).map_err(|e| e.to_string())?;
+            }
+            self.block_hashes.remove(block.header.hash()).map_err(|e| e.to_string())?;
+            self.blocks.remove(&k).map_err(|e| e.to_string())?;
+            removed.push(block);
+        }
+        for tree in [&self.headers, &self.states, &self.state_roots, &self.block_blooms] {
+            for item in tree.range(above..) {
+                let (k, _) = item.map_err(|e| e.to_string())?;
+                tree.remove(&k).map_err(|e| e.to_string())?;
+            }
+        }
+        // Rolled-back transactions were the last ones sequenced, so trim from the end.
+        if !removed_txs.is_empty() {
+            while let Some((k, v)) = self.tx_sequence.last().map_err(|e| e.to_string())? {
+                if !removed_txs.iter().any(|h| h[..] == v[..]) {
+                    break;
+                }
+                self.tx_sequence.remove(&k).map_err(|e| e.to_string())?;
+            }
+            let next = match self.tx_sequence.last().map_err(|e| e.to_string())? {
+                Some((k, _)) => height_from_key(&k).ok_or("Corrupt transaction sequence key")? + 1,
+                None => 0,
+            };
+            self.meta.insert(META_NEXT_TX_SEQ, &height_key(next)).map_err(|e| e.to_string())?;
+        }
+
+        self.db.flush().map_err(|e| e.to_string())?;
+        Ok(removed)
     }
 
     pub fn put_block(&self, block: &Block) -> Result<(), String> {
-        let val = serde_json::to_vec(block).map_err(|e| e.to_string())?;
-        // Index by height
-        let height_key = format!("block:{}", block.header.height);
-        self.db.insert(height_key, val.clone()).map_err(|e| e.to_string())?;
-        // Index by hash
-        let hash_key = format!("blockhash:{}", hex::encode(block.header.hash()));
-        self.db.insert(hash_key, val).map_err(|e| e.to_string())?;
+        let height = height_key(block.header.height);
+        self.blocks.insert(height, encode(block)?).map_err(|e| e.to_string())?;
+        self.block_hashes.insert(block.header.hash(), &height).map_err(|e| e.to_string())?;
         self.db.flush().map_err(|e| e.to_string())?; // Ensure block is on disk
         Ok(())
     }
 
     pub fn get_block(&self, height: u64) -> Result<Option<Block>, String> {
-        let key = format!("block:{}", height);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(Some(serde_json::from_slice(&data).map_err(|e| e.to_string())?)),
-            None => Ok(None),
-        }
+        Self::get_decoded(&self.blocks, &height_key(height))
     }
 
     pub fn get_block_by_hash(&self, hash_hex: &str) -> Result<Option<Block>, String> {
-        let h = hash_hex.strip_prefix("0x").unwrap_or(hash_hex).to_lowercase();
-        let key = format!("blockhash:{}", h);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(Some(serde_json::from_slice(&data).map_err(|e| e.to_string())?)),
+        let key = match hash_key(hash_hex) {
+            Some(k) => k,
+            None => return Ok(None),
+        };
+        match self.block_hashes.get(key).map_err(|e| e.to_string())? {
+            Some(h) => match height_from_key(&h) {
+                Some(height) => self.get_block(height),
+                None => Err("Corrupt block hash index entry".to_string()),
+            },
             None => Ok(None),
         }
     }
 
     pub fn put_state_root(&self, height: u64, root: [u8; 32]) -> Result<(), String> {
-        let key = format!("stateroot:{}", height);
-        self.db.insert(key, &root).map_err(|e| e.to_string())?;
+        self.state_roots.insert(height_key(height), &root).map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub fn put_transaction(&self, tx: &crate::types::transaction::Transaction) -> Result<(), String> {
-        let key = format!("tx:{}", hex::encode(tx.hash()));
-        let val = serde_json::to_vec(tx).map_err(|e| e.to_string())?;
-        self.db.insert(key, val).map_err(|e| e.to_string())?;
+    pub fn get_state_root(&self, height: u64) -> Result<Option<[u8; 32]>, String> {
+        match self.state_roots.get(height_key(height)).map_err(|e| e.to_string())? {
+            Some(v) => Ok(Some(v.as_ref().try_into().map_err(|_| "Corrupt state root entry".to_string())?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_transaction(&self, tx: &Transaction) -> Result<(), String> {
+        self.transactions.insert(tx.hash(), encode(tx)?).map_err(|e| e.to_string())?;
         Ok(())
     }
 
     // Store mapping of transaction hash to block height and hash
     pub fn put_transaction_location(&self, tx_hash: &[u8; 32], block_height: u64, block_hash: &[u8; 32], tx_index: usize) -> Result<(), String> {
-        let key = format!("txloc:{}", hex::encode(tx_hash));
-        let location = serde_json::json!({
-            "block_height": block_height,
-            "block_hash": hex::encode(block_hash),
-            "tx_index": tx_index
-        });
-        let val = serde_json::to_vec(&location).map_err(|e| e.to_string())?;
-        self.db.insert(key, val).map_err(|e| e.to_string())?;
+        let location = TxLocation {
+            block_height,
+            block_hash: *block_hash,
+            tx_index: tx_index as u32,
+        };
+        self.tx_locations.insert(tx_hash, encode(&location)?).map_err(|e| e.to_string())?;
         Ok(())
     }
 
     pub fn get_transaction_location(&self, tx_hash: &str) -> Result<Option<(u64, String, usize)>, String> {
-        let h = tx_hash.strip_prefix("0x").unwrap_or(tx_hash).to_lowercase();
-        let key = format!("txloc:{}", h);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => {
-                let location: serde_json::Value = serde_json::from_slice(&data).map_err(|e| e.to_string())?;
-                let height = location["block_height"].as_u64().unwrap_or(0);
-                let hash = location["block_hash"].as_str().unwrap_or("").to_string();
-                let index = location["tx_index"].as_u64().unwrap_or(0) as usize;
-                Ok(Some((height, hash, index)))
-            },
-            None => Ok(None),
-        }
+        let key = match hash_key(tx_hash) {
+            Some(k) => k,
+            None => return Ok(None),
+        };
+        let location: Option<TxLocation> = Self::get_decoded(&self.tx_locations, &key)?;
+        Ok(location.map(|l| (l.block_height, hex::encode(l.block_hash), l.tx_index as usize)))
     }
 
-    pub fn get_transaction(&self, hash_hex: &str) -> Result<Option<crate::types::transaction::Transaction>, String> {
-        let h = hash_hex.strip_prefix("0x").unwrap_or(hash_hex).to_lowercase();
-        let key = format!("tx:{}", h);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(Some(serde_json::from_slice(&data).map_err(|e| e.to_string())?)),
+    pub fn get_transaction(&self, hash_hex: &str) -> Result<Option<Transaction>, String> {
+        match hash_key(hash_hex) {
+            Some(key) => Self::get_decoded(&self.transactions, &key),
             None => Ok(None),
         }
     }
-    
-    pub fn put_receipt(&self, receipt: &crate::types::transaction::TransactionReceipt) -> Result<(), String> {
-        let key = format!("receipt:{}", hex::encode(receipt.tx_hash));
-        let val = serde_json::to_vec(receipt).map_err(|e| e.to_string())?;
-        self.db.insert(key, val).map_err(|e| e.to_string())?;
+
+    pub fn put_receipt(&self, receipt: &TransactionReceipt) -> Result<(), String> {
+        self.receipts.insert(receipt.tx_hash, encode(receipt)?).map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub fn get_receipt(&self, hash_hex: &str) -> Result<Option<crate::types::transaction::TransactionReceipt>, String> {
-        let h = hash_hex.strip_prefix("0x").unwrap_or(hash_hex).to_lowercase();
-        let key = format!("receipt:{}", h);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(Some(serde_json::from_slice(&data).map_err(|e| e.to_string())?)),
+    pub fn get_receipt(&self, hash_hex: &str) -> Result<Option<TransactionReceipt>, String> {
+        match hash_key(hash_hex) {
+            Some(key) => Self::get_decoded(&self.receipts, &key),
             None => Ok(None),
         }
     }
 
-    pub fn put_state(&self, height: u64, state: &crate::state::account::State) -> Result<(), String> {
-        let key = format!("state:{}", height);
-        let val = serde_json::to_vec(state).map_err(|e| e.to_string())?;
-        self.db.insert(key, val).map_err(|e| e.to_string())?;
+    pub fn put_state(&self, height: u64, state: &State) -> Result<(), String> {
+        self.states.insert(height_key(height), encode(state)?).map_err(|e| e.to_string())?;
         // Also update latest state pointer
-        self.db.insert("latest_state_height", &height.to_be_bytes()).map_err(|e| e.to_string())?;
+        self.meta.insert(META_LATEST_STATE_HEIGHT, &height.to_be_bytes()).map_err(|e| e.to_string())?;
         self.db.flush().map_err(|e| e.to_string())?; // Ensure state and pointer are on disk
         Ok(())
     }
 
-    pub fn get_state(&self, height: u64) -> Result<Option<crate::state::account::State>, String> {
-        let key = format!("state:{}", height);
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(Some(serde_json::from_slice(&data).map_err(|e| e.to_string())?)),
-            None => Ok(None),
-        }
+    pub fn get_state(&self, height: u64) -> Result<Option<State>, String> {
+        Self::get_decoded(&self.states, &height_key(height))
+    }
+
+    /// Height of the head pointer, without loading the state.
+    pub fn latest_height(&self) -> Result<Option<u64>, String> {
+        let val = self.meta.get(META_LATEST_STATE_HEIGHT).map_err(|e| e.to_string())?;
+        Ok(val.and_then(|v| height_from_key(&v)))
     }
 
-    pub fn get_latest_state(&self) -> Result<Option<(u64, crate::state::account::State)>, String> {
-        let height_val = self.db.get("latest_state_height").map_err(|e| e.to_string())?;
+    pub fn get_latest_state(&self) -> Result<Option<(u64, State)>, String> {
+        let height_val = self.meta.get(META_LATEST_STATE_HEIGHT).map_err(|e| e.to_string())?;
         if let Some(h_bytes) = height_val {
-            let height = u64::from_be_bytes(h_bytes.as_ref().try_into().unwrap_or([0; 8]));
-            let key = format!("state:{}", height);
-            let state_val = self.db.get(key).map_err(|e| e.to_string())?;
-            if let Some(data) = state_val {
-                let state = serde_json::from_slice(&data).map_err(|e| e.to_string())?;
+            let height = height_from_key(&h_bytes).unwrap_or(0);
+            if let Some(state) = self.get_state(height)? {
                 return Ok(Some((height, state)));
             }
         }
@@ -148,116 +443,67 @@ impl Storage {
     /// Senior Architect Update: Fallback mechanism to find the actual max height in the DB
     /// if the 'latest_state_height' pointer is out of sync or missing.
     pub fn get_max_height_fallback(&self) -> u64 {
-        let mut max_h = 0;
-        for item in self.db.scan_prefix("block:") {
-            if let Ok((k, _)) = item {
-                let k_str = String::from_utf8_lossy(&k);
-                if let Some(h_str) = k_str.strip_prefix("block:") {
-                    if let Ok(h) = h_str.parse::<u64>() {
-                        if h > max_h { max_h = h; }
-                    }
-                }
-            }
-        }
-        max_h
+        self.blocks.last().ok().flatten()
+            .and_then(|(k, _)| height_from_key(&k))
+            .unwrap_or(0)
     }
 
-    pub fn put_index(&self, address: &crate::address::Address, tx_hash: [u8; 32]) -> Result<(), String> {
-        let key = format!("addr_txs:{}", address.to_hex());
-        let mut txs = self.get_address_history(address)?;
-        txs.push(tx_hash);
-        let val = serde_json::to_vec(&txs).map_err(|e| e.to_string())?;
-        self.db.insert(key, val).map_err(|e| e.to_string())?;
+    /// Records a rejected block so it is never re-applied and can be inspected later.
+    pub fn quarantine_block(&self, entry: &QuarantinedBlock) -> Result<(), String> {
+        self.quarantine.insert(entry.block.header.hash(), encode(entry)?).map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub fn get_address_history(&self, address: &crate::address::Address) -> Result<Vec<[u8; 32]>, String> {
-        let key = format!("addr_txs:{}", address.to_hex());
-        let val = self.db.get(key).map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(serde_json::from_slice(&data).map_err(|e| e.to_string())?),
-            None => Ok(Vec::new()),
-        }
+    pub fn get_quarantined(&self, hash: &[u8; 32]) -> Result<Option<QuarantinedBlock>, String> {
+        Self::get_decoded(&self.quarantine, hash)
     }
 
-    pub fn put_global_transaction(&self, tx_hash: [u8; 32]) -> Result<(), String> {
-        let mut txs = self.get_global_transactions()?;
-        txs.push(tx_hash);
-        let val = serde_json::to_vec(&txs).map_err(|e| e.to_string())?;
-        self.db.insert("global_txs", val).map_err(|e| e.to_string())?;
+    pub fn is_quarantined(&self, hash: &[u8; 32]) -> Result<bool, String> {
+        self.quarantine.contains_key(hash).map_err(|e| e.to_string())
+    }
+
+    /// Stores the precommits that finalized the block at `commit.height`.
+    pub fn put_finality_commit(&self, commit: &FinalityCommit) -> Result<(), String> {
+        self.finality_commits.insert(height_key(commit.height), encode(commit)?).map_err(|e| e.to_string())?;
+        self.finality_commits.flush().map_err(|e| e.to_string())?;
         Ok(())
     }
 
-    pub fn get_global_transactions(&self) -> Result<Vec<[u8; 32]>, String> {
-        let val = self.db.get("global_txs").map_err(|e| e.to_string())?;
-        match val {
-            Some(data) => Ok(serde_json::from_slice(&data).map_err(|e| e.to_string())?),
-            None => Ok(Vec::new()),
+    pub fn get_finality_commit(&self, height: u64) -> Result<Option<FinalityCommit>, String> {
+        Self::get_decoded(&self.finality_commits, &height_key(height))
+    }
+
+    /// Commit for the highest finalized height, if any block has been finalized by BFT.
+    pub fn latest_finality_commit(&self) -> Result<Option<FinalityCommit>, String> {
+        match self.finality_commits.last().map_err(|e| e.to_string())? {
+            Some((_, v)) => Ok(Some(decode(&v)?)),
+            None => Ok(None),
         }
     }
 
     pub fn clear_all_data(&self) -> Result<(), String> {
-        // Clear blocks
-        for key in self.db.scan_prefix("block:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear block hashes
-        for key in self.db.scan_prefix("blockhash:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear transactions
-        for key in self.db.scan_prefix("tx:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
+        for tree in [
+            &self.blocks,
+            &self.block_hashes,
+            &self.headers,
+            &self.transactions,
+            &self.tx_locations,
+            &self.receipts,
+            &self.states,
+            &self.state_roots,
+            &self.address_index,
+            &self.tx_sequence,
+            &self.block_blooms,
+            &self.log_index,
+            &self.quarantine,
+            &self.finality_commits,
+        ] {
+            tree.clear().map_err(|e| e.to_string())?;
         }
-        
-        // Clear transaction locations
-        for key in self.db.scan_prefix("txloc:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear receipts
-        for key in self.db.scan_prefix("receipt:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear address indices
-        for key in self.db.scan_prefix("addr_txs:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear global transactions
-        self.db.remove("global_txs").map_err(|e| e.to_string())?;
-        
-        // Clear state roots
-        for key in self.db.scan_prefix("stateroot:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        // Clear states
-        for key in self.db.scan_prefix("state:") {
-            if let Ok((k, _)) = key {
-                self.db.remove(k).map_err(|e| e.to_string())?;
-            }
-        }
-        
-        self.db.remove("latest_state_height").map_err(|e| e.to_string())?;
-        
+
+        self.meta.remove(META_NEXT_TX_SEQ).map_err(|e| e.to_string())?;
+        self.meta.remove(META_LATEST_STATE_HEIGHT).map_err(|e| e.to_string())?;
+
         self.db.flush().map_err(|e| e.to_string())?;
         println!("[STORAGE] Database cleared successfully");
         Ok(())
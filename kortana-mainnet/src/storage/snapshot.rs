@@ -0,0 +1,289 @@
+// File: src/storage/snapshot.rs
+//
+// State snapshots for bootstrapping a node without replaying from genesis.
+//
+// Archive layout (all integers big-endian):
+//   magic "KORTSNAP" (8) | format (2) | chain id (8) | height (8) | payload length (8)
+//   | payload: codec-encoded `SnapshotArchive` | keccak256 of everything before it (32)
+//
+// The fixed header lets tooling identify an archive without decoding it; the trailing
+// checksum catches truncated or corrupted files before anything is written to the DB.
+//
+// An archive only vouches for itself: its headers link and its state matches their
+// roots, but a made-up chain can do both. Its head block must therefore be a checkpoint
+// the operator trusts, and every header below it follows by hash.
+
+use std::io::{Read, Write};
+use serde::{Serialize, Deserialize};
+use sha3::{Digest, Keccak256};
+use super::codec::{decode, encode, hash_key, height_key};
+use super::{Storage, META_LATEST_STATE_HEIGHT};
+use crate::state::account::State;
+use crate::state::trie::MerklePatriciaTrie;
+use crate::types::block::{Block, BlockHeader};
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"KORTSNAP";
+const SNAPSHOT_FORMAT: u16 = 1;
+const SNAPSHOT_HEADER_LEN: usize = 8 + 2 + 8 + 8 + 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotArchive {
+    pub chain_id: u64,
+    pub height: u64,
+    /// Headers 0..=height, so the importing node can serve and verify the chain history.
+    pub headers: Vec<BlockHeader>,
+    /// The `state_roots` entries 0..=height.
+    pub state_roots: Vec<(u64, [u8; 32])>,
+    /// Full block at `height`; the node resumes on top of it.
+    pub head_block: Block,
+    pub state: State,
+}
+
+/// A block the operator trusts to be canonical, written `HEIGHT:HASH` and read off a
+/// node they run or another source they trust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub hash: [u8; 32],
+}
+
+impl std::str::FromStr for Checkpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (height, hash) = s.split_once(':').ok_or("Checkpoint must be HEIGHT:HASH")?;
+        Ok(Self {
+            height: height.parse().map_err(|_| format!("Invalid checkpoint height '{}'", height))?,
+            hash: hash_key(hash).ok_or_else(|| format!("Invalid checkpoint hash '{}'", hash))?,
+        })
+    }
+}
+
+impl std::fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:0x{}", self.height, hex::encode(self.hash))
+    }
+}
+
+/// Summary printed by the CLI after export/import.
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    pub chain_id: u64,
+    pub height: u64,
+    pub block_hash: [u8; 32],
+    pub state_root: [u8; 32],
+    pub accounts: usize,
+}
+
+impl SnapshotInfo {
+    /// The checkpoint another node imports this snapshot with.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { height: self.height, hash: self.block_hash }
+    }
+}
+
+impl SnapshotArchive {
+    fn info(&self) -> SnapshotInfo {
+        SnapshotInfo {
+            chain_id: self.chain_id,
+            height: self.height,
+            block_hash: self.head_block.header.hash(),
+            state_root: self.head_block.header.state_root,
+            accounts: self.state.accounts.len(),
+        }
+    }
+
+    /// Checks that the head block is `checkpoint`, that the headers form a chain from
+    /// `genesis_hash` to it, that every state root entry matches its header, and that the
+    /// state itself hashes to the head header's state root.
+    pub fn verify(&self, chain_id: u64, genesis_hash: [u8; 32], checkpoint: &Checkpoint) -> Result<(), String> {
+        if self.chain_id != chain_id {
+            return Err(format!("Snapshot is for chain {} but this node runs chain {}", self.chain_id, chain_id));
+        }
+        if self.headers.len() as u64 != self.height + 1 {
+            return Err(format!("Snapshot has {} headers for height {}", self.headers.len(), self.height));
+        }
+        if self.headers[0].hash() != genesis_hash {
+            return Err("Snapshot genesis does not match this node's genesis block".to_string());
+        }
+        for (i, pair) in self.headers.windows(2).enumerate() {
+            if pair[0].height != i as u64 || pair[1].height != i as u64 + 1 {
+                return Err(format!("Snapshot header {} is out of order", i));
+            }
+            if pair[1].parent_hash != pair[0].hash() {
+                return Err(format!("Snapshot header {} does not link to its parent", i + 1));
+            }
+        }
+
+        let head = &self.headers[self.height as usize];
+        if self.head_block.header.hash() != head.hash() {
+            return Err("Snapshot head block does not match its header".to_string());
+        }
+        let head_checkpoint = Checkpoint { height: self.height, hash: head.hash() };
+        if head_checkpoint != *checkpoint {
+            return Err(format!("Snapshot head {} is not the trusted checkpoint {}", head_checkpoint, checkpoint));
+        }
+        if self.state_roots.len() != self.headers.len() {
+            return Err(format!("Snapshot has {} state roots for {} headers", self.state_roots.len(), self.headers.len()));
+        }
+        for (h, root) in &self.state_roots {
+            match self.headers.get(*h as usize) {
+                Some(header) if header.state_root == *root => {}
+                _ => return Err(format!("Snapshot state root for height {} does not match its header", h)),
+            }
+        }
+
+        // Rebuild the storage and account tries instead of trusting the serialized ones.
+        for (addr, account) in &self.state.accounts {
+            let mut trie = MerklePatriciaTrie::new();
+            for (key, value) in self.state.storage.get(addr).into_iter().flatten() {
+                trie.insert(key, value.to_vec());
+            }
+            let serialized = self.state.storage_tries.get(addr).map(|t| t.root_hash).unwrap_or([0u8; 32]);
+            if trie.root_hash != account.storage_root || serialized != account.storage_root {
+                return Err(format!("Snapshot storage of {} does not match its storage root", addr.to_hex()));
+            }
+        }
+        let committed = self.state.storage.get(&crate::staking::staking_address())
+            .and_then(|slots| slots.get(&crate::staking::STAKING_ROOT_SLOT));
+        if committed != Some(&self.state.staking.root()) {
+            return Err("Snapshot staking store does not match its committed root".to_string());
+        }
+        let committed = self.state.storage.get(&crate::core::governance::governance_address())
+            .and_then(|slots| slots.get(&crate::core::governance::GOVERNANCE_ROOT_SLOT));
+        if committed != Some(&self.state.governance.root()) {
+            return Err("Snapshot governance store does not match its committed root".to_string());
+        }
+        let committed = self.state.storage.get(&crate::core::governance::governance_address())
+            .and_then(|slots| slots.get(&crate::core::governance::PARAMS_ROOT_SLOT));
+        if committed != Some(&self.state.params.root()) {
+            return Err("Snapshot chain parameters do not match their committed root".to_string());
+        }
+        let mut trie = MerklePatriciaTrie::new();
+        for (addr, account) in &self.state.accounts {
+            trie.insert(&addr.to_bytes(), serde_json::to_vec(account).map_err(|e| e.to_string())?);
+        }
+        if trie.root_hash != head.state_root || self.state.calculate_root() != head.state_root {
+            return Err(format!(
+                "Snapshot state root 0x{} does not match header state root 0x{}",
+                hex::encode(trie.root_hash), hex::encode(head.state_root)
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn write_to(&self, path: &str) -> Result<(), String> {
+        let payload = encode(self)?;
+        let mut out = Vec::with_capacity(SNAPSHOT_HEADER_LEN + payload.len() + 32);
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.extend_from_slice(&SNAPSHOT_FORMAT.to_be_bytes());
+        out.extend_from_slice(&self.chain_id.to_be_bytes());
+        out.extend_from_slice(&self.height.to_be_bytes());
+        out.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+        out.extend_from_slice(&payload);
+        let checksum: [u8; 32] = Keccak256::digest(&out).into();
+        out.extend_from_slice(&checksum);
+
+        let mut file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+        file.write_all(&out).map_err(|e| e.to_string())?;
+        file.sync_all().map_err(|e| e.to_string())
+    }
+
+    pub fn read_from(path: &str) -> Result<Self, String> {
+        let mut bytes = Vec::new();
+        std::fs::File::open(path).and_then(|mut f| f.read_to_end(&mut bytes)).map_err(|e| e.to_string())?;
+        if bytes.len() < SNAPSHOT_HEADER_LEN + 32 || &bytes[..8] != SNAPSHOT_MAGIC {
+            return Err("Not a Kortana snapshot archive".to_string());
+        }
+
+        let (body, checksum) = bytes.split_at(bytes.len() - 32);
+        let expected: [u8; 32] = Keccak256::digest(body).into();
+        if checksum != expected {
+            return Err("Snapshot checksum mismatch: archive is corrupted or truncated".to_string());
+        }
+
+        let be_u64 = |at: usize| u64::from_be_bytes(body[at..at + 8].try_into().unwrap());
+        let format = u16::from_be_bytes([body[8], body[9]]);
+        if format != SNAPSHOT_FORMAT {
+            return Err(format!("Unsupported snapshot format {} (expected {})", format, SNAPSHOT_FORMAT));
+        }
+        let (chain_id, height, len) = (be_u64(10), be_u64(18), be_u64(26) as usize);
+        if body.len() != SNAPSHOT_HEADER_LEN + len {
+            return Err("Snapshot payload length does not match header".to_string());
+        }
+
+        let archive: SnapshotArchive = decode(&body[SNAPSHOT_HEADER_LEN..])?;
+        if archive.chain_id != chain_id || archive.height != height {
+            return Err("Snapshot header does not match its payload".to_string());
+        }
+        Ok(archive)
+    }
+}
+
+impl Storage {
+    /// Header at `height`, from the header chain or, failing that, the stored block.
+    pub fn get_header(&self, height: u64) -> Result<Option<BlockHeader>, String> {
+        if let Some(header) = Self::get_decoded(&self.headers, &height_key(height))? {
+            return Ok(Some(header));
+        }
+        Ok(self.get_block(height)?.map(|b| b.header))
+    }
+
+    /// Writes a snapshot of the chain at `height` to `path`.
+    pub fn export_snapshot(&self, height: u64, chain_id: u64, path: &str) -> Result<SnapshotInfo, String> {
+        let state = self.get_state(height)?.ok_or_else(|| format!("No state stored at height {}", height))?;
+        let head_block = self.get_block(height)?.ok_or_else(|| format!("No block stored at height {}", height))?;
+
+        let mut headers = Vec::with_capacity(height as usize + 1);
+        let mut state_roots = Vec::with_capacity(height as usize + 1);
+        for h in 0..=height {
+            let header = self.get_header(h)?.ok_or_else(|| format!("Missing header at height {}", h))?;
+            let root = self.get_state_root(h)?.unwrap_or(header.state_root);
+            state_roots.push((h, root));
+            headers.push(header);
+        }
+
+        let archive = SnapshotArchive { chain_id, height, headers, state_roots, head_block, state };
+        archive.write_to(path)?;
+        Ok(archive.info())
+    }
+
+    /// Verifies the archive at `path` against the trusted `checkpoint` and loads it into
+    /// an empty database, leaving the node ready to resume at the snapshot height.
+    pub fn import_snapshot(&self, path: &str, chain_id: u64, genesis_hash: [u8; 32], checkpoint: &Checkpoint) -> Result<SnapshotInfo, String> {
+        if self.blocks.first().map_err(|e| e.to_string())?.is_some() || self.headers.first().map_err(|e| e.to_string())?.is_some() {
+            return Err("Refusing to import a snapshot into a non-empty database".to_string());
+        }
+
+        let archive = SnapshotArchive::read_from(path)?;
+        archive.verify(chain_id, genesis_hash, checkpoint)?;
+        self.write_archive(&archive)?;
+        Ok(archive.info())
+    }
+
+    /// Installs state assembled by snap sync. Only the genesis block may be present.
+    pub fn install_snap_state(&self, archive: &SnapshotArchive, genesis_hash: [u8; 32], checkpoint: &Checkpoint) -> Result<SnapshotInfo, String> {
+        if self.latest_height()?.unwrap_or(0) > 0 {
+            return Err("Refusing to install snap-synced state over an existing chain".to_string());
+        }
+        archive.verify(archive.chain_id, genesis_hash, checkpoint)?;
+        self.write_archive(archive)?;
+        Ok(archive.info())
+    }
+
+    fn write_archive(&self, archive: &SnapshotArchive) -> Result<(), String> {
+        for header in &archive.headers {
+            self.headers.insert(height_key(header.height), encode(header)?).map_err(|e| e.to_string())?;
+        }
+        for (h, root) in &archive.state_roots {
+            self.put_state_root(*h, *root)?;
+        }
+        self.put_block(&archive.head_block)?;
+        let h_key = height_key(archive.height);
+        self.states.insert(h_key, encode(&archive.state)?).map_err(|e| e.to_string())?;
+        // The head pointer goes last: until it is written the import is simply incomplete.
+        self.meta.insert(META_LATEST_STATE_HEIGHT, &h_key).map_err(|e| e.to_string())?;
+        self.db.flush().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
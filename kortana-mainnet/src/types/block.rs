@@ -3,8 +3,17 @@
 use serde::{Serialize, Deserialize};
 use sha3::{Digest, Keccak256};
 use crate::address::Address;
+use crate::consensus::bft::FinalityCommit;
+use crate::consensus::evidence::Evidence;
+use crate::consensus::PohEntry;
+use crate::types::bloom::Bloom;
 use crate::types::transaction::Transaction;
 
+/// Header version written by this release. Version 1 headers predate the VRF proof,
+/// logs bloom, evidence root and last commit hash and are hashed without them, so
+/// blocks stored before those fields existed keep their hashes.
+pub const HEADER_VERSION: u32 = 2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockHeader {
     pub version: u32,
@@ -21,10 +30,44 @@ pub struct BlockHeader {
     pub gas_used: u64,
     pub gas_limit: u64,
     pub base_fee: u128,
+    /// Beta of `vrf_proof`; seeds the leader selection of the next block.
     pub vrf_output: [u8; 32],
+    /// Proposer's ECVRF proof over the parent's `vrf_output` and this slot, under the
+    /// key that signs the block.
+    #[serde(default)]
+    pub vrf_proof: Vec<u8>,
+    /// Union of the logs blooms of all receipts in the block.
+    #[serde(default)]
+    pub logs_bloom: Bloom,
+    /// Merkle root of the hashes of the evidence included in the block.
+    #[serde(default)]
+    pub evidence_root: [u8; 32],
+    /// Hash of the finality commit carried in the block, zero if it carries none.
+    #[serde(default)]
+    pub last_commit_hash: [u8; 32],
+}
+
+/// Input the proposer of `slot` proves with the VRF: the parent's output and the slot.
+fn vrf_input(parent_vrf_output: &[u8; 32], slot: u64) -> Vec<u8> {
+    let mut alpha = b"KORTANA/VRF/v1".to_vec();
+    alpha.extend_from_slice(parent_vrf_output);
+    alpha.extend_from_slice(&slot.to_be_bytes());
+    alpha
 }
 
 impl BlockHeader {
+    /// Fills in `vrf_proof` and `vrf_output` for a block on a parent with the given output.
+    pub fn prove_vrf(&mut self, private_key: &[u8], parent_vrf_output: &[u8; 32]) -> Result<(), String> {
+        self.vrf_proof = crate::crypto::vrf::prove(private_key, &vrf_input(parent_vrf_output, self.slot))?;
+        self.vrf_output = crate::crypto::vrf::proof_to_hash(&self.vrf_proof).ok_or("Malformed VRF proof")?;
+        Ok(())
+    }
+
+    /// Checks `vrf_proof` against the proposer's public key and that `vrf_output` is its output.
+    pub fn verify_vrf(&self, public_key: &[u8], parent_vrf_output: &[u8; 32]) -> bool {
+        crate::crypto::vrf::verify(public_key, &vrf_input(parent_vrf_output, self.slot), &self.vrf_proof) == Some(self.vrf_output)
+    }
+
     pub fn hash(&self) -> [u8; 32] {
         let mut hasher = Keccak256::new();
         hasher.update(self.version.to_be_bytes());
@@ -42,6 +85,12 @@ impl BlockHeader {
         hasher.update(self.gas_limit.to_be_bytes());
         hasher.update(self.base_fee.to_be_bytes());
         hasher.update(self.vrf_output);
+        if self.version >= HEADER_VERSION {
+            hasher.update(&self.vrf_proof);
+            hasher.update(self.logs_bloom.0);
+            hasher.update(self.evidence_root);
+            hasher.update(self.last_commit_hash);
+        }
         hasher.finalize().into()
     }
 }
@@ -51,6 +100,16 @@ pub struct Block {
     pub header: BlockHeader,
     pub transactions: Vec<Transaction>,
     pub signature: Vec<u8>,
+    /// Misbehaviour of validators, punished when the block is applied.
+    #[serde(default)]
+    pub evidence: Vec<Evidence>,
+    /// Commit for an earlier block; its signers are credited with participation.
+    #[serde(default)]
+    pub last_commit: Option<FinalityCommit>,
+    /// Proof-of-History segment from the parent's PoH tail to this header's, with the
+    /// transactions mixed in in block order.
+    #[serde(default)]
+    pub poh_entries: Vec<PohEntry>,
 }
 
 impl Block {
@@ -59,12 +118,21 @@ impl Block {
             header,
             transactions,
             signature: Vec::new(),
+            evidence: Vec::new(),
+            last_commit: None,
+            poh_entries: Vec::new(),
         }
     }
 
     pub fn sign(&mut self, private_key: &[u8]) {
         let hash = self.header.hash();
-        self.signature = crate::crypto::sign_message(private_key, &hash);
+        self.signature = crate::crypto::sign_recoverable(private_key, &hash);
+    }
+
+    /// Address of the key that signed the header, if the signature is well-formed.
+    pub fn recover_signer(&self) -> Option<Address> {
+        let pubkey = crate::crypto::recover_pubkey(&self.header.hash(), &self.signature)?;
+        Some(Address::from_pubkey(&pubkey))
     }
 
     pub fn verify(&self, public_key: &[u8]) -> bool {
@@ -77,9 +145,22 @@ impl Block {
         Self::compute_merkle_root(&tx_hashes)
     }
 
+    pub fn calculate_evidence_root(evidence: &[Evidence]) -> [u8; 32] {
+        let hashes: Vec<[u8; 32]> = evidence.iter().map(|e| e.hash()).collect();
+        Self::compute_merkle_root(&hashes)
+    }
+
+    pub fn calculate_last_commit_hash(commit: Option<&FinalityCommit>) -> [u8; 32] {
+        commit.map_or([0u8; 32], |c| Keccak256::digest(bincode::serialize(c).expect("Commit serializes")).into())
+    }
+
+    /// Roots over the transaction hashes and over the full receipts, so the header
+    /// commits to every status, gas figure, log and bloom the block produced.
     pub fn calculate_merkle_roots(txs: &[Transaction], receipts: &[crate::types::transaction::TransactionReceipt]) -> ([u8; 32], [u8; 32]) {
         let tx_hashes: Vec<[u8; 32]> = txs.iter().map(|tx| tx.hash()).collect();
-        let receipt_hashes: Vec<[u8; 32]> = receipts.iter().map(|r| r.tx_hash).collect();
+        let receipt_hashes: Vec<[u8; 32]> = receipts.iter()
+            .map(|r| Keccak256::digest(bincode::serialize(r).expect("Receipt serializes")).into())
+            .collect();
         
         (
             Self::compute_merkle_root(&tx_hashes),
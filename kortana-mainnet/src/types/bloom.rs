@@ -0,0 +1,146 @@
+// File: src/types/bloom.rs
+//
+// 2048-bit logs bloom, bit-compatible with Ethereum's (Yellow Paper M3:2048): each
+// input sets three bits taken from the low 11 bits of the first three byte pairs of
+// its keccak256 hash. Inputs are log addresses (20-byte EVM form) and log topics.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha3::{Digest, Keccak256};
+use crate::types::transaction::{TransactionLog, TransactionReceipt};
+
+pub const BLOOM_BYTES: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Bloom(pub [u8; BLOOM_BYTES]);
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Bloom([0u8; BLOOM_BYTES])
+    }
+}
+
+impl std::fmt::Debug for Bloom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Bloom({})", self.to_hex())
+    }
+}
+
+impl Bloom {
+    fn bits(input: &[u8]) -> [(usize, u8); 3] {
+        let hash = Keccak256::digest(input);
+        let mut out = [(0usize, 0u8); 3];
+        for (i, slot) in out.iter_mut().enumerate() {
+            let bit = (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) & 0x7ff;
+            *slot = (BLOOM_BYTES - 1 - bit / 8, 1u8 << (bit % 8));
+        }
+        out
+    }
+
+    pub fn accrue(&mut self, input: &[u8]) {
+        for (byte, mask) in Self::bits(input) {
+            self.0[byte] |= mask;
+        }
+    }
+
+    pub fn accrue_log(&mut self, log: &TransactionLog) {
+        self.accrue(&log.address.as_evm_address());
+        for topic in &log.topics {
+            self.accrue(topic);
+        }
+    }
+
+    pub fn accrue_bloom(&mut self, other: &Bloom) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a |= b;
+        }
+    }
+
+    pub fn from_logs(logs: &[TransactionLog]) -> Self {
+        let mut bloom = Bloom::default();
+        for log in logs {
+            bloom.accrue_log(log);
+        }
+        bloom
+    }
+
+    /// Block-level bloom: the union of all receipt blooms.
+    pub fn from_receipts(receipts: &[TransactionReceipt]) -> Self {
+        let mut bloom = Bloom::default();
+        for receipt in receipts {
+            bloom.accrue_bloom(&receipt.logs_bloom);
+        }
+        bloom
+    }
+
+    /// May contain `input`; false means it definitely does not.
+    pub fn contains_input(&self, input: &[u8]) -> bool {
+        Self::bits(input).iter().all(|(byte, mask)| self.0[*byte] & mask == *mask)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|b| *b == 0)
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.0))
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, String> {
+        let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| e.to_string())?;
+        let arr: [u8; BLOOM_BYTES] = bytes.try_into().map_err(|_| "Bloom must be 256 bytes".to_string())?;
+        Ok(Bloom(arr))
+    }
+}
+
+// Hex string in human-readable formats (JSON), raw bytes in binary ones (storage).
+impl Serialize for Bloom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bloom {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Bloom::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            let bytes: Vec<u8> = serde::de::Deserialize::deserialize(deserializer)?;
+            let arr: [u8; BLOOM_BYTES] = bytes.try_into().map_err(|_| serde::de::Error::custom("Bloom must be 256 bytes"))?;
+            Ok(Bloom(arr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+
+    #[test]
+    fn test_bloom_contains_accrued_inputs() {
+        let log = TransactionLog {
+            address: Address::from_pubkey(b"token"),
+            topics: vec![[0xddu8; 32]],
+            data: vec![],
+        };
+        let bloom = Bloom::from_logs(std::slice::from_ref(&log));
+        assert!(bloom.contains_input(&log.address.as_evm_address()));
+        assert!(bloom.contains_input(&[0xddu8; 32]));
+        assert!(!bloom.contains_input(&[0x01u8; 32]));
+    }
+
+    #[test]
+    fn test_bloom_serde_roundtrip() {
+        let mut bloom = Bloom::default();
+        bloom.accrue(b"kortana");
+        let json = serde_json::to_string(&bloom).unwrap();
+        assert_eq!(serde_json::from_str::<Bloom>(&json).unwrap(), bloom);
+        let bin = bincode::serialize(&bloom).unwrap();
+        assert_eq!(bincode::deserialize::<Bloom>(&bin).unwrap(), bloom);
+    }
+}
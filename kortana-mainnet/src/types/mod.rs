@@ -1,3 +1,4 @@
 // File: src/types/mod.rs
 pub mod transaction;
 pub mod block;
+pub mod bloom;
@@ -174,9 +174,11 @@ impl Transaction {
                  Address::from_evm_address(b)
              };
 
-             let chain_id = if v >= 35 { (v - 35) / 2 } else { 72511 };
+             // Unprotected (pre-EIP-155) signatures are valid on every chain; refuse them
+             if v < 35 { return Err("Legacy transaction without EIP-155 chain ID".into()); }
+             let chain_id = (v - 35) / 2;
              
-             // Reconstruct signing data for Legacy/EIP-155
+             // Reconstruct signing data for EIP-155
              let mut s_rlp = RlpStream::new_list(9);
              s_rlp.append(&nonce).append(&gas_price).append(&gas_limit).append(&to_bytes)
                   .append(&value).append(&data).append(&chain_id).append(&0u8).append(&0u8);
@@ -200,7 +202,7 @@ impl Transaction {
         let recovery_id = if bytes[0] == 0x02 {
             RecoveryId::try_from(v as u8).map_err(|_| "Invalid recovery ID")?
         } else {
-            let rec_v = if v >= 35 { (v - 35) % 2 } else { v % 2 };
+            let rec_v = (v - 35) % 2;
             RecoveryId::try_from(rec_v as u8).map_err(|_| "Invalid legacy recovery ID")?
         };
 
@@ -245,6 +247,8 @@ pub struct TransactionReceipt {
     pub gas_used: u64,
     pub logs: Vec<TransactionLog>,
     pub contract_address: Option<Address>, // Contract address for deployments
+    #[serde(default)]
+    pub logs_bloom: crate::types::bloom::Bloom,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,7 +2,7 @@
 
 use serde::{Serialize, Deserialize};
 use sha3::{Digest, Keccak256};
-use crate::parameters::CHAIN_ID;
+use crate::core::forks::Fork;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EvmError {
@@ -380,7 +380,7 @@ impl EvmExecutor {
                 0x43 => { self.consume_gas(2)?; self.stack.push(Self::u128_to_u256(header.height as u128))?; } // NUMBER
                 0x44 => { self.consume_gas(2)?; self.stack.push(header.vrf_output)?; } // PREVRANDAO / DIFFICULTY
                 0x45 => { self.consume_gas(2)?; self.stack.push(Self::u128_to_u256(header.gas_limit as u128))?; } // GASLIMIT
-                0x46 => { self.consume_gas(2)?; self.stack.push(Self::u128_to_u256(CHAIN_ID as u128))?; } // CHAINID
+                0x46 => { self.consume_gas(2)?; self.stack.push(Self::u128_to_u256(state.params.chain_id as u128))?; } // CHAINID
                 0x47 => { // SELFBALANCE
                     self.consume_gas(5)?;
                     let acc = state.get_account(&self.address);
@@ -406,8 +406,7 @@ impl EvmExecutor {
                      self.consume_gas(20000)?; 
                      let key = self.stack.pop()?;
                      let val = self.stack.pop()?;
-                     let storage = state.storage.entry(self.address).or_default();
-                     storage.insert(key, val);
+                     state.set_storage(self.address, key, val);
                 }
 
                 // Flow
@@ -458,6 +457,16 @@ impl EvmExecutor {
                     self.consume_gas(2)?;
                     self.stack.push(Self::u128_to_u256(self.gas_remaining as u128))?;
                 }
+                0x5E if state.governance.forks.is_active(Fork::Mcopy, header.height) => { // MCOPY
+                    let dest_offset = Self::u256_to_usize(self.stack.pop()?)?;
+                    let offset = Self::u256_to_usize(self.stack.pop()?)?;
+                    let length = Self::u256_to_usize(self.stack.pop()?)?;
+                    self.consume_gas(3 + 3 * length.div_ceil(32) as u64)?;
+                    if length > 0 {
+                        let data = self.memory.load(offset, length)?;
+                        self.memory.store(dest_offset, &data);
+                    }
+                }
                 0x5F => { // PUSH0
                     self.consume_gas(2)?;
                     self.stack.push([0u8; 32])?;
@@ -961,6 +970,10 @@ mod tests {
             gas_limit: 1000000,
             base_fee: 1,
             vrf_output: [0u8; 32],
+            vrf_proof: Vec::new(),
+            logs_bloom: Default::default(),
+            evidence_root: [0u8; 32],
+            last_commit_hash: [0u8; 32],
         };
         
         // PUSH1 0x01, PUSH1 0x02, ADD, STOP
@@ -61,8 +61,7 @@ impl QuorlinExecutor {
                         key_bytes
                     };
 
-                    let storage = state.storage.entry(self.address).or_default();
-                    storage.insert(key_hash, bytes);
+                    state.set_storage(self.address, key_hash, bytes);
                 }
                 QuorlinOpcode::Address => {
                     // For Quorlin, address is just numeric-ish for now
@@ -0,0 +1,258 @@
+// tests/bft_test.rs
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::consensus::bft::{voting_stake, FinalityCommit, FinalityGadget, Proposal, Vote, VoteKind};
+use kortana_blockchain_rust::consensus::tendermint::{proposer_for, BftOutput, RoundState, Step, Timeout, TIMEOUT_DELTA, TIMEOUT_PROPOSE};
+use kortana_blockchain_rust::consensus::ValidatorInfo;
+use kortana_blockchain_rust::crypto::bls::BlsSecretKey;
+use kortana_blockchain_rust::staking::StakingStore;
+use kortana_blockchain_rust::storage::Storage;
+use k256::ecdsa::SigningKey;
+
+const CHAIN_ID: u64 = 9002;
+const BLOCK_X: [u8; 32] = [0xaa; 32];
+const BLOCK_Y: [u8; 32] = [0xbb; 32];
+
+struct Net {
+    keys: Vec<BlsSecretKey>,
+    validators: Vec<ValidatorInfo>,
+    nodes: Vec<Option<RoundState>>,
+    timeouts: Vec<(usize, Timeout)>,
+    commits: Vec<(usize, FinalityCommit)>,
+}
+
+impl Net {
+    /// Four equally staked validators at height 1; `offline` nodes neither send nor receive.
+    fn new(offline: &[usize]) -> Self {
+        let keys: Vec<BlsSecretKey> = (1..=4u8).map(|i| BlsSecretKey::from_seed(&[i; 32])).collect();
+        let validators: Vec<ValidatorInfo> = keys.iter().zip(1..=4u8)
+            .map(|(k, i)| ValidatorInfo { address: address_of(&[i; 32]), stake: 100, is_active: true, commission: 500, missed_blocks: 0, consensus_pubkey: k.public_key() })
+            .collect();
+        let nodes = keys.iter().enumerate()
+            .map(|(i, k)| (!offline.contains(&i)).then(|| RoundState::new(CHAIN_ID, 1, &validators, Some((validators[i].address, k.clone())))))
+            .collect();
+        Self { keys, validators, nodes, timeouts: Vec::new(), commits: Vec::new() }
+    }
+
+    fn index_of(&self, address: Address) -> usize {
+        self.validators.iter().position(|v| v.address == address).unwrap()
+    }
+
+    fn start(&mut self, candidate: [u8; 32]) {
+        for i in 0..self.nodes.len() {
+            let mut out = match self.nodes[i].as_mut() {
+                Some(node) => node.start(),
+                None => continue,
+            };
+            out.extend(self.nodes[i].as_mut().unwrap().add_candidate(candidate));
+            self.deliver(i, out);
+        }
+    }
+
+    /// Gossips `out` from node `from` to every other online node, recursively.
+    fn deliver(&mut self, from: usize, out: Vec<BftOutput>) {
+        let mut queue: Vec<(usize, BftOutput)> = out.into_iter().map(|o| (from, o)).collect();
+        while let Some((sender, output)) = queue.pop() {
+            for to in 0..self.nodes.len() {
+                let node = match self.nodes[to].as_mut() {
+                    Some(node) => node,
+                    None => continue,
+                };
+                let produced = match &output {
+                    BftOutput::Proposal(p) if to != sender => node.on_proposal(p.clone()).unwrap(),
+                    BftOutput::Vote(v) if to != sender => node.on_vote(v.clone()).unwrap(),
+                    _ => continue,
+                };
+                queue.extend(produced.into_iter().map(|o| (to, o)));
+            }
+            match output {
+                BftOutput::Timeout(t) => self.timeouts.push((sender, t)),
+                BftOutput::Commit(c) => self.commits.push((sender, c)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Fires every scheduled timeout, as if the network had gone quiet.
+    fn fire_timeouts(&mut self) {
+        for (i, t) in std::mem::take(&mut self.timeouts) {
+            let out = self.nodes[i].as_mut().unwrap().on_timeout(t);
+            self.deliver(i, out);
+        }
+    }
+}
+
+fn address_of(account_key: &[u8]) -> Address {
+    Address::from_pubkey(&SigningKey::from_slice(account_key).unwrap().verifying_key().to_sec1_bytes())
+}
+
+fn temp_db(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("kortana_bft_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_validators_commit_the_proposed_block_in_round_zero() {
+    let mut net = Net::new(&[]);
+    net.start(BLOCK_X);
+
+    assert_eq!(net.commits.len(), 4);
+    for (_, commit) in &net.commits {
+        assert_eq!((commit.height, commit.round, commit.block_hash), (1, 0, BLOCK_X));
+        commit.verify(CHAIN_ID, &net.validators).unwrap();
+    }
+
+    // One aggregate signature and a signer bitmap, whatever order the set is listed in.
+    // The first node to decide did so on exactly a quorum of precommits.
+    let commit = net.commits.iter().map(|(_, c)| c.clone()).find(|c| c.signer_count() == 3).unwrap();
+    assert_eq!((commit.signers.len(), commit.signature.len()), (1, 96));
+    let mut reordered = net.validators.clone();
+    reordered.reverse();
+    commit.verify(CHAIN_ID, &reordered).unwrap();
+
+    // A commit claiming a signer whose signature is not in the aggregate, with too little
+    // stake, or for another block does not verify
+    let mut forged = commit.clone();
+    forged.signers[0] = 0b1111;
+    assert!(forged.verify(CHAIN_ID, &net.validators).is_err());
+    let mut forged = commit.clone();
+    forged.signers[0] = 1 << forged.signers[0].trailing_zeros();
+    assert!(forged.verify(CHAIN_ID, &net.validators).is_err());
+    let mut forged = commit.clone();
+    forged.block_hash = BLOCK_Y;
+    assert!(forged.verify(CHAIN_ID, &net.validators).is_err());
+    let mut forged = commit.clone();
+    forged.signers.push(0);
+    assert!(forged.verify(CHAIN_ID, &net.validators).is_err());
+}
+
+#[test]
+fn test_silent_proposer_times_out_into_next_round() {
+    let proposer = {
+        let net = Net::new(&[]);
+        net.index_of(proposer_for(&net.validators, 1, 0).unwrap())
+    };
+    let mut net = Net::new(&[proposer]);
+    net.start(BLOCK_X);
+    assert!(net.commits.is_empty());
+    assert!(net.timeouts.iter().all(|(_, t)| t.step == Step::Propose && t.after == TIMEOUT_PROPOSE));
+
+    // Propose timeout -> nil prevotes -> nil precommits -> precommit timeout -> round 1
+    net.fire_timeouts();
+    assert!(net.timeouts.iter().any(|(_, t)| t.step == Step::Precommit && t.round == 0));
+    net.fire_timeouts();
+
+    assert_eq!(net.commits.len(), 3);
+    for (_, commit) in &net.commits {
+        assert_eq!((commit.round, commit.block_hash), (1, BLOCK_X));
+    }
+    // Round 1 waits longer than round 0
+    assert!(net.timeouts.iter().any(|(_, t)| t.round == 1 && t.step == Step::Propose && t.after == TIMEOUT_PROPOSE + TIMEOUT_DELTA));
+}
+
+#[test]
+fn test_locked_validator_prevotes_nil_for_other_block() {
+    let net = Net::new(&[]);
+    let vals = &net.validators;
+    let key_of = |addr: Address| net.keys[net.index_of(addr)].clone();
+    let me = (0..4).find(|i| Some(vals[*i].address) != proposer_for(vals, 1, 0) && Some(vals[*i].address) != proposer_for(vals, 1, 1)).unwrap();
+    let mut node = RoundState::new(CHAIN_ID, 1, vals, Some((vals[me].address, net.keys[me].clone())));
+    node.start();
+    node.add_candidate(BLOCK_X);
+    node.add_candidate(BLOCK_Y);
+
+    // Round 0: X is proposed and gets a polka; we lock on it and precommit it
+    let p0 = proposer_for(vals, 1, 0).unwrap();
+    let out = node.on_proposal(Proposal::new_signed(CHAIN_ID, 1, 0, BLOCK_X, None, p0, &key_of(p0))).unwrap();
+    assert!(out.iter().any(|o| matches!(o, BftOutput::Vote(v) if v.kind == VoteKind::Prevote && v.block_hash == Some(BLOCK_X))));
+    let others: Vec<usize> = (0..4).filter(|i| *i != me).collect();
+    let mut out = Vec::new();
+    for i in &others[..2] {
+        out.extend(node.on_vote(Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 1, 0, Some(BLOCK_X), vals[*i].address, &net.keys[*i])).unwrap());
+    }
+    assert!(out.iter().any(|o| matches!(o, BftOutput::Vote(v) if v.kind == VoteKind::Precommit && v.block_hash == Some(BLOCK_X))));
+
+    // The others precommit nil; the round times out
+    for i in &others[..2] {
+        node.on_vote(Vote::new_signed(CHAIN_ID, VoteKind::Precommit, 1, 0, None, vals[*i].address, &net.keys[*i])).unwrap();
+    }
+    node.on_timeout(Timeout { height: 1, round: 0, step: Step::Precommit, after: TIMEOUT_PROPOSE });
+    assert_eq!(node.round, 1);
+
+    // Round 1 proposes Y without a polka: we stay locked on X
+    let p1 = proposer_for(vals, 1, 1).unwrap();
+    let out = node.on_proposal(Proposal::new_signed(CHAIN_ID, 1, 1, BLOCK_Y, None, p1, &key_of(p1))).unwrap();
+    assert!(out.iter().any(|o| matches!(o, BftOutput::Vote(v) if v.kind == VoteKind::Prevote && v.round == 1 && v.block_hash.is_none())));
+
+    // Messages from the wrong proposer or with bad signatures are refused
+    assert!(node.on_proposal(Proposal::new_signed(CHAIN_ID, 1, 2, BLOCK_Y, None, p1, &key_of(p1))).is_err());
+    let mut vote = Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 1, 1, Some(BLOCK_Y), vals[others[0]].address, &net.keys[others[0]]);
+    vote.block_hash = Some(BLOCK_X);
+    assert!(node.on_vote(vote).is_err());
+}
+
+#[test]
+fn test_finality_commits_are_persisted() {
+    let mut net = Net::new(&[]);
+    net.start(BLOCK_X);
+    let commit = net.commits[0].1.clone();
+
+    let storage = Storage::new(&temp_db("commits"));
+    assert!(storage.latest_finality_commit().unwrap().is_none());
+    storage.put_finality_commit(&commit).unwrap();
+    let mut later = commit.clone();
+    later.height = 7;
+    storage.put_finality_commit(&later).unwrap();
+
+    assert_eq!(storage.get_finality_commit(1).unwrap().unwrap().block_hash, BLOCK_X);
+    assert_eq!(storage.latest_finality_commit().unwrap().unwrap().height, 7);
+    assert!(storage.get_finality_commit(2).unwrap().is_none());
+}
+
+#[test]
+fn test_votes_are_bound_to_chain_and_registered_key() {
+    let mut net = Net::new(&[]);
+    let address = net.validators[0].address;
+    let key = net.keys[0].clone();
+    let mut gadget = FinalityGadget::new();
+
+    let vote = Vote::new_signed(CHAIN_ID, VoteKind::Precommit, 1, 0, Some(BLOCK_X), address, &key);
+    assert!(gadget.add_vote(&vote, &net.validators, CHAIN_ID).unwrap().is_none());
+
+    // Replayed on another chain, or under a different message type
+    assert!(vote.verify(CHAIN_ID + 1, &net.validators).is_err());
+    let mut prevote = vote.clone();
+    prevote.kind = VoteKind::Prevote;
+    assert!(gadget.add_vote(&prevote, &net.validators, CHAIN_ID).is_err());
+
+    // Signed with a key other than the registered one
+    let new_key = BlsSecretKey::from_seed(&[9u8; 32]);
+    net.validators[0].consensus_pubkey = new_key.public_key();
+    assert!(vote.verify(CHAIN_ID, &net.validators).is_err());
+    let rotated = Vote::new_signed(CHAIN_ID, VoteKind::Precommit, 1, 0, Some(BLOCK_X), address, &new_key);
+    rotated.verify(CHAIN_ID, &net.validators).unwrap();
+
+    // Validators without a registered key cannot vote and carry no voting power
+    net.validators[0].consensus_pubkey.clear();
+    assert!(rotated.verify(CHAIN_ID, &net.validators).is_err());
+    assert_eq!(voting_stake(&net.validators), 300);
+    assert!((0..3).all(|round| proposer_for(&net.validators, 1, round) != Some(address)));
+}
+
+#[test]
+fn test_consensus_key_registration_requires_proof_of_possession() {
+    let validator = address_of(&[1u8; 32]);
+    let key = BlsSecretKey::from_seed(&[1u8; 32]);
+    let other = BlsSecretKey::from_seed(&[2u8; 32]);
+    let mut staking = StakingStore::new();
+    staking.delegate(validator, validator, 1_000, 0);
+
+    // A proof for another key, or a message signature instead of a proof, is refused
+    assert!(staking.register_consensus_key(validator, key.public_key(), &other.proof_of_possession()).is_err());
+    assert!(staking.register_consensus_key(validator, key.public_key(), &key.sign(&key.public_key())).is_err());
+    assert!(staking.register_consensus_key(validator, vec![0u8; 48], &key.proof_of_possession()).is_err());
+    assert!(staking.consensus_keys.is_empty());
+
+    staking.register_consensus_key(validator, key.public_key(), &key.proof_of_possession()).unwrap();
+    assert_eq!(staking.consensus_keys[&validator], key.public_key());
+}
@@ -0,0 +1,121 @@
+// tests/chain_spec_test.rs
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::chain_spec::ChainSpec;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::forks::Fork;
+use kortana_blockchain_rust::core::genesis::{create_genesis_state, create_genesis_state_for, genesis_hash};
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{CHAIN_ID, DEV_CHAIN_ID, MIN_VALIDATOR_STAKE, TESTNET_CHAIN_ID, UNBONDING_PERIOD_BLOCKS};
+use kortana_blockchain_rust::types::block::BlockHeader;
+use kortana_blockchain_rust::types::transaction::{Transaction, VmType};
+use kortana_blockchain_rust::vm::evm::EvmExecutor;
+use k256::ecdsa::SigningKey;
+use rlp::RlpStream;
+use sha3::{Digest, Keccak256};
+
+const DNR: u128 = 1_000_000_000_000_000_000;
+
+fn header(height: u64) -> BlockHeader {
+    BlockHeader {
+        version: 1, height, slot: height, timestamp: 1_700_000_000, parent_hash: [0u8; 32], state_root: [0u8; 32], transactions_root: [0u8; 32], receipts_root: [0u8; 32],
+        poh_hash: [0u8; 32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30_000_000, base_fee: 1, vrf_output: [0u8; 32], vrf_proof: Vec::new(),
+        logs_bloom: Default::default(), evidence_root: [0u8; 32], last_commit_hash: [0u8; 32],
+    }
+}
+
+/// Legacy transfer signed for `chain_id`, with `v` EIP-155 encoded or, if `unprotected`, 27/28.
+fn signed_legacy(key: &SigningKey, chain_id: u64, unprotected: bool) -> Vec<u8> {
+    let to = [0x11u8; 20];
+    let mut unsigned = RlpStream::new_list(9);
+    unsigned.append(&0u64).append(&1u128).append(&21_000u64).append(&to.as_slice()).append(&5u128).append(&Vec::<u8>::new())
+        .append(&chain_id).append(&0u8).append(&0u8);
+    let hash = Keccak256::digest(unsigned.out());
+    let (signature, recovery_id) = key.sign_prehash_recoverable(&hash).unwrap();
+    let v = if unprotected { 27 + recovery_id.to_byte() as u64 } else { chain_id * 2 + 35 + recovery_id.to_byte() as u64 };
+    let (r, s) = signature.split_bytes();
+    let mut signed = RlpStream::new_list(9);
+    signed.append(&0u64).append(&1u128).append(&21_000u64).append(&to.as_slice()).append(&5u128).append(&Vec::<u8>::new())
+        .append(&v).append(&&r[..]).append(&&s[..]);
+    signed.out().to_vec()
+}
+
+#[test]
+fn test_presets_build_distinct_genesis_states() {
+    let mainnet = create_genesis_state();
+    assert_eq!(mainnet.params.chain_id, CHAIN_ID);
+    // Foundation, faucet and owner, plus three validators holding 1M DNR and bonding 1M more
+    assert_eq!(mainnet.staking.total_supply, (490_000_000_000 + 306_993_000_000 + 7_000_000 + 6 * 1_000_000) * DNR);
+    let racknerd = Address::from_hex("0xadf256ef0fa3c0fe774f7dac0fd04040e4fdef50").unwrap();
+    assert!(mainnet.staking.consensus_keys.contains_key(&racknerd));
+    assert_eq!(mainnet.staking.validator_set().len(), 3);
+
+    let testnet = create_genesis_state_for(&ChainSpec::testnet()).unwrap();
+    assert_eq!(testnet.params.chain_id, TESTNET_CHAIN_ID);
+    assert_eq!(testnet.params.min_validator_stake, 32 * DNR);
+    assert_eq!(testnet.staking.validator_set().len(), 1);
+
+    let dev_spec = ChainSpec::dev();
+    let dev = create_genesis_state_for(&dev_spec).unwrap();
+    assert_eq!(dev.params.chain_id, DEV_CHAIN_ID);
+    assert!(dev.governance.forks.is_active(Fork::Mcopy, 0));
+    assert!(!mainnet.governance.forks.is_active(Fork::Mcopy, u64::MAX));
+
+    let hashes = [ChainSpec::mainnet(), ChainSpec::testnet(), dev_spec].map(|spec| genesis_hash(&spec).unwrap());
+    assert!(hashes[0] != hashes[1] && hashes[1] != hashes[2] && hashes[0] != hashes[2]);
+    assert_eq!(genesis_hash(&ChainSpec::load("mainnet").unwrap()).unwrap(), hashes[0]);
+}
+
+#[test]
+fn test_spec_file_fills_in_defaults_and_round_trips() {
+    let json = r#"{
+        "name": "private",
+        "chain_id": 4242,
+        "genesis_timestamp": 1750000000,
+        "params": {"block_gas_limit": 60000000, "chain_id": 1},
+        "accounts": [{"address": "0x28e514Ce1a0554B83f6d5EEEE11B07D0e294D9F9", "balance": 5000000000000000000000000}],
+        "validators": [{"address": "0x1111111111111111111111111111111111111111", "moniker": "v1", "stake": 1000000000000000000000000}],
+        "bootnodes": ["/ip4/10.0.0.1/tcp/30333"],
+        "forks": {"CalldataGas": 100}
+    }"#;
+    let spec = ChainSpec::from_json(json).unwrap();
+    assert_eq!(spec.params.chain_id, 4242);
+    assert_eq!(spec.params.block_gas_limit, 60_000_000);
+    assert_eq!(spec.params.unbonding_period_blocks, UNBONDING_PERIOD_BLOCKS);
+    assert_eq!(ChainSpec::from_json(&spec.to_json()).unwrap(), spec);
+
+    let state = create_genesis_state_for(&spec).unwrap();
+    assert!(state.governance.forks.is_active(Fork::CalldataGas, 100));
+    assert_eq!(state.staking.total_supply, 5_000_000 * DNR + MIN_VALIDATOR_STAKE);
+
+    // No validators, or a stake below the minimum, cannot start a chain
+    assert!(ChainSpec::from_json(r#"{"name": "x", "chain_id": 5, "genesis_timestamp": 0, "validators": []}"#).is_err());
+    let low = json.replace("1000000000000000000000000}", "1}");
+    assert!(create_genesis_state_for(&ChainSpec::from_json(&low).unwrap()).is_err());
+}
+
+#[test]
+fn test_transactions_and_contracts_see_the_spec_chain_id() {
+    let mut state = create_genesis_state_for(&ChainSpec::dev()).unwrap();
+    let validator = Address::from_hex(&ChainSpec::dev().validators[0].address).unwrap();
+    let tx = |nonce, chain_id| Transaction {
+        nonce, from: validator, to: Address::from_pubkey(b"recipient"), value: 1, gas_limit: 100_000, gas_price: 1,
+        data: Vec::new(), vm_type: VmType::EVM, chain_id, signature: None, cached_hash: None,
+    };
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    assert!(processor.process_transaction(tx(0, CHAIN_ID), &header(1)).is_err());
+    assert_eq!(processor.process_transaction(tx(0, DEV_CHAIN_ID), &header(1)).unwrap().status, 1);
+
+    // CHAINID, MSTORE, RETURN 32 bytes
+    let code = [0x46, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xF3];
+    let out = EvmExecutor::new(Address::from_pubkey(b"contract"), 100_000).execute(&code, processor.state, &header(1)).unwrap();
+    assert_eq!(out[24..], DEV_CHAIN_ID.to_be_bytes());
+}
+
+#[test]
+fn test_unprotected_legacy_transactions_are_refused() {
+    let key = SigningKey::from_bytes((&[7u8; 32]).into()).unwrap();
+    let tx = Transaction::decode_ethereum(&signed_legacy(&key, TESTNET_CHAIN_ID, false)).unwrap();
+    assert_eq!(tx.chain_id, TESTNET_CHAIN_ID);
+    assert_eq!(tx.value, 5);
+    assert!(Transaction::decode_ethereum(&signed_legacy(&key, TESTNET_CHAIN_ID, true)).is_err());
+}
@@ -0,0 +1,71 @@
+// tests/common/mod.rs
+//
+// Fixtures shared by the tests that drive `BlockProcessor` directly. Each test crate
+// uses only some of them.
+#![allow(dead_code)]
+
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::governance::governance_address;
+use kortana_blockchain_rust::parameters::{CHAIN_ID, GAS_LIMIT_PER_BLOCK};
+use kortana_blockchain_rust::staking::staking_address;
+use kortana_blockchain_rust::types::block::BlockHeader;
+use kortana_blockchain_rust::types::transaction::{Transaction, VmType};
+
+pub fn account(seed: &[u8]) -> Address {
+    Address::from_pubkey(seed)
+}
+
+/// Header at `height` (and slot `height`) proposed by the zero address.
+pub fn header(height: u64) -> BlockHeader {
+    BlockHeader {
+        version: 1,
+        height,
+        slot: height,
+        timestamp: 1_700_000_000,
+        parent_hash: [0u8; 32],
+        state_root: [0u8; 32],
+        transactions_root: [0u8; 32],
+        receipts_root: [0u8; 32],
+        poh_hash: [0u8; 32],
+        poh_sequence: 0,
+        proposer: Address::ZERO,
+        gas_used: 0,
+        gas_limit: GAS_LIMIT_PER_BLOCK,
+        base_fee: FeeMarket::new().base_fee,
+        vrf_output: [1u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
+    }
+}
+
+pub fn proposed_header(height: u64, proposer: Address) -> BlockHeader {
+    BlockHeader { proposer, ..header(height) }
+}
+
+/// Unsigned transaction at the minimum gas price.
+pub fn tx(from: Address, to: Address, nonce: u64, value: u128, data: Vec<u8>) -> Transaction {
+    Transaction {
+        nonce,
+        from,
+        to,
+        value,
+        gas_limit: 100_000,
+        gas_price: 1,
+        data,
+        vm_type: VmType::EVM,
+        chain_id: CHAIN_ID,
+        signature: None,
+        cached_hash: None,
+    }
+}
+
+pub fn staking_tx(from: Address, nonce: u64, value: u128, data: Vec<u8>) -> Transaction {
+    tx(from, staking_address(), nonce, value, data)
+}
+
+pub fn governance_tx(from: Address, nonce: u64, value: u128, data: Vec<u8>) -> Transaction {
+    tx(from, governance_address(), nonce, value, data)
+}
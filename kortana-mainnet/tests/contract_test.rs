@@ -39,7 +39,7 @@ mod tests {
 
         let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
         let header = kortana_blockchain_rust::types::block::BlockHeader {
-            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32]
+            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32], vrf_proof: Vec::new(), logs_bloom: Default::default(), evidence_root: [0u8; 32], last_commit_hash: [0u8; 32]
         };
 
         let receipt = processor.process_transaction(tx, &header).unwrap();
@@ -72,7 +72,7 @@ mod tests {
 
         let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
         let header = kortana_blockchain_rust::types::block::BlockHeader {
-            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32]
+            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32], vrf_proof: Vec::new(), logs_bloom: Default::default(), evidence_root: [0u8; 32], last_commit_hash: [0u8; 32]
         };
 
         let receipt = processor.process_transaction(tx, &header).unwrap();
@@ -115,7 +115,7 @@ mod tests {
 
         let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
         let header = kortana_blockchain_rust::types::block::BlockHeader {
-            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32]
+            version: 1, height: 1, slot: 1, timestamp: 123456789, parent_hash: [0u8;32], state_root: [0u8;32], transactions_root: [0u8;32], receipts_root: [0u8;32], poh_hash: [0u8;32], poh_sequence: 0, proposer: Address::ZERO, gas_used: 0, gas_limit: 30000000, base_fee: 1, vrf_output: [0u8; 32], vrf_proof: Vec::new(), logs_bloom: Default::default(), evidence_root: [0u8; 32], last_commit_hash: [0u8; 32]
         };
 
         let receipt = processor.process_transaction(tx, &header).unwrap();
@@ -73,6 +73,10 @@ fn test_header(height: u64) -> BlockHeader {
         gas_limit: 30_000_000,
         base_fee: 1,
         vrf_output: [0u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
     }
 }
 
@@ -0,0 +1,186 @@
+// tests/governance_test.rs
+mod common;
+
+use common::{account, governance_tx, header};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::consensus::slot_clock::SlotClock;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::governance::{GovernanceStore, ProposalStatus, ProposalType, VoteOption};
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{BLOCK_TIME_SECS, BLOCKS_PER_EPOCH, GAS_LIMIT_PER_BLOCK, GOV_MIN_DEPOSIT, GOV_VOTING_PERIOD_BLOCKS, MIN_VALIDATOR_STAKE};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+use std::time::Duration;
+
+fn parameter_change(key: &str, value: &str) -> ProposalType {
+    ProposalType::ParameterChange { key: key.to_string(), value: value.to_string() }
+}
+
+/// Validators "a" (2M bonded) and "b" (1M bonded), and a delegator with 1M on "a": 4M
+/// bonded in all. Everyone holds a deposit's worth of DNR.
+fn setup() -> (State, Address, Address, Address) {
+    let mut state = State::new();
+    let (a, b, delegator) = (account(b"a"), account(b"b"), account(b"delegator"));
+    for addr in [a, b, delegator] {
+        let mut acc = state.get_account(&addr);
+        acc.balance = GOV_MIN_DEPOSIT + 1_000_000;
+        state.update_account(addr, acc);
+    }
+    state.staking.create_validator(a, ValidatorProfile::new("a", 500, 1000, 100), None, 2 * MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.create_validator(b, ValidatorProfile::new("b", 500, 1000, 100), None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.delegate(delegator, a, MIN_VALIDATOR_STAKE, 0);
+    state.staking.total_supply = 4 * MIN_VALIDATOR_STAKE + 3 * (GOV_MIN_DEPOSIT + 1_000_000);
+    (state, a, b, delegator)
+}
+
+#[test]
+fn test_passed_parameter_change_applies_at_the_next_epoch_and_refunds_the_deposit() {
+    let (mut state, a, b, delegator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+
+    // Too small a deposit, or a change that could never execute, is refused
+    let key = b"gov_voting_period_blocks";
+    let data = [&[1u8, key.len() as u8][..], key, b"500"].concat();
+    let receipt = processor.process_transaction(governance_tx(b, 0, GOV_MIN_DEPOSIT - 1, data.clone()), &header(1)).unwrap();
+    assert_eq!(receipt.status, 0);
+    assert!(processor.state.governance.submit_proposal(b, parameter_change("gov_unknown", "1"), GOV_MIN_DEPOSIT, 1, &processor.state.params).is_err());
+    assert!(processor.state.governance.submit_proposal(b, parameter_change("gov_quorum_bps", "10001"), GOV_MIN_DEPOSIT, 1, &processor.state.params).is_err());
+
+    let receipt = processor.process_transaction(governance_tx(b, 1, GOV_MIN_DEPOSIT, data), &header(1)).unwrap();
+    assert_eq!(receipt.status, 1);
+    assert_eq!(processor.state.get_account(&b).balance, 1_000_000 - 2 * 50_000);
+    let end = 1 + GOV_VOTING_PERIOD_BLOCKS;
+    assert_eq!(processor.state.governance.proposals[&1].end_block, end);
+
+    // Only bonded stake votes
+    let vote = |option: u8| [&[3u8][..], &1u64.to_be_bytes(), &[option]].concat();
+    let outsider = account(b"outsider");
+    assert!(processor.state.governance.vote(1, outsider, VoteOption::Yes, &processor.state.staking, 2).is_err());
+
+    // "a" votes No and its delegator inherits that, until it votes Yes itself; "b" votes Yes
+    assert_eq!(processor.process_transaction(governance_tx(a, 0, 0, vote(1)), &header(2)).unwrap().status, 1);
+    let tally = |state: &State| GovernanceStore::tally(&state.governance.proposals[&1], &state.staking);
+    assert_eq!(tally(processor.state).no, 3 * MIN_VALIDATOR_STAKE);
+    assert_eq!(processor.process_transaction(governance_tx(b, 2, 0, vote(0)), &header(2)).unwrap().status, 1);
+    assert_eq!(processor.process_transaction(governance_tx(delegator, 0, 0, vote(0)), &header(3)).unwrap().status, 1);
+    let counted = tally(processor.state);
+    assert_eq!((counted.yes, counted.no), (2 * MIN_VALIDATOR_STAKE, 2 * MIN_VALIDATOR_STAKE));
+
+    // Votes count until the last voting block, where "a" abstaining breaks the tie
+    processor.end_block(&header(end - 1));
+    assert_eq!(processor.state.governance.proposals[&1].status, ProposalStatus::Voting);
+    processor.state.governance.vote(1, a, VoteOption::Abstain, &processor.state.staking, end).unwrap();
+    processor.end_block(&header(end));
+    assert!(processor.state.governance.vote(1, a, VoteOption::Yes, &processor.state.staking, end + 1).is_err());
+
+    let proposal = &processor.state.governance.proposals[&1];
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+    assert_eq!(proposal.tally.abstain, 2 * MIN_VALIDATOR_STAKE);
+    assert_eq!(processor.state.get_account(&b).balance, GOV_MIN_DEPOSIT + 1_000_000 - 3 * 50_000);
+
+    // The change waits for the next epoch boundary
+    assert_eq!(processor.state.params.gov_voting_period_blocks, GOV_VOTING_PERIOD_BLOCKS);
+    let boundary = (end / BLOCKS_PER_EPOCH + 1) * BLOCKS_PER_EPOCH;
+    processor.end_block(&header(boundary - 1));
+    assert_eq!(processor.state.params.gov_voting_period_blocks, GOV_VOTING_PERIOD_BLOCKS);
+    processor.end_block(&header(boundary));
+    assert_eq!(processor.state.params.gov_voting_period_blocks, 500);
+    assert!(processor.state.governance.scheduled.is_empty());
+    assert_eq!(processor.state.governance.submit_proposal(a, parameter_change("gov_quorum_bps", "5000"), GOV_MIN_DEPOSIT, boundary, &processor.state.params).unwrap(), 2);
+    assert_eq!(processor.state.governance.proposals[&2].end_block, boundary + 500);
+}
+
+#[test]
+fn test_missed_quorum_and_veto_burn_the_deposit() {
+    let (mut state, a, b, delegator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let governance = &mut processor.state.governance;
+    for _ in 0..3 {
+        governance.submit_proposal(b, parameter_change("gov_veto_bps", "5000"), GOV_MIN_DEPOSIT, 1, &processor.state.params).unwrap();
+    }
+    let staking = &processor.state.staking;
+    // 1: only "b", a quarter of the stake, votes
+    governance.vote(1, b, VoteOption::Yes, staking, 2).unwrap();
+    // 2: half of the votes veto
+    governance.vote(2, a, VoteOption::Yes, staking, 2).unwrap();
+    governance.vote(2, delegator, VoteOption::NoWithVeto, staking, 2).unwrap();
+    governance.vote(2, b, VoteOption::NoWithVeto, staking, 2).unwrap();
+    // 3: plain No wins; the deposit comes back
+    governance.vote(3, a, VoteOption::No, staking, 2).unwrap();
+
+    let supply = processor.state.staking.total_supply;
+    processor.end_block(&header(1 + GOV_VOTING_PERIOD_BLOCKS));
+    let status: Vec<ProposalStatus> = processor.state.governance.proposals.values().map(|p| p.status.clone()).collect();
+    assert_eq!(status, vec![ProposalStatus::NoQuorum, ProposalStatus::Vetoed, ProposalStatus::Rejected]);
+    assert_eq!(processor.state.staking.total_supply, supply - 2 * GOV_MIN_DEPOSIT);
+    assert_eq!(processor.state.get_account(&b).balance, 2 * GOV_MIN_DEPOSIT + 1_000_000);
+    assert!(processor.state.governance.scheduled.is_empty());
+}
+
+#[test]
+fn test_scheduled_change_that_no_longer_validates_fails_at_activation() {
+    let (mut state, a, b, _) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let params = processor.state.params.clone();
+    let governance = &mut processor.state.governance;
+    // Each is valid alone; together the jail threshold would exceed the liveness window
+    governance.submit_proposal(b, parameter_change("max_missed_blocks_before_jail", "80"), GOV_MIN_DEPOSIT, 1, &params).unwrap();
+    governance.submit_proposal(b, parameter_change("liveness_window_blocks", "60"), GOV_MIN_DEPOSIT, 1, &params).unwrap();
+    for id in [1, 2] {
+        governance.vote(id, a, VoteOption::Yes, &processor.state.staking, 2).unwrap();
+    }
+    processor.end_block(&header(1 + GOV_VOTING_PERIOD_BLOCKS));
+    let root = processor.state.calculate_root();
+
+    let boundary = (1 + GOV_VOTING_PERIOD_BLOCKS) / BLOCKS_PER_EPOCH * BLOCKS_PER_EPOCH + BLOCKS_PER_EPOCH;
+    processor.end_block(&header(boundary));
+    assert_eq!(processor.state.params.max_missed_blocks_before_jail, 80);
+    assert_eq!(processor.state.params.liveness_window_blocks, 100);
+    assert_eq!(processor.state.governance.proposals[&1].status, ProposalStatus::Passed);
+    assert!(matches!(processor.state.governance.proposals[&2].status, ProposalStatus::Failed(_)));
+    assert_ne!(processor.state.calculate_root(), root);
+}
+
+#[test]
+fn test_activated_gas_limit_moves_the_fee_market_target() {
+    let (mut state, a, b, _) = setup();
+    let id = state.governance.submit_proposal(a, parameter_change("block_gas_limit", "40000000"), GOV_MIN_DEPOSIT, 1, &state.params).unwrap();
+    state.governance.vote(id, a, VoteOption::Yes, &state.staking, 2).unwrap();
+    state.governance.vote(id, b, VoteOption::Yes, &state.staking, 2).unwrap();
+    let end = 1 + GOV_VOTING_PERIOD_BLOCKS;
+    let boundary = (end / BLOCKS_PER_EPOCH + 1) * BLOCKS_PER_EPOCH;
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&header(end));
+    assert_eq!(FeeMarket::after(&header(end), &processor.state.params).target_gas_per_block, GAS_LIMIT_PER_BLOCK / 2);
+    processor.end_block(&header(boundary));
+    assert_eq!(processor.state.params.block_gas_limit, 40_000_000);
+    // The market for the block after the boundary targets half the new limit
+    assert_eq!(FeeMarket::after(&header(boundary), &processor.state.params).target_gas_per_block, 20_000_000);
+}
+
+#[tokio::test]
+async fn test_slot_clock_follows_an_activated_block_time() {
+    let (mut state, a, b, _) = setup();
+    let mut clock = SlotClock::new(&state.params);
+    assert_eq!(clock.slot_duration(), Duration::from_secs(BLOCK_TIME_SECS));
+    let id = state.governance.submit_proposal(a, parameter_change("block_time_secs", "1"), GOV_MIN_DEPOSIT, 1, &state.params).unwrap();
+    state.governance.vote(id, a, VoteOption::Yes, &state.staking, 2).unwrap();
+    state.governance.vote(id, b, VoteOption::Yes, &state.staking, 2).unwrap();
+    let end = 1 + GOV_VOTING_PERIOD_BLOCKS;
+    let boundary = (end / BLOCKS_PER_EPOCH + 1) * BLOCKS_PER_EPOCH;
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&header(end));
+    assert!(!clock.follow(&processor.state.params));
+    processor.end_block(&header(boundary));
+    assert!(clock.follow(&processor.state.params));
+    assert!(!clock.follow(&processor.state.params));
+    assert_eq!(clock.slot_duration(), Duration::from_secs(1));
+
+    // The next slot starts one new slot length after the change
+    let start = tokio::time::Instant::now();
+    clock.tick().await;
+    let waited = start.elapsed();
+    assert!(waited >= Duration::from_millis(900) && waited < Duration::from_secs(BLOCK_TIME_SECS), "{:?}", waited);
+}
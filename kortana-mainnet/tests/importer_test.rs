@@ -0,0 +1,564 @@
+// tests/importer_test.rs
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::consensus::{ConsensusEngine, PohRecorder};
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::genesis::{create_genesis_block, create_genesis_state};
+use kortana_blockchain_rust::core::chain::{accept_block, finalize_block, update_head};
+use kortana_blockchain_rust::core::importer::{BlockImporter, ImportError};
+use kortana_blockchain_rust::consensus::fork_choice::BlockTree;
+use kortana_blockchain_rust::consensus::snap::{SnapPhase, SnapSync};
+use kortana_blockchain_rust::consensus::sync::{serve_request, SyncEngine};
+use kortana_blockchain_rust::network::messages::{SyncRequest, SyncResponse};
+use kortana_blockchain_rust::crypto::bls::BlsSecretKey;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{BLOCKS_PER_EPOCH, CHAIN_ID, GAS_LIMIT_PER_BLOCK, MIN_VALIDATOR_STAKE};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::{StakingStore, ValidatorProfile};
+use kortana_blockchain_rust::storage::{QuarantinedBlock, Storage};
+use kortana_blockchain_rust::storage::snapshot::Checkpoint;
+use std::sync::Arc;
+use kortana_blockchain_rust::types::block::{Block, BlockHeader, HEADER_VERSION};
+use kortana_blockchain_rust::types::transaction::{Transaction, TransactionReceipt, VmType};
+use k256::ecdsa::SigningKey;
+
+fn temp_db(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("kortana_importer_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    path.to_string_lossy().to_string()
+}
+
+struct Fixture {
+    storage: Storage,
+    consensus: ConsensusEngine,
+    state: State,
+    genesis: Block,
+    key: Vec<u8>,
+}
+
+fn setup(name: &str) -> Fixture {
+    setup_with(name, create_genesis_state())
+}
+
+/// Genesis `state` with the fixture key as its only validator; the engine takes its set
+/// and leader schedule from that staking state, as the node does.
+fn setup_with(name: &str, mut state: State) -> Fixture {
+    let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+    let proposer = Address::from_pubkey(&key.verifying_key().to_sec1_bytes());
+    let bls = BlsSecretKey::from_seed(&[7u8; 32]);
+    state.staking = StakingStore::new();
+    state.staking.create_validator(
+        proposer, ValidatorProfile::new("", 500, 1000, 100), Some((bls.public_key(), bls.proof_of_possession().as_slice())),
+        MIN_VALIDATOR_STAKE, 0, &state.params,
+    ).unwrap();
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
+    state.commit_staking();
+    let mut consensus = ConsensusEngine::new(Vec::new());
+    consensus.sync_validators(&state.staking);
+
+    let storage = Storage::new(&temp_db(name));
+    let genesis = create_genesis_block(state.calculate_root());
+    storage.commit_block(&genesis, &[], &state).unwrap();
+    Fixture { storage, consensus, state, genesis, key: key.to_bytes().to_vec() }
+}
+
+fn child_block(f: &Fixture, key: &[u8]) -> Block {
+    next_block(&f.genesis.header, 1, key, &f.state).0
+}
+
+fn block_on(parent: &BlockHeader, slot: u64, key: &[u8], state_root: [u8; 32]) -> Block {
+    let proposer = Address::from_pubkey(&SigningKey::from_slice(key).unwrap().verifying_key().to_sec1_bytes());
+    let mut header = BlockHeader {
+        version: HEADER_VERSION,
+        height: parent.height + 1,
+        slot,
+        timestamp: parent.timestamp + 2,
+        parent_hash: parent.hash(),
+        state_root,
+        transactions_root: [0u8; 32],
+        receipts_root: [0u8; 32],
+        poh_hash: [0u8; 32],
+        poh_sequence: 0,
+        proposer,
+        gas_used: 0,
+        gas_limit: GAS_LIMIT_PER_BLOCK,
+        base_fee: FeeMarket::new().base_fee,
+        vrf_output: [1u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
+    };
+    header.prove_vrf(key, &parent.vrf_output).unwrap();
+    let mut poh = PohRecorder::new(parent);
+    poh.tick_to(slot);
+    (header.poh_hash, header.poh_sequence) = poh.tail();
+    let mut block = Block::new(header, vec![]);
+    block.poh_entries = poh.entries().to_vec();
+    block.sign(key);
+    block
+}
+
+/// A block carrying `txs`, with the roots, gas and bloom of executing it on `state`.
+fn block_with_txs(parent: &BlockHeader, slot: u64, key: &[u8], state: &State, txs: Vec<Transaction>) -> (Block, Vec<TransactionReceipt>) {
+    let mut block = block_on(parent, slot, key, [0u8; 32]);
+    let mut poh = PohRecorder::new(parent);
+    for tx in &txs {
+        poh.record(&tx.hash());
+    }
+    poh.tick_to(slot);
+    (block.header.poh_hash, block.header.poh_sequence) = poh.tail();
+    block.poh_entries = poh.entries().to_vec();
+    block.header.transactions_root = Block::calculate_tx_root(&txs);
+    block.transactions = txs;
+
+    let mut post = state.clone();
+    let receipts = BlockProcessor::new(&mut post, FeeMarket::new()).validate_block(&block).unwrap();
+    block.header.state_root = post.calculate_root();
+    block.header.gas_used = receipts.iter().map(|r| r.gas_used).sum();
+    block.header.receipts_root = Block::calculate_merkle_roots(&block.transactions, &receipts).1;
+    block.sign(key);
+    (block, receipts)
+}
+
+/// An empty block on `parent` together with the state executing it on `state` leaves.
+fn next_block(parent: &BlockHeader, slot: u64, key: &[u8], state: &State) -> (Block, State) {
+    let (block, _) = block_with_txs(parent, slot, key, state, vec![]);
+    let mut post = state.clone();
+    BlockProcessor::new(&mut post, FeeMarket::new()).validate_block(&block).unwrap();
+    (block, post)
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+}
+
+#[test]
+fn test_valid_block_is_imported() {
+    let mut f = setup("valid");
+    let block = child_block(&f, &f.key);
+    assert_eq!(block.recover_signer(), Some(block.header.proposer));
+
+    let mut fees = FeeMarket::new();
+    BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(f.storage.get_latest_state().unwrap().unwrap().0, 1);
+    assert_eq!(f.storage.get_block(1).unwrap().unwrap().header.hash(), block.header.hash());
+}
+
+#[test]
+fn test_block_from_non_leader_is_rejected_and_quarantined() {
+    let mut f = setup("non_leader");
+    let block = child_block(&f, &[9u8; 32]);
+
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::WrongLeader { .. }), "{}", err);
+    assert!(err.is_invalid());
+    assert!(err.peer_penalty() > 0);
+    assert!(f.storage.get_block(1).unwrap().is_none());
+
+    let entry = QuarantinedBlock { block: block.clone(), reason: err.to_string(), peer: Some("peer-a".into()), received_at: 0 };
+    f.storage.quarantine_block(&entry).unwrap();
+    assert!(f.storage.is_quarantined(&block.header.hash()).unwrap());
+    assert_eq!(f.storage.get_quarantined(&block.header.hash()).unwrap().unwrap().peer.as_deref(), Some("peer-a"));
+}
+
+#[test]
+fn test_tampered_header_fails_signature_check() {
+    let mut f = setup("tampered");
+    let mut block = child_block(&f, &f.key);
+    block.header.gas_used = 21_000;
+
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::SignerMismatch { .. } | ImportError::BadSignature), "{}", err);
+}
+
+#[test]
+fn test_header_hash_covers_new_fields_only_from_version_2() {
+    let mut f = setup("version");
+    let mut block = child_block(&f, &f.key);
+    let mut legacy = block.header.clone();
+    legacy.version = 1;
+    let hash = legacy.hash();
+    legacy.logs_bloom.0[0] = 1;
+    legacy.last_commit_hash = [3u8; 32];
+    assert_eq!(legacy.hash(), hash);
+    let v2 = block.header.hash();
+    block.header.logs_bloom.0[0] = 1;
+    assert_ne!(block.header.hash(), v2);
+
+    // Peers must send blocks whose hash commits to every header field
+    let mut block = child_block(&f, &f.key);
+    block.header.version = 1;
+    block.sign(&f.key);
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::UnsupportedVersion(1)), "{}", err);
+}
+
+#[test]
+fn test_receipts_root_commits_to_receipt_contents() {
+    let mut f = setup("receipts");
+    let tx = Transaction {
+        nonce: 0, from: Address::from_pubkey(b"foundation"), to: Address::from_pubkey(b"recipient"), value: 1, gas_limit: 21_000,
+        gas_price: FeeMarket::new().base_fee, data: vec![], vm_type: VmType::EVM, chain_id: CHAIN_ID, signature: None, cached_hash: None,
+    };
+    let (mut block, mut receipts) = block_with_txs(&f.genesis.header, 1, &f.key, &f.state, vec![tx]);
+    let honest = block.clone();
+
+    // The proposer reports the transfer as failed
+    receipts[0].status = 0;
+    block.header.receipts_root = Block::calculate_merkle_roots(&block.transactions, &receipts).1;
+    block.sign(&f.key);
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::ReceiptsRootMismatch), "{}", err);
+
+    BlockImporter::new(&f.storage, &f.consensus).import(&honest, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(f.storage.get_receipt(&hex::encode(honest.transactions[0].hash())).unwrap().unwrap().status, 1);
+}
+
+#[test]
+fn test_base_fee_is_derived_from_parent_header() {
+    // A 40k gas limit targets 20k, so a single transfer makes a busy block
+    let mut genesis_state = create_genesis_state();
+    genesis_state.params.block_gas_limit = 40_000;
+    let params = genesis_state.params.clone();
+    let mut f = setup_with("base_fee", genesis_state);
+    let tx = Transaction {
+        nonce: 0, from: Address::from_pubkey(b"foundation"), to: Address::from_pubkey(b"recipient"), value: 1, gas_limit: 21_000,
+        gas_price: 10, data: vec![], vm_type: VmType::EVM, chain_id: CHAIN_ID, signature: None, cached_hash: None,
+    };
+    let (mut busy, _) = block_with_txs(&f.genesis.header, 1, &f.key, &f.state, vec![tx]);
+    busy.header.gas_limit = params.block_gas_limit;
+    busy.sign(&f.key);
+    let mut fees = FeeMarket::new();
+    BlockImporter::new(&f.storage, &f.consensus).import(&busy, &mut f.state, &mut fees, now()).unwrap();
+    let expected = FeeMarket::after(&busy.header, &params).base_fee;
+    assert!(expected > busy.header.base_fee);
+    assert_eq!(fees.base_fee, expected);
+
+    // A restarted node holds a fresh fee market, yet still expects the raised base fee
+    let mut fees = FeeMarket::new();
+    let (mut stale, _) = next_block(&busy.header, 2, &f.key, &f.state);
+    stale.header.gas_limit = params.block_gas_limit;
+    stale.sign(&f.key);
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&stale, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::Execution(_)), "{}", err);
+
+    let mut block = stale.clone();
+    block.header.base_fee = expected;
+    block.sign(&f.key);
+    BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(fees.base_fee, FeeMarket::after(&block.header, &params).base_fee);
+}
+
+#[test]
+fn test_vrf_proof_over_wrong_seed_is_rejected() {
+    let mut f = setup("bad_vrf");
+    let mut block = child_block(&f, &f.key);
+    // A well-formed proof, but not over the parent's VRF output
+    block.header.prove_vrf(&f.key, &[7u8; 32]).unwrap();
+    block.sign(&f.key);
+
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::BadVrfProof { .. }), "{}", err);
+    assert!(err.is_invalid());
+}
+
+#[test]
+fn test_poh_segment_must_replay_and_cover_the_slots() {
+    let mut f = setup("bad_poh");
+    let importer = BlockImporter::new(&f.storage, &f.consensus);
+    let block = child_block(&f, &f.key);
+    importer.check_poh(&block, &f.genesis.header).unwrap();
+
+    // A forged entry breaks the replay even though the tail still matches the header
+    let mut forged = block.clone();
+    forged.poh_entries[3].hash = [5u8; 32];
+    assert!(matches!(importer.check_poh(&forged, &f.genesis.header), Err(ImportError::BadPoh(_))));
+
+    // A transaction mixed into the stream that the body does not carry
+    let mut mixed = block.clone();
+    let mut poh = PohRecorder::new(&f.genesis.header);
+    poh.record(&[9u8; 32]);
+    poh.tick_to(1);
+    (mixed.header.poh_hash, mixed.header.poh_sequence) = poh.tail();
+    mixed.poh_entries = poh.entries().to_vec();
+    assert!(matches!(importer.check_poh(&mixed, &f.genesis.header), Err(ImportError::BadPoh(_))));
+
+    // Claiming a later slot without hashing through it is caught from the header alone
+    let mut skipped = block_on(&f.genesis.header, 3, &f.key, f.state.calculate_root());
+    skipped.header.poh_sequence = block.header.poh_sequence;
+    skipped.sign(&f.key);
+    let mut fees = FeeMarket::new();
+    let err = importer.import(&skipped, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::PohTooShort { .. }), "{}", err);
+}
+
+#[test]
+fn test_state_root_mismatch_leaves_state_untouched() {
+    let mut f = setup("state_root");
+    let mut block = child_block(&f, &f.key);
+    block.header.state_root = [3u8; 32];
+    block.sign(&f.key);
+    let root_before = f.state.calculate_root();
+
+    let mut fees = FeeMarket::new();
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(matches!(err, ImportError::StateRootMismatch { .. }), "{}", err);
+    assert_eq!(f.state.calculate_root(), root_before);
+    assert_eq!(f.storage.get_latest_state().unwrap().unwrap().0, 0);
+
+    // Out-of-order blocks are not the sender's fault
+    block.header.height = 5;
+    let err = BlockImporter::new(&f.storage, &f.consensus).import(&block, &mut f.state, &mut fees, now()).unwrap_err();
+    assert!(!err.is_invalid());
+}
+
+#[test]
+fn test_longer_branch_triggers_reorg_and_invalid_branch_is_abandoned() {
+    let mut f = setup("reorg");
+    let genesis_state = f.state.clone();
+    let genesis_hash = f.genesis.header.hash();
+    f.consensus.head_hash = genesis_hash;
+    let mut tree = BlockTree::new(0, genesis_hash);
+    let mut fees = FeeMarket::new();
+
+    let (a1, a1_state) = next_block(&f.genesis.header, 1, &f.key, &genesis_state);
+    assert!(accept_block(&mut tree, &f.storage, &f.consensus, &a1, now()).unwrap());
+    let update = update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(update.imported.len(), 1);
+    assert!(update.reorg.is_none());
+    assert_eq!(f.consensus.head_hash, a1.header.hash());
+
+    // A competing branch that becomes longer wins
+    let (b1, b1_state) = next_block(&f.genesis.header, 2, &f.key, &genesis_state);
+    let (b2, _) = next_block(&b1.header, 3, &f.key, &b1_state);
+    accept_block(&mut tree, &f.storage, &f.consensus, &b1, now()).unwrap();
+    accept_block(&mut tree, &f.storage, &f.consensus, &b2, now()).unwrap();
+    let update = update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    let reorg = update.reorg.expect("expected a reorg");
+    assert_eq!(reorg.depth, 1);
+    assert_eq!(reorg.common_ancestor_height, 0);
+    assert_eq!(reorg.removed, vec![a1.header.hash()]);
+    assert_eq!(reorg.added, vec![b1.header.hash(), b2.header.hash()]);
+    assert_eq!(update.orphaned.len(), 1);
+    assert_eq!(f.storage.get_block(1).unwrap().unwrap().header.hash(), b1.header.hash());
+    assert_eq!(f.storage.get_latest_state().unwrap().unwrap().0, 2);
+
+    // An even longer branch whose tip fails execution is dropped and the head stays valid
+    let (a2, _) = next_block(&a1.header, 4, &f.key, &a1_state);
+    let a3 = block_on(&a2.header, 5, &f.key, [9u8; 32]);
+    for b in [&a2, &a3] {
+        accept_block(&mut tree, &f.storage, &f.consensus, b, now()).unwrap();
+    }
+    let update = update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(update.rejected.len(), 1);
+    assert!(matches!(update.rejected[0].1, ImportError::StateRootMismatch { .. }));
+    assert!(tree.is_invalid(&a3.header.hash()));
+    assert_eq!(f.storage.get_latest_state().unwrap().unwrap().0, 2);
+    let head = f.storage.get_block(2).unwrap().unwrap().header.hash();
+    assert_eq!(head, f.consensus.head_hash);
+    assert!(head == a2.header.hash() || head == b2.header.hash());
+}
+
+#[test]
+fn test_engine_takes_the_new_leader_schedule_at_an_epoch_boundary() {
+    let mut f = setup("epoch_schedule");
+    // Stand-in for the last block of epoch 0
+    let mut last = block_on(&f.genesis.header, 1, &f.key, f.state.calculate_root());
+    last.header.height = BLOCKS_PER_EPOCH - 1;
+    last.sign(&f.key);
+    f.storage.commit_block(&last, &[], &f.state).unwrap();
+    f.consensus.head_hash = last.header.hash();
+    let mut tree = BlockTree::new(last.header.height, last.header.hash());
+    let mut fees = FeeMarket::new();
+
+    let (boundary, _) = next_block(&last.header, 2, &f.key, &f.state);
+    accept_block(&mut tree, &f.storage, &f.consensus, &boundary, now()).unwrap();
+    update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(f.state.staking.leader_schedule().epoch, 1);
+    assert_eq!(&f.consensus.leader_schedule, f.state.staking.leader_schedule());
+
+    // The first block of the new epoch is checked against the new schedule
+    let (next, _) = next_block(&boundary.header, 3, &f.key, &f.state);
+    assert!(accept_block(&mut tree, &f.storage, &f.consensus, &next, now()).unwrap());
+}
+
+#[test]
+fn test_finality_commit_overrides_fork_choice() {
+    let mut f = setup("finalize");
+    let genesis_state = f.state.clone();
+    let genesis_hash = f.genesis.header.hash();
+    f.consensus.head_hash = genesis_hash;
+    let mut tree = BlockTree::new(0, genesis_hash);
+    let mut fees = FeeMarket::new();
+
+    let (a1, _) = next_block(&f.genesis.header, 1, &f.key, &genesis_state);
+    let (b1, b1_state) = next_block(&f.genesis.header, 2, &f.key, &genesis_state);
+    let (b2, _) = next_block(&b1.header, 3, &f.key, &b1_state);
+    for b in [&a1, &b1, &b2] {
+        accept_block(&mut tree, &f.storage, &f.consensus, b, now()).unwrap();
+    }
+    update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(f.consensus.head_hash, b2.header.hash());
+
+    // Validators committed to a1: the shorter branch becomes canonical and final
+    let err = finalize_block(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, [5u8; 32], 1, now()).unwrap_err();
+    assert!(matches!(err, ImportError::UnknownBlock(_)) && !err.is_invalid());
+    let update = finalize_block(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, a1.header.hash(), 1, now()).unwrap();
+    assert_eq!(update.reorg.expect("expected a reorg").new_head, a1.header.hash());
+    assert_eq!(f.consensus.head_hash, a1.header.hash());
+    assert_eq!((f.consensus.finalized_height, f.consensus.finalized_hash), (1, a1.header.hash()));
+    assert_eq!(tree.root(), (1, a1.header.hash()));
+    assert_eq!(f.storage.get_latest_state().unwrap().unwrap().0, 1);
+
+    // The losing branch can no longer be imported
+    assert!(accept_block(&mut tree, &f.storage, &f.consensus, &b2, now()).is_err());
+}
+
+#[test]
+fn test_sync_downloads_batches_from_peer_and_executes_them() {
+    // Serving node with a few blocks on top of genesis
+    let mut server = setup("sync_server");
+    let mut fees = FeeMarket::new();
+    let mut parent = server.genesis.header.clone();
+    for slot in 1..=5 {
+        let (block, _) = next_block(&parent, slot, &server.key, &server.state);
+        BlockImporter::new(&server.storage, &server.consensus).import(&block, &mut server.state, &mut fees, now()).unwrap();
+        parent = block.header;
+    }
+    let (genesis_hash, head_height) = match serve_request(&server.storage, &SyncRequest::Status) {
+        SyncResponse::Status { genesis_hash, head_height, .. } => (genesis_hash, head_height),
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert_eq!(head_height, 5);
+
+    // Fresh node catches up
+    let mut f = setup("sync_client");
+    f.consensus.head_hash = f.genesis.header.hash();
+    let mut tree = BlockTree::new(0, f.genesis.header.hash());
+    let mut sync = SyncEngine::new(Arc::new(Storage::new(&temp_db("sync_engine"))));
+    assert!(!sync.on_status("other-chain", [1u8; 32], 9, genesis_hash, 0));
+    assert!(sync.on_status("peer-a", genesis_hash, head_height, f.genesis.header.hash(), 0));
+    assert!(sync.is_syncing());
+
+    let requests = sync.next_requests(0, std::time::Instant::now());
+    assert_eq!(requests.len(), 1);
+    let (peer, request) = &requests[0];
+    assert_eq!(peer, "peer-a");
+    match serve_request(&server.storage, request) {
+        SyncResponse::Blocks { start_height, blocks } => {
+            // A gap in the batch is refused and the range requested again
+            let mut gapped = blocks.clone();
+            gapped.remove(1);
+            assert!(sync.on_blocks("peer-a", start_height, gapped).is_err());
+            assert_eq!(sync.next_requests(0, std::time::Instant::now()).len(), 1);
+            sync.on_blocks("peer-a", start_height, blocks).unwrap();
+        }
+        other => panic!("unexpected response {:?}", other),
+    }
+
+    for block in sync.take_ready(0) {
+        accept_block(&mut tree, &f.storage, &f.consensus, &block, now()).unwrap();
+    }
+    update_head(&mut tree, &f.storage, &mut f.consensus, &mut f.state, &mut fees, now()).unwrap();
+    assert_eq!(f.storage.latest_height().unwrap(), Some(5));
+    assert_eq!(f.storage.get_block(5).unwrap().unwrap().header.hash(), parent.hash());
+    assert!(sync.check_complete(5));
+    assert!(sync.progress(5).is_none());
+}
+
+/// Genesis state with many accounts and a contract holding storage and code.
+fn rich_genesis_state() -> State {
+    let mut state = create_genesis_state();
+    let base = Address::from_pubkey(&SigningKey::from_slice(&[3u8; 32]).unwrap().verifying_key().to_sec1_bytes());
+    for i in 0..200u64 {
+        let addr = Address::derive_contract_address(&base, i);
+        let mut account = state.get_account(&addr);
+        account.balance = i as u128 + 1;
+        state.update_account(addr, account);
+    }
+    let contract = Address::derive_contract_address(&base, 10_000);
+    for i in 0..300u16 {
+        let mut key = [0u8; 32];
+        key[..2].copy_from_slice(&i.to_be_bytes());
+        state.set_storage(contract, key, [(i % 200) as u8 + 1; 32]);
+    }
+    let code = vec![0x60, 0x00, 0x60, 0x00, 0xf3];
+    let code_hash: [u8; 32] = <sha3::Keccak256 as sha3::Digest>::digest(&code).into();
+    state.put_code(code_hash, code);
+    let mut account = state.get_account(&contract);
+    account.is_contract = true;
+    account.code_hash = code_hash;
+    state.update_account(contract, account);
+    state
+}
+
+#[test]
+fn test_snap_sync_downloads_verified_state_at_pivot() {
+    let mut server = setup_with("snap_server", rich_genesis_state());
+    let mut fees = FeeMarket::new();
+    let mut parent = server.genesis.header.clone();
+    for slot in 1..=4 {
+        let (block, _) = next_block(&parent, slot, &server.key, &server.state);
+        BlockImporter::new(&server.storage, &server.consensus).import(&block, &mut server.state, &mut fees, now()).unwrap();
+        parent = block.header;
+    }
+
+    let client = setup_with("snap_client", rich_genesis_state());
+    let pivot = 3;
+    let peers = vec!["peer-a".to_string(), "peer-b".to_string()];
+
+    // Headers that do not end in the trusted checkpoint are refused
+    let forged = Checkpoint { height: pivot, hash: [1u8; 32] };
+    let mut snap = SnapSync::new(client.genesis.header.clone(), forged);
+    let mut refused = false;
+    for (peer, request) in snap.next_requests(&peers, std::time::Instant::now()) {
+        let response = serve_request(&server.storage, &request);
+        refused |= snap.on_response(&peer, response, &client.consensus, &client.storage, now()).is_err_and(|e| e.contains("checkpoint"));
+    }
+    assert!(refused);
+    assert_eq!(snap.phase, SnapPhase::Headers);
+
+    let checkpoint = Checkpoint { height: pivot, hash: server.storage.get_header(pivot).unwrap().unwrap().hash() };
+    let mut snap = SnapSync::new(client.genesis.header.clone(), checkpoint);
+    let mut tampered = false;
+    for _ in 0..200 {
+        if snap.is_done() {
+            break;
+        }
+        for (peer, request) in snap.next_requests(&peers, std::time::Instant::now()) {
+            let mut response = serve_request(&server.storage, &request);
+            // One forged answer: an account silently left out of a range
+            if let SyncResponse::AccountRange(range) = &mut response {
+                if !tampered && range.entries.len() > 2 {
+                    tampered = true;
+                    range.entries.remove(1);
+                    assert!(snap.on_response(&peer, response, &client.consensus, &client.storage, now()).is_err());
+                    continue;
+                }
+            }
+            snap.on_response(&peer, response, &client.consensus, &client.storage, now()).unwrap();
+        }
+    }
+    assert!(tampered);
+    assert!(snap.is_done());
+    assert_eq!(snap.accounts_synced(), server.state.accounts.len());
+
+    let archive = snap.finish(1).unwrap();
+    assert!(client.storage.install_snap_state(&archive, client.genesis.header.hash(), &forged).is_err());
+    let info = client.storage.install_snap_state(&archive, client.genesis.header.hash(), &checkpoint).unwrap();
+    assert_eq!(info.height, pivot);
+    let (height, state) = client.storage.get_latest_state().unwrap().unwrap();
+    assert_eq!(height, pivot);
+    assert_eq!(state.calculate_root(), server.storage.get_header(pivot).unwrap().unwrap().state_root);
+    // The contract's storage and the staking and governance accounts' committed roots
+    let pivot_state = server.storage.get_state(pivot).unwrap().unwrap();
+    assert_eq!(state.storage_tries.len(), 3);
+    assert_eq!(state.staking.root(), pivot_state.staking.root());
+    assert_eq!(state.governance.root(), pivot_state.governance.root());
+    assert_eq!(state.codes.len(), server.state.codes.len());
+}
@@ -46,6 +46,10 @@ fn test_full_transaction_flow() {
         gas_limit: 1000000,
         base_fee: 1,
         vrf_output: [0u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
     };
 
     let mut processor = BlockProcessor::new(&mut state, kortana_blockchain_rust::core::fees::FeeMarket::new());
@@ -0,0 +1,114 @@
+// tests/rewards_test.rs
+mod common;
+
+use common::{account, proposed_header, staking_tx};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::genesis::create_genesis_state;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{calculate_block_reward, DEFAULT_COMMISSION_BPS, MIN_VALIDATOR_STAKE};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+
+fn word(value: u128) -> Vec<u8> {
+    let mut word = vec![0u8; 16];
+    word.extend_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// A validator with a self-bond and one delegator of the same size, elected alone. Both
+/// hold 1M wei for fees.
+fn setup() -> (State, Address, Address) {
+    let mut state = State::new();
+    let (validator, delegator) = (account(b"validator"), account(b"delegator"));
+    for addr in [validator, delegator] {
+        let mut acc = state.get_account(&addr);
+        acc.balance = 1_000_000;
+        state.update_account(addr, acc);
+    }
+    let profile = ValidatorProfile::new("validator", DEFAULT_COMMISSION_BPS, 1000, 100);
+    state.staking.create_validator(validator, profile, None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.delegate(delegator, validator, MIN_VALIDATOR_STAKE, 0);
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
+    state.staking.total_supply = 2 * MIN_VALIDATOR_STAKE + 2_000_000;
+    (state, validator, delegator)
+}
+
+#[test]
+fn test_block_reward_splits_commission_and_stake() {
+    let (mut state, validator, delegator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&proposed_header(1, validator));
+
+    let reward = calculate_block_reward(1);
+    let commission = reward * DEFAULT_COMMISSION_BPS as u128 / 10000;
+    let share = (reward - commission) / 2;
+    let supply = 2 * MIN_VALIDATOR_STAKE + 2_000_000 + reward;
+    let staking = &processor.state.staking;
+    assert_eq!(staking.total_supply, supply);
+    assert_eq!(staking.pending_rewards(delegator, validator), share);
+    assert_eq!(staking.pending_rewards(validator, validator), share + commission);
+    assert_eq!(staking.call(&[&[0x10][..], &delegator.to_bytes(), &validator.to_bytes()].concat()).unwrap(), word(share));
+    assert_eq!(staking.call(&[&[0x11][..], &validator.to_bytes()].concat()).unwrap(), word(commission));
+
+    // Withdrawing credits the balance, minus the burned fee, and leaves nothing pending
+    let receipt = processor.process_transaction(staking_tx(delegator, 0, 0, [&[5u8][..], &validator.to_bytes()].concat()), &proposed_header(2, validator)).unwrap();
+    assert_eq!(receipt.status, 1);
+    let fee = receipt.gas_used as u128;
+    assert_eq!(processor.state.get_account(&delegator).balance, 1_000_000 - fee + share);
+    assert_eq!(processor.state.staking.pending_rewards(delegator, validator), 0);
+    assert_eq!(processor.state.staking.total_supply, supply - fee);
+
+    // The operator collects its share and the commission
+    processor.process_transaction(staking_tx(validator, 0, 0, [&[5u8][..], &validator.to_bytes()].concat()), &proposed_header(2, validator)).unwrap();
+    assert_eq!(processor.state.get_account(&validator).balance, 1_000_000 - 50_000 + share + commission);
+    assert_eq!(processor.state.staking.pending_rewards(validator, validator), 0);
+}
+
+#[test]
+fn test_late_delegators_and_slashes_only_affect_later_rewards() {
+    let (mut state, validator, delegator) = setup();
+    let late = account(b"late");
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&proposed_header(1, validator));
+    let first = processor.state.staking.pending_rewards(delegator, validator);
+
+    // Joins with as much as each of the others: a third of later rewards, none of earlier ones
+    processor.state.staking.delegate(late, validator, MIN_VALIDATOR_STAKE, 1);
+    assert_eq!(processor.state.staking.pending_rewards(late, validator), 0);
+    processor.end_block(&proposed_header(2, validator));
+    let reward = calculate_block_reward(2);
+    let third = (reward - reward * DEFAULT_COMMISSION_BPS as u128 / 10000) / 3;
+    assert_eq!(processor.state.staking.pending_rewards(late, validator), third);
+    assert_eq!(processor.state.staking.pending_rewards(delegator, validator), first + third);
+
+    // After a 10% slash every delegator earns on 90% of its stake; earlier rewards stay
+    let supply = processor.state.staking.total_supply;
+    let burned = processor.state.staking.slash(validator, 1000, [9u8; 32], 3);
+    assert_eq!(processor.state.staking.total_supply, supply - burned);
+    processor.end_block(&proposed_header(3, validator));
+    let after = processor.state.staking.pending_rewards(late, validator);
+    assert!(after.abs_diff(2 * third) <= 1, "{} vs {}", after, 2 * third);
+    assert_eq!(processor.state.staking.pending_rewards(delegator, validator), first + after);
+
+    // Undelegating settles what was earned; the delegation keeps no stake but can still withdraw
+    processor.state.staking.undelegate(late, validator, MIN_VALIDATOR_STAKE * 9 / 10, 3, &processor.state.params).unwrap();
+    assert_eq!(processor.state.staking.withdraw_rewards(late, validator).unwrap(), after);
+    assert!(processor.state.staking.withdraw_rewards(late, validator).is_err());
+}
+
+#[test]
+fn test_genesis_supply_counts_balances_and_stake() {
+    let mut state = create_genesis_state();
+    let balances: u128 = state.accounts.values().map(|a| a.balance).sum();
+    let genesis_supply = state.staking.total_supply;
+    assert_eq!(genesis_supply, balances + 3 * MIN_VALIDATOR_STAKE);
+
+    // Blocks from outside the active set mint nothing
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&proposed_header(1, account(b"stranger")));
+    assert_eq!(processor.state.staking.total_supply, genesis_supply);
+    let proposer = processor.state.staking.validator_set()[0].address;
+    processor.end_block(&proposed_header(2, proposer));
+    assert_eq!(processor.state.staking.total_supply, genesis_supply + calculate_block_reward(2));
+}
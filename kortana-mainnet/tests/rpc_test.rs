@@ -61,3 +61,37 @@ async fn test_rpc_formatting() {
     assert!(result["to"].as_str().unwrap().starts_with("0x"));
     assert!(result["value"].as_str().unwrap().starts_with("0x"));
 }
+
+#[tokio::test]
+async fn test_faucet_submits_a_signed_transfer_off_mainnet() {
+    let spec = kortana_blockchain_rust::chain_spec::ChainSpec::testnet();
+    let state = Arc::new(Mutex::new(kortana_blockchain_rust::core::genesis::create_genesis_state_for(&spec).unwrap()));
+    let mempool = Arc::new(Mutex::new(Mempool::new(1000)));
+    let path = std::env::temp_dir().join(format!("kortana_rpc_faucet_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let storage = Arc::new(Storage::new(&path.to_string_lossy()));
+    let consensus = Arc::new(Mutex::new(ConsensusEngine::new(vec![])));
+    let (tx_chan, _rx) = mpsc::channel(10);
+    let request = |id: u64| JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        method: "eth_requestDNR".to_string(),
+        params: Some(serde_json::json!(["0x1111111111111111111111111111111111111111", "25"])),
+        id: serde_json::json!(id),
+    };
+
+    let handler = RpcHandler::new(state.clone(), mempool.clone(), storage.clone(), consensus.clone(), tx_chan.clone(), Arc::new(AtomicU64::new(0)), spec.chain_id);
+    for id in [1, 2] {
+        assert!(handler.handle(request(id)).await.result.unwrap().as_str().unwrap().starts_with("0x"));
+    }
+    // Transfers from the testnet faucet account, in nonce order, that leave balances to consensus
+    let faucet = Address::from_hex(&spec.validators[0].address).unwrap();
+    let pending = mempool.lock().unwrap().get_all();
+    let mut nonces: Vec<u64> = pending.iter().map(|tx| tx.nonce).collect();
+    nonces.sort();
+    assert_eq!(nonces, vec![0, 1]);
+    assert!(pending.iter().all(|tx| tx.from == faucet && tx.value == 25 * 10u128.pow(18) && tx.signature.is_some() && tx.chain_id == spec.chain_id));
+    assert_eq!(state.lock().unwrap().get_account(&Address::from_hex("0x1111111111111111111111111111111111111111").unwrap()).balance, 0);
+
+    let mainnet = RpcHandler::new(state, mempool, storage, consensus, tx_chan, Arc::new(AtomicU64::new(0)), kortana_blockchain_rust::parameters::CHAIN_ID);
+    assert!(mainnet.handle(request(3)).await.error.is_some());
+}
@@ -0,0 +1,234 @@
+// tests/slashing_test.rs
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::consensus::bft::{FinalityCommit, Vote, VoteKind};
+use kortana_blockchain_rust::consensus::evidence::{Evidence, EvidencePool};
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::crypto::bls::BlsSecretKey;
+use kortana_blockchain_rust::parameters::{CHAIN_ID, DEFAULT_COMMISSION_BPS, GAS_LIMIT_PER_BLOCK, JAIL_DURATION_SLOTS, LIVENESS_WINDOW_BLOCKS, MAX_EVIDENCE_AGE_BLOCKS, MIN_VALIDATOR_STAKE};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+use kortana_blockchain_rust::types::block::{Block, BlockHeader};
+use k256::ecdsa::SigningKey;
+
+const OFFENDER_KEY: [u8; 32] = [5u8; 32];
+
+fn address_of(key: &[u8]) -> Address {
+    Address::from_pubkey(&SigningKey::from_slice(key).unwrap().verifying_key().to_sec1_bytes())
+}
+
+/// State where the offender has a self-bond of 1000 and a delegation of 500, and a BLS
+/// consensus key registered.
+fn bonded_state() -> State {
+    let mut state = State::new();
+    let offender = address_of(&OFFENDER_KEY);
+    state.staking.delegate(offender, offender, 1000, 0);
+    state.staking.delegate(address_of(&[6u8; 32]), offender, 500, 0);
+    let bls = BlsSecretKey::from_seed(&OFFENDER_KEY);
+    state.staking.register_consensus_key(offender, bls.public_key(), &bls.proof_of_possession()).unwrap();
+    state
+}
+
+fn header(height: u64, slot: u64, proposer: Address, parent_hash: [u8; 32]) -> BlockHeader {
+    BlockHeader {
+        version: 1,
+        height,
+        slot,
+        timestamp: 1_700_000_000,
+        parent_hash,
+        state_root: [0u8; 32],
+        transactions_root: [0u8; 32],
+        receipts_root: [0u8; 32],
+        poh_hash: [0u8; 32],
+        poh_sequence: 0,
+        proposer,
+        gas_used: 0,
+        gas_limit: GAS_LIMIT_PER_BLOCK,
+        base_fee: FeeMarket::new().base_fee,
+        vrf_output: [1u8; 32],
+        vrf_proof: Vec::new(),
+        logs_bloom: Default::default(),
+        evidence_root: [0u8; 32],
+        last_commit_hash: [0u8; 32],
+    }
+}
+
+fn signed_block(key: &[u8], slot: u64, parent_hash: [u8; 32]) -> Block {
+    let mut block = Block::new(header(5, slot, address_of(&OFFENDER_KEY), parent_hash), Vec::new());
+    block.sign(key);
+    block
+}
+
+#[test]
+fn test_double_proposal_is_detected_and_slashed_once() {
+    let mut state = bonded_state();
+    let offender = address_of(&OFFENDER_KEY);
+    let a = signed_block(&OFFENDER_KEY, 9, [1u8; 32]);
+    let b = signed_block(&OFFENDER_KEY, 9, [2u8; 32]);
+
+    let mut pool = EvidencePool::new();
+    assert!(pool.observe_block(&a).is_none());
+    assert!(pool.observe_block(&a).is_none());
+    assert!(pool.observe_block(&signed_block(&OFFENDER_KEY, 10, [2u8; 32])).is_none());
+    let evidence = pool.observe_block(&b).expect("two blocks for slot 9");
+    assert_eq!(evidence.offender(), offender);
+    assert!(pool.add(evidence.clone(), &state.staking, &state.params, CHAIN_ID, 6).unwrap());
+    assert!(!pool.add(evidence.clone(), &state.staking, &state.params, CHAIN_ID, 6).unwrap());
+
+    // A header not signed by its proposer proves nothing
+    let forged = Evidence::DoubleProposal {
+        first: Box::new((a.header.clone(), a.signature.clone())),
+        second: Box::new((b.header.clone(), signed_block(&[8u8; 32], 9, [2u8; 32]).signature)),
+    };
+    assert!(forged.verify(&state.staking, &state.params, CHAIN_ID, 6).is_err());
+
+    // 10% of the self-bond and of the delegation is burned, once
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let at = header(6, 11, Address::ZERO, [0u8; 32]);
+    processor.apply_evidence(std::slice::from_ref(&evidence), &at).unwrap();
+    assert_eq!(processor.state.staking.bonded_stake(&offender), 900 + 450);
+    assert!(processor.apply_evidence(std::slice::from_ref(&evidence), &at).is_err());
+    assert!(pool.select(&processor.state.staking, &processor.state.params, CHAIN_ID, 7).is_empty());
+}
+
+#[test]
+fn test_duplicate_votes_are_detected_and_slashed() {
+    let mut state = bonded_state();
+    let offender = address_of(&OFFENDER_KEY);
+    let bls = BlsSecretKey::from_seed(&OFFENDER_KEY);
+    let vote_x = Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 5, 0, Some([0xaa; 32]), offender, &bls);
+    let vote_y = Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 5, 0, Some([0xbb; 32]), offender, &bls);
+
+    let mut pool = EvidencePool::new();
+    assert!(pool.observe_vote(&vote_x).is_none());
+    // A precommit or a vote in another round does not conflict with the prevote
+    assert!(pool.observe_vote(&Vote::new_signed(CHAIN_ID, VoteKind::Precommit, 5, 0, Some([0xbb; 32]), offender, &bls)).is_none());
+    assert!(pool.observe_vote(&Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 5, 1, Some([0xbb; 32]), offender, &bls)).is_none());
+    let evidence = pool.observe_vote(&vote_y).expect("two prevotes in round 0");
+    evidence.verify(&state.staking, &state.params, CHAIN_ID, 6).unwrap();
+
+    // Too old to punish, not conflicting, or not signed with the registered key
+    assert!(evidence.verify(&state.staking, &state.params, CHAIN_ID, 6 + MAX_EVIDENCE_AGE_BLOCKS).is_err());
+    let same = Evidence::DuplicateVote { first: vote_x.clone(), second: vote_x.clone() };
+    assert!(same.verify(&state.staking, &state.params, CHAIN_ID, 6).is_err());
+    let other_key = BlsSecretKey::from_seed(&[9u8; 32]);
+    let foreign = Vote::new_signed(CHAIN_ID, VoteKind::Prevote, 5, 0, Some([0xbb; 32]), offender, &other_key);
+    let forged = Evidence::DuplicateVote { first: vote_x, second: foreign };
+    assert!(forged.verify(&state.staking, &state.params, CHAIN_ID, 6).is_err());
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.apply_evidence(&[evidence], &header(6, 11, Address::ZERO, [0u8; 32])).unwrap();
+    assert_eq!(processor.state.staking.bonded_stake(&offender), 670 + 335);
+}
+
+#[test]
+fn test_block_commits_to_its_evidence() {
+    let mut state = bonded_state();
+    let a = signed_block(&OFFENDER_KEY, 9, [1u8; 32]);
+    let b = signed_block(&OFFENDER_KEY, 9, [2u8; 32]);
+    let evidence = Evidence::DoubleProposal {
+        first: Box::new((a.header.clone(), a.signature.clone())),
+        second: Box::new((b.header.clone(), b.signature.clone())),
+    };
+
+    let mut block = Block::new(header(6, 11, Address::ZERO, [0u8; 32]), Vec::new());
+    block.evidence = vec![evidence.clone()];
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    assert_eq!(processor.validate_block(&block).unwrap_err(), "Invalid evidence root");
+
+    block.header.evidence_root = Block::calculate_evidence_root(&block.evidence);
+    processor.validate_block(&block).unwrap();
+    assert!(processor.state.staking.is_slashed(&evidence.offence_id()));
+}
+
+/// State with validators for keys 11..=13 elected for epoch 0, each with a registered BLS
+/// key. 11 and 12 self-bond twice the minimum stake and 13 the minimum, so 11 and 12 carry
+/// a commit on their own.
+fn validator_state() -> State {
+    let mut state = State::new();
+    for seed in 11u8..=13 {
+        let addr = address_of(&[seed; 32]);
+        let stake = if seed == 13 { MIN_VALIDATOR_STAKE } else { 2 * MIN_VALIDATOR_STAKE };
+        let bls = BlsSecretKey::from_seed(&[seed; 32]);
+        let profile = ValidatorProfile::new("", DEFAULT_COMMISSION_BPS, 1000, 100);
+        state.staking.create_validator(addr, profile, Some((bls.public_key(), &bls.proof_of_possession())), stake, 0, &state.params).unwrap();
+    }
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
+    state
+}
+
+/// Commit for `height` signed by validators 11 and 12; 13 is offline.
+fn commit_without_13(state: &State, height: u64) -> FinalityCommit {
+    let votes: Vec<Vote> = (11u8..=12)
+        .map(|seed| Vote::new_signed(CHAIN_ID, VoteKind::Precommit, height, 0, Some([0xcc; 32]), address_of(&[seed; 32]), &BlsSecretKey::from_seed(&[seed; 32])))
+        .collect();
+    FinalityCommit::aggregate([0xcc; 32], height, 0, &votes, state.staking.validator_set()).unwrap()
+}
+
+#[test]
+fn test_offline_validator_is_jailed_for_downtime_and_can_unjail() {
+    let mut state = validator_state();
+    let offline = address_of(&[13u8; 32]);
+    let voters: Vec<Address> = state.staking.validator_set().iter().map(|v| v.address).collect();
+    let online: Vec<Address> = voters.iter().copied().filter(|a| *a != offline).collect();
+
+    // Misses spread over more than the window are forgotten as they fall out of it
+    for height in 1..=LIVENESS_WINDOW_BLOCKS {
+        let signers = if height % 4 == 0 { &online } else { &voters };
+        assert!(state.staking.record_commit(height, &voters, signers, height, &state.params).is_empty());
+    }
+    assert_eq!(state.staking.liveness[&offline].missed, LIVENESS_WINDOW_BLOCKS / 4);
+
+    let mut height = LIVENESS_WINDOW_BLOCKS;
+    // The next misses reach MAX_MISSED_BLOCKS_BEFORE_JAIL within the window
+    let jailed = loop {
+        height += 1;
+        let jailed = state.staking.record_commit(height, &voters, &online, height, &state.params);
+        if !jailed.is_empty() {
+            break jailed;
+        }
+    };
+    assert_eq!(jailed, vec![offline]);
+    assert_eq!(state.staking.self_bond(&offline), MIN_VALIDATOR_STAKE - MIN_VALIDATOR_STAKE / 100);
+    assert!(state.staking.is_jailed(&offline));
+    assert!(!state.staking.validator_set().iter().any(|v| v.address == offline && v.is_active));
+
+    // Out of the set until the sentence is served, and only with a qualifying self-bond
+    assert!(state.staking.unjail(offline, height + JAIL_DURATION_SLOTS - 1, &state.params).is_err());
+    assert!(state.staking.unjail(offline, height + JAIL_DURATION_SLOTS, &state.params).is_err());
+    state.staking.delegate(offline, offline, MIN_VALIDATOR_STAKE / 100, height);
+    state.staking.unjail(offline, height + JAIL_DURATION_SLOTS, &state.params).unwrap();
+    assert!(!state.staking.validator_set().iter().any(|v| v.address == offline && v.is_active));
+    state.staking.elect_validators(1, &[0u8; 32], &state.params);
+    assert!(state.staking.validator_set().iter().all(|v| v.is_active && v.missed_blocks == 0));
+    assert!(state.staking.unjail(offline, height + JAIL_DURATION_SLOTS, &state.params).is_err());
+}
+
+#[test]
+fn test_block_records_the_participation_in_its_last_commit() {
+    let mut state = validator_state();
+    let offline = address_of(&[13u8; 32]);
+    let commit = commit_without_13(&state, 4);
+
+    let mut block = Block::new(header(6, 11, Address::ZERO, [0u8; 32]), Vec::new());
+    block.last_commit = Some(commit.clone());
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    assert_eq!(processor.validate_block(&block).unwrap_err(), "Invalid last commit hash");
+
+    block.header.last_commit_hash = Block::calculate_last_commit_hash(block.last_commit.as_ref());
+    processor.validate_block(&block).unwrap();
+    assert_eq!(processor.state.staking.last_commit_height, 4);
+    assert_eq!(processor.state.staking.liveness[&offline].missed, 1);
+    assert_eq!(processor.state.staking.liveness[&address_of(&[11u8; 32])].missed, 0);
+
+    // A commit is recorded once, and only for a height below the block carrying it
+    let next = header(7, 12, Address::ZERO, [0u8; 32]);
+    assert!(processor.apply_last_commit(Some(&commit), &next).is_err());
+    assert!(processor.apply_last_commit(Some(&commit_without_13(processor.state, 7)), &next).is_err());
+    processor.apply_last_commit(Some(&commit_without_13(processor.state, 6)), &next).unwrap();
+
+    // Too little stake behind it
+    let mut thin = commit_without_13(processor.state, 8);
+    thin.signers = vec![0b0001];
+    assert!(processor.apply_last_commit(Some(&thin), &header(9, 14, Address::ZERO, [0u8; 32])).is_err());
+}
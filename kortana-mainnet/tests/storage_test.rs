@@ -0,0 +1,353 @@
+// tests/storage_test.rs
+use kortana_blockchain_rust::core::genesis::{create_genesis_block, create_genesis_state};
+use kortana_blockchain_rust::storage::Storage;
+use kortana_blockchain_rust::storage::snapshot::Checkpoint;
+
+fn temp_db(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("kortana_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_commit_block_is_readable() {
+    let storage = Storage::new(&temp_db("commit"));
+    let state = create_genesis_state();
+    let genesis = create_genesis_block(state.calculate_root());
+
+    storage.commit_block(&genesis, &[], &state).unwrap();
+
+    let (height, loaded) = storage.get_latest_state().unwrap().unwrap();
+    assert_eq!(height, 0);
+    assert_eq!(loaded.calculate_root(), genesis.header.state_root);
+    assert_eq!(storage.get_state_root(0).unwrap(), Some(genesis.header.state_root));
+    let by_hash = storage.get_block_by_hash(&hex::encode(genesis.header.hash())).unwrap().unwrap();
+    assert_eq!(by_hash.header.height, 0);
+}
+
+#[test]
+fn test_commit_block_rejects_mismatched_state() {
+    let storage = Storage::new(&temp_db("mismatch"));
+    let state = create_genesis_state();
+    let genesis = create_genesis_block([7u8; 32]);
+
+    assert!(storage.commit_block(&genesis, &[], &state).is_err());
+    assert!(storage.get_block(0).unwrap().is_none());
+}
+
+#[test]
+fn test_integrity_check_rolls_back_partial_commit() {
+    let storage = Storage::new(&temp_db("integrity"));
+    let state = create_genesis_state();
+    let genesis = create_genesis_block(state.calculate_root());
+    storage.commit_block(&genesis, &[], &state).unwrap();
+
+    // Simulate a crash after the block was written but before its state was
+    let mut orphan = create_genesis_block(state.calculate_root());
+    orphan.header.height = 1;
+    orphan.header.parent_hash = genesis.header.hash();
+    storage.put_block(&orphan).unwrap();
+
+    let report = storage.check_integrity().unwrap();
+    assert_eq!(report.head, Some(0));
+    assert_eq!(report.rolled_back, vec![1]);
+    assert!(storage.get_block(1).unwrap().is_none());
+    assert_eq!(storage.get_latest_state().unwrap().unwrap().0, 0);
+}
+
+#[test]
+fn test_address_history_pages_with_cursor() {
+    use kortana_blockchain_rust::address::Address;
+    use kortana_blockchain_rust::types::transaction::{Transaction, TransactionReceipt, VmType};
+
+    let storage = Storage::new(&temp_db("pages"));
+    let state = create_genesis_state();
+    let mut block = create_genesis_block(state.calculate_root());
+    let alice = Address::from_pubkey(b"alice");
+    for nonce in 0..5 {
+        block.transactions.push(Transaction {
+            nonce,
+            from: alice,
+            to: Address::from_pubkey(b"bob"),
+            value: 1,
+            gas_limit: 21000,
+            gas_price: 1,
+            data: vec![],
+            vm_type: VmType::EVM,
+            chain_id: kortana_blockchain_rust::parameters::CHAIN_ID,
+            signature: None,
+            cached_hash: None,
+        });
+    }
+    let receipts: Vec<TransactionReceipt> = block.transactions.iter().map(|tx| TransactionReceipt {
+        tx_hash: tx.hash(),
+        status: 1,
+        gas_used: 21000,
+        logs: vec![],
+        contract_address: None,
+        logs_bloom: Default::default(),
+    }).collect();
+    storage.commit_block(&block, &receipts, &state).unwrap();
+
+    let (first, cursor) = storage.get_address_transactions(&alice, None, 3).unwrap();
+    assert_eq!(first, vec![block.transactions[4].hash(), block.transactions[3].hash(), block.transactions[2].hash()]);
+    let (second, end) = storage.get_address_transactions(&alice, cursor, 3).unwrap();
+    assert_eq!(second, vec![block.transactions[1].hash(), block.transactions[0].hash()]);
+    assert!(end.is_none());
+
+    assert_eq!(storage.transaction_count().unwrap(), 5);
+    let (recent, next) = storage.get_recent_transactions(None, 2).unwrap();
+    assert_eq!(recent[0], block.transactions[4].hash());
+    assert_eq!(next, Some(3));
+}
+
+#[test]
+fn test_log_index_finds_emitting_blocks() {
+    use kortana_blockchain_rust::address::Address;
+    use kortana_blockchain_rust::types::bloom::Bloom;
+    use kortana_blockchain_rust::types::transaction::{Transaction, TransactionLog, TransactionReceipt, VmType};
+
+    let storage = Storage::new(&temp_db("logs"));
+    let state = create_genesis_state();
+    let mut block = create_genesis_block(state.calculate_root());
+    let token = Address::from_pubkey(b"token");
+    block.transactions.push(Transaction {
+        nonce: 0,
+        from: Address::from_pubkey(b"alice"),
+        to: token,
+        value: 0,
+        gas_limit: 50000,
+        gas_price: 1,
+        data: vec![],
+        vm_type: VmType::EVM,
+        chain_id: kortana_blockchain_rust::parameters::CHAIN_ID,
+        signature: None,
+        cached_hash: None,
+    });
+    let logs = vec![TransactionLog { address: token, topics: vec![[0xddu8; 32]], data: vec![] }];
+    let receipt = TransactionReceipt {
+        tx_hash: block.transactions[0].hash(),
+        status: 1,
+        gas_used: 30000,
+        logs_bloom: Bloom::from_logs(&logs),
+        logs,
+        contract_address: None,
+    };
+    storage.commit_block(&block, &[receipt], &state).unwrap();
+
+    let evm = token.as_evm_address();
+    assert!(storage.find_log_heights(&[evm], Some(&[[0xddu8; 32]]), 0, 10).unwrap().contains(&0));
+    assert!(storage.find_log_heights(&[evm], Some(&[[0x01u8; 32]]), 0, 10).unwrap().is_empty());
+    assert!(storage.find_log_heights(&[evm], None, 1, 10).unwrap().is_empty());
+    assert!(storage.get_block_bloom(0).unwrap().unwrap().contains_input(&[0xddu8; 32]));
+}
+
+#[test]
+fn test_reindex_rebuilds_dropped_indexes() {
+    use kortana_blockchain_rust::address::Address;
+    use kortana_blockchain_rust::types::bloom::Bloom;
+    use kortana_blockchain_rust::types::transaction::{Transaction, TransactionLog, TransactionReceipt, VmType};
+
+    let path = temp_db("reindex");
+    let storage = Storage::new(&path);
+    let state = create_genesis_state();
+    let genesis = create_genesis_block(state.calculate_root());
+    storage.commit_block(&genesis, &[], &state).unwrap();
+
+    let (alice, bob, token) = (Address::from_pubkey(b"alice"), Address::from_pubkey(b"bob"), Address::from_pubkey(b"token"));
+    let mut parent = genesis.header.hash();
+    for height in 1..=3u64 {
+        let mut block = create_genesis_block(state.calculate_root());
+        block.header.height = height;
+        block.header.parent_hash = parent;
+        let mut receipts = Vec::new();
+        for (nonce, to) in [(2 * height, bob), (2 * height + 1, token)] {
+            let tx = Transaction {
+                nonce, from: alice, to, value: 1, gas_limit: 50000, gas_price: 1, data: vec![], vm_type: VmType::EVM,
+                chain_id: kortana_blockchain_rust::parameters::CHAIN_ID, signature: None, cached_hash: None,
+            };
+            let logs = if to == token { vec![TransactionLog { address: token, topics: vec![[height as u8; 32]], data: vec![] }] } else { vec![] };
+            receipts.push(TransactionReceipt { tx_hash: tx.hash(), status: 1, gas_used: 21000, logs_bloom: Bloom::from_logs(&logs), logs, contract_address: None });
+            block.transactions.push(tx);
+        }
+        storage.commit_block(&block, &receipts, &state).unwrap();
+        parent = block.header.hash();
+    }
+
+    let evm = token.as_evm_address();
+    let lookups = |storage: &Storage| (
+        storage.get_address_transactions(&alice, None, 100).unwrap(),
+        storage.get_address_transactions(&bob, None, 2).unwrap(),
+        storage.get_recent_transactions(None, 100).unwrap(),
+        storage.find_log_heights(&[evm], None, 0, 10).unwrap(),
+        storage.find_log_heights(&[evm], Some(&[[2u8; 32]]), 0, 10).unwrap(),
+        storage.get_block_bloom(3).unwrap(),
+    );
+    let before = lookups(&storage);
+    assert_eq!(before.0.0.len(), 6);
+    assert_eq!(before.3.len(), 3);
+    drop(storage);
+
+    {
+        let db = sled::open(&path).unwrap();
+        for tree in ["address_index", "tx_sequence", "block_blooms", "log_index"] {
+            assert!(db.drop_tree(tree).unwrap());
+        }
+        db.flush().unwrap();
+    }
+    let storage = Storage::new(&path);
+    assert!(storage.get_address_transactions(&alice, None, 100).unwrap().0.is_empty());
+    assert!(storage.find_log_heights(&[evm], None, 0, 10).unwrap().is_empty());
+
+    let report = storage.reindex().unwrap();
+    assert_eq!((report.blocks, report.transactions), (4, 6));
+    assert_eq!(lookups(&storage), before);
+}
+
+#[test]
+fn test_snapshot_roundtrip_and_tamper_detection() {
+    let source = Storage::new(&temp_db("snap_src"));
+    let state = create_genesis_state();
+    let genesis = create_genesis_block(state.calculate_root());
+    source.commit_block(&genesis, &[], &state).unwrap();
+
+    let mut next = create_genesis_block(state.calculate_root());
+    next.header.height = 1;
+    next.header.parent_hash = genesis.header.hash();
+    source.commit_block(&next, &[], &state).unwrap();
+
+    let path = temp_db("snap_file");
+    let chain_id = kortana_blockchain_rust::parameters::CHAIN_ID;
+    let info = source.export_snapshot(1, chain_id, &path).unwrap();
+    assert_eq!(info.block_hash, next.header.hash());
+
+    let checkpoint = info.checkpoint();
+    assert_eq!(checkpoint.to_string().parse::<Checkpoint>().unwrap(), checkpoint);
+    let target = Storage::new(&temp_db("snap_dst"));
+    // Without the trusted head hash the archive only vouches for itself
+    let forged = Checkpoint { height: 1, hash: [9u8; 32] };
+    assert!(target.import_snapshot(&path, chain_id, genesis.header.hash(), &forged).unwrap_err().contains("checkpoint"));
+    assert!(target.import_snapshot(&path, chain_id, genesis.header.hash(), &Checkpoint { height: 0, ..checkpoint }).is_err());
+    target.import_snapshot(&path, chain_id, genesis.header.hash(), &checkpoint).unwrap();
+    let (height, loaded) = target.get_latest_state().unwrap().unwrap();
+    assert_eq!(height, 1);
+    assert_eq!(loaded.calculate_root(), next.header.state_root);
+    assert_eq!(target.get_header(0).unwrap().unwrap().hash(), genesis.header.hash());
+    assert!(target.import_snapshot(&path, chain_id, genesis.header.hash(), &checkpoint).is_err());
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let mid = bytes.len() / 2;
+    bytes[mid] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    let fresh = Storage::new(&temp_db("snap_bad"));
+    assert!(fresh.import_snapshot(&path, chain_id, genesis.header.hash(), &checkpoint).unwrap_err().contains("checksum"));
+}
+
+/// Header hash as the JSON-layout node computed it, before the header grew VRF proofs,
+/// blooms, evidence roots and commit hashes.
+fn baseline_header_hash(h: &kortana_blockchain_rust::types::block::BlockHeader) -> [u8; 32] {
+    use sha3::{Digest, Keccak256};
+    let mut hasher = Keccak256::new();
+    hasher.update(h.version.to_be_bytes());
+    hasher.update(h.height.to_be_bytes());
+    hasher.update(h.slot.to_be_bytes());
+    hasher.update(h.timestamp.to_be_bytes());
+    hasher.update(h.parent_hash);
+    hasher.update(h.state_root);
+    hasher.update(h.transactions_root);
+    hasher.update(h.receipts_root);
+    hasher.update(h.poh_hash);
+    hasher.update(h.poh_sequence.to_be_bytes());
+    hasher.update(h.proposer.to_bytes());
+    hasher.update(h.gas_used.to_be_bytes());
+    hasher.update(h.gas_limit.to_be_bytes());
+    hasher.update(h.base_fee.to_be_bytes());
+    hasher.update(h.vrf_output);
+    hasher.finalize().into()
+}
+
+#[test]
+fn test_migrate_legacy_json_database() {
+    use kortana_blockchain_rust::address::Address;
+    use kortana_blockchain_rust::storage::codec::SCHEMA_VERSION;
+    use kortana_blockchain_rust::types::block::Block;
+    use kortana_blockchain_rust::types::bloom::Bloom;
+    use kortana_blockchain_rust::types::transaction::{Transaction, TransactionLog, TransactionReceipt, VmType};
+
+    // Blocks as the JSON layout serialized them, without the fields added since
+    let legacy_json = |block: &Block| {
+        let mut value = serde_json::to_value(block).unwrap();
+        for field in ["evidence", "last_commit", "poh_entries"] {
+            value.as_object_mut().unwrap().remove(field);
+        }
+        for field in ["vrf_proof", "logs_bloom", "evidence_root", "last_commit_hash"] {
+            value["header"].as_object_mut().unwrap().remove(field);
+        }
+        serde_json::to_vec(&value).unwrap()
+    };
+
+    let state = create_genesis_state();
+    let genesis = create_genesis_block(state.calculate_root());
+    let alice = Address::from_pubkey(b"alice");
+    let token = Address::from_pubkey(b"token");
+    let tx = Transaction {
+        nonce: 0, from: alice, to: token, value: 5, gas_limit: 50000, gas_price: 1, data: vec![], vm_type: VmType::EVM,
+        chain_id: kortana_blockchain_rust::parameters::CHAIN_ID, signature: None, cached_hash: None,
+    };
+    let mut block = create_genesis_block(state.calculate_root());
+    block.header.height = 1;
+    block.header.slot = 1;
+    block.header.vrf_output = [9u8; 32];
+    block.header.parent_hash = baseline_header_hash(&genesis.header);
+    block.transactions.push(tx.clone());
+    let block_hash = baseline_header_hash(&block.header);
+    let logs = vec![TransactionLog { address: token, topics: vec![[0xddu8; 32]], data: vec![] }];
+    let receipt = TransactionReceipt { tx_hash: tx.hash(), status: 1, gas_used: 30000, logs, contract_address: None, logs_bloom: Bloom::default() };
+
+    let path = temp_db("migrate");
+    {
+        let db = sled::open(&path).unwrap();
+        for b in [&genesis, &block] {
+            db.insert(format!("block:{}", b.header.height), legacy_json(b)).unwrap();
+            db.insert(format!("blockhash:{}", hex::encode(baseline_header_hash(&b.header))), legacy_json(b)).unwrap();
+            db.insert(format!("stateroot:{}", b.header.height), &b.header.state_root).unwrap();
+        }
+        let tx_hash = hex::encode(tx.hash());
+        db.insert(format!("tx:{}", tx_hash), serde_json::to_vec(&tx).unwrap()).unwrap();
+        let location = serde_json::json!({"block_height": 1, "block_hash": hex::encode(block_hash), "tx_index": 0});
+        db.insert(format!("txloc:{}", tx_hash), serde_json::to_vec(&location).unwrap()).unwrap();
+        let mut receipt_json = serde_json::to_value(&receipt).unwrap();
+        receipt_json.as_object_mut().unwrap().remove("logs_bloom");
+        db.insert(format!("receipt:{}", tx_hash), serde_json::to_vec(&receipt_json).unwrap()).unwrap();
+        db.insert(format!("addr_txs:{}", alice.to_hex()), serde_json::to_vec(&vec![tx.hash()]).unwrap()).unwrap();
+        db.flush().unwrap();
+    }
+
+    let storage = Storage::new(&path);
+    assert!(storage.has_legacy_data());
+    assert_eq!(storage.schema_version().unwrap(), None);
+    let report = storage.migrate_legacy_json().unwrap();
+    assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+    assert_eq!((report.blocks, report.transactions, report.locations, report.receipts, report.state_roots), (2, 1, 1, 1, 2));
+    assert_eq!(report.reindexed_transactions, 1);
+    assert!(!storage.has_legacy_data());
+    assert_eq!(storage.schema_version().unwrap(), Some(SCHEMA_VERSION));
+
+    // Blocks keep the hashes their children and transaction locations point at
+    let migrated_genesis = storage.get_block(0).unwrap().unwrap();
+    let migrated = storage.get_block(1).unwrap().unwrap();
+    assert_eq!(migrated.header.hash(), block_hash);
+    assert_eq!(migrated.header.parent_hash, migrated_genesis.header.hash());
+    assert_eq!(storage.get_block_by_hash(&hex::encode(block_hash)).unwrap().unwrap().header.height, 1);
+    let (height, location_hash, index) = storage.get_transaction_location(&hex::encode(tx.hash())).unwrap().unwrap();
+    assert_eq!((height, index), (1, 0));
+    assert_eq!(storage.get_block_by_hash(&location_hash).unwrap().unwrap().transactions[0].hash(), tx.hash());
+    assert_eq!(storage.get_transaction(&hex::encode(tx.hash())).unwrap().unwrap().value, 5);
+    assert_eq!(storage.get_state_root(1).unwrap(), Some(block.header.state_root));
+
+    // Receipts gain their bloom, and the indexes are rebuilt from the blocks
+    let migrated_receipt = storage.get_receipt(&hex::encode(tx.hash())).unwrap().unwrap();
+    assert_eq!(migrated_receipt.logs_bloom, Bloom::from_logs(&migrated_receipt.logs));
+    assert_eq!(storage.get_address_transactions(&alice, None, 10).unwrap().0, vec![tx.hash()]);
+    assert!(storage.find_log_heights(&[token.as_evm_address()], Some(&[[0xddu8; 32]]), 0, 10).unwrap().contains(&1));
+}
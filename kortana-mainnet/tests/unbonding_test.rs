@@ -0,0 +1,100 @@
+// tests/unbonding_test.rs
+mod common;
+
+use common::{account, header, staking_tx};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{MIN_VALIDATOR_STAKE, UNBONDING_PERIOD_BLOCKS};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+
+/// Validators "a" and "b" with the minimum self-bond, and a delegator holding 1M wei.
+fn setup() -> (State, Address, Address, Address) {
+    let mut state = State::new();
+    let (a, b, delegator) = (account(b"a"), account(b"b"), account(b"delegator"));
+    for v in [a, b] {
+        state.staking.create_validator(v, ValidatorProfile::new("", 500, 1000, 100), None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    }
+    let mut acc = state.get_account(&delegator);
+    acc.balance = 1_000_000;
+    state.update_account(delegator, acc);
+    state.staking.total_supply = 2 * MIN_VALIDATOR_STAKE + 1_000_000;
+    (state, a, b, delegator)
+}
+
+#[test]
+fn test_delegations_aggregate_and_matured_unbonding_is_paid_at_block_end() {
+    let (mut state, a, _, delegator) = setup();
+    state.staking.delegate(delegator, a, 600, 1);
+    state.staking.delegate(delegator, a, 400, 2);
+    assert_eq!(state.staking.delegations[&a].len(), 2);
+    assert_eq!(state.staking.delegated(&delegator, &a), 1000);
+
+    // Both amounts together can leave; more cannot
+    assert!(state.staking.undelegate(delegator, a, 1001, 3, &state.params).is_err());
+    state.staking.undelegate(delegator, a, 700, 3, &state.params).unwrap();
+    state.staking.undelegate(delegator, a, 300, 4, &state.params).unwrap();
+    assert!(!state.staking.delegations[&a].contains_key(&delegator));
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&header(3 + UNBONDING_PERIOD_BLOCKS - 1));
+    assert_eq!(processor.state.get_account(&delegator).balance, 1_000_000);
+    processor.end_block(&header(3 + UNBONDING_PERIOD_BLOCKS));
+    assert_eq!(processor.state.get_account(&delegator).balance, 1_000_700);
+    processor.end_block(&header(4 + UNBONDING_PERIOD_BLOCKS));
+    assert_eq!(processor.state.get_account(&delegator).balance, 1_001_000);
+    assert!(processor.state.staking.unbonding.is_empty());
+}
+
+#[test]
+fn test_redelegation_moves_stake_at_once_and_cannot_hop() {
+    let (mut state, a, b, delegator) = setup();
+    let c = account(b"c");
+    state.staking.create_validator(c, ValidatorProfile::new("", 500, 1000, 100), None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.delegate(delegator, a, 1000, 1);
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let data = [&[9u8][..], &a.to_bytes(), &b.to_bytes()].concat();
+    assert_eq!(processor.process_transaction(staking_tx(delegator, 0, 400, data), &header(2)).unwrap().status, 1);
+    let staking = &mut processor.state.staking;
+    assert_eq!(staking.delegated(&delegator, &a), 600);
+    assert_eq!(staking.delegated(&delegator, &b), 400);
+    assert_eq!(staking.bonded_stake(&b), MIN_VALIDATOR_STAKE + 400);
+    assert!(staking.unbonding.is_empty());
+
+    // The redelegated stake cannot move on before it matures
+    assert!(staking.redelegate(delegator, b, c, 100, 3, &processor.state.params).is_err());
+    assert!(staking.redelegate(delegator, a, a, 100, 3, &processor.state.params).is_err());
+    assert!(staking.redelegate(delegator, a, account(b"nobody"), 100, 3, &processor.state.params).is_err());
+    staking.redelegate(delegator, b, c, 100, 2 + UNBONDING_PERIOD_BLOCKS, &processor.state.params).unwrap();
+
+    // Maturity forgets the redelegation
+    processor.end_block(&header(2 + UNBONDING_PERIOD_BLOCKS));
+    assert!(processor.state.staking.redelegations.iter().all(|r| r.source == b));
+}
+
+#[test]
+fn test_stake_that_left_after_an_offence_is_still_slashed() {
+    let (mut state, a, b, delegator) = setup();
+    state.staking.delegate(delegator, a, 4000, 1);
+
+    // Before the offence at height 10: out of reach. After it: liable.
+    state.staking.undelegate(delegator, a, 1000, 5, &state.params).unwrap();
+    state.staking.redelegate(delegator, a, b, 1000, 8, &state.params).unwrap();
+    state.staking.undelegate(delegator, a, 1000, 12, &state.params).unwrap();
+    state.staking.redelegate(delegator, a, b, 1000, 15, &state.params).unwrap();
+    let supply = state.staking.total_supply;
+
+    let burned = state.staking.slash(a, 1000, [7u8; 32], 10);
+    let unbonding: Vec<u128> = state.staking.unbonding.iter().map(|u| u.amount).collect();
+    assert_eq!(unbonding, vec![1000, 900]);
+    assert_eq!(state.staking.delegated(&delegator, &b), 1900);
+    assert_eq!(state.staking.self_bond(&a), MIN_VALIDATOR_STAKE - MIN_VALIDATOR_STAKE / 10);
+    assert_eq!(burned, 100 + 100 + MIN_VALIDATOR_STAKE / 10);
+    assert_eq!(state.staking.total_supply, supply - burned);
+
+    // Redelegated stake that has since left the destination is out of reach
+    state.staking.undelegate(delegator, b, 1900, 16, &state.params).unwrap();
+    assert_eq!(state.staking.slash(a, 1000, [8u8; 32], 10), 90 + (MIN_VALIDATOR_STAKE - MIN_VALIDATOR_STAKE / 10) / 10);
+}
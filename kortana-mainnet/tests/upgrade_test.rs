@@ -0,0 +1,123 @@
+// tests/upgrade_test.rs
+mod common;
+
+use common::{account, governance_tx, header, tx};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::forks::{release_forks, Fork};
+use kortana_blockchain_rust::core::governance::{ProposalStatus, VoteOption};
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{GOV_MIN_DEPOSIT, GOV_VOTING_PERIOD_BLOCKS, MIN_VALIDATOR_STAKE, NODE_VERSION};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+use kortana_blockchain_rust::vm::evm::{EvmError, EvmExecutor};
+
+fn upgrade_data(version: &str, height: u64) -> Vec<u8> {
+    [&[2u8][..], &[7u8; 32], &height.to_be_bytes(), version.as_bytes()].concat()
+}
+
+/// A validator holding all the stake, with a deposit's worth of DNR to propose with.
+fn setup() -> (State, Address) {
+    let mut state = State::new();
+    let validator = account(b"validator");
+    let mut acc = state.get_account(&validator);
+    acc.balance = 2 * GOV_MIN_DEPOSIT;
+    state.update_account(validator, acc);
+    state.staking.create_validator(validator, ValidatorProfile::new("v", 500, 1000, 100), None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.total_supply = MIN_VALIDATOR_STAKE + 2 * GOV_MIN_DEPOSIT;
+    (state, validator)
+}
+
+/// Passes an upgrade to `version` at `height`, proposed at block 1.
+fn pass_upgrade(processor: &mut BlockProcessor, validator: Address, version: &str, height: u64) {
+    let nonce = processor.state.get_account(&validator).nonce;
+    let receipt = processor.process_transaction(governance_tx(validator, nonce, GOV_MIN_DEPOSIT, upgrade_data(version, height)), &header(1)).unwrap();
+    assert_eq!(receipt.status, 1);
+    let id = processor.state.governance.next_id - 1;
+    processor.state.governance.vote(id, validator, VoteOption::Yes, &processor.state.staking, 2).unwrap();
+    processor.end_block(&header(1 + GOV_VOTING_PERIOD_BLOCKS));
+}
+
+#[test]
+fn test_upgrade_plan_halts_other_releases_at_its_height() {
+    let (mut state, validator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+
+    // The upgrade cannot land before voting ends
+    let receipt = processor.process_transaction(governance_tx(validator, 0, GOV_MIN_DEPOSIT, upgrade_data("2.0.0", 1 + GOV_VOTING_PERIOD_BLOCKS)), &header(1)).unwrap();
+    assert_eq!(receipt.status, 0);
+
+    let at = 2000;
+    pass_upgrade(&mut processor, validator, "2.0.0", at);
+    let plan = processor.state.governance.upgrade_plan.clone().unwrap();
+    assert_eq!((plan.version.as_str(), plan.hash, plan.height), ("2.0.0", [7u8; 32], at));
+
+    // Blocks below the height still run; at it this release must stop
+    let governance = &processor.state.governance;
+    assert!(governance.upgrade_needed(at - 1, NODE_VERSION).is_none());
+    assert!(governance.upgrade_needed(at, NODE_VERSION).is_some());
+    assert!(governance.upgrade_needed(at, "2.0.0").is_none());
+    processor.begin_block(&header(at - 1)).unwrap();
+    assert!(processor.begin_block(&header(at)).is_err());
+    assert!(processor.state.governance.upgrade_plan.is_some());
+
+    // A later plan for this release replaces it and is carried out
+    pass_upgrade(&mut processor, validator, NODE_VERSION, 2500);
+    assert!(processor.state.governance.upgrade_needed(2500, NODE_VERSION).is_none());
+    processor.begin_block(&header(2500)).unwrap();
+    let governance = &processor.state.governance;
+    assert!(governance.upgrade_plan.is_none());
+    assert_eq!(governance.upgrades[&2500].version, NODE_VERSION);
+    assert_eq!(governance.proposals.values().filter(|p| p.status == ProposalStatus::Passed).count(), 2);
+}
+
+#[test]
+fn test_forks_switch_on_at_the_upgrade_height() {
+    let (mut state, validator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    pass_upgrade(&mut processor, validator, "1.2.0", 2000);
+    assert_eq!(release_forks("1.2.0"), &[Fork::CalldataGas, Fork::Mcopy]);
+
+    // As the 1.2.0 binary does when it reaches the plan
+    let plan = processor.state.governance.begin_block(2000, "1.2.0").unwrap().unwrap();
+    assert_eq!(plan.version, "1.2.0");
+    let forks = &processor.state.governance.forks;
+    assert!(!forks.is_active(Fork::Mcopy, 1999));
+    assert!(forks.is_active(Fork::Mcopy, 2000));
+
+    // MSTORE8 0x42 at 0, MCOPY one byte to 32, RETURN it
+    let code = [0x60, 0x42, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0x60, 0x20, 0x5E, 0x60, 0x01, 0x60, 0x20, 0xF3];
+    let contract = account(b"contract");
+    let before = EvmExecutor::new(contract, 100_000).execute(&code, processor.state, &header(1999));
+    assert!(matches!(before, Err(EvmError::InvalidOpcode)));
+    let after = EvmExecutor::new(contract, 100_000).execute(&code, processor.state, &header(2000)).unwrap();
+    assert_eq!(after, vec![0x42]);
+
+    // Calldata now counts towards the intrinsic gas: 4 per zero byte, 16 per other
+    let recipient = account(b"recipient");
+    let nonce = processor.state.get_account(&validator).nonce;
+    let receipt = processor.process_transaction(tx(validator, recipient, nonce, 1, vec![0, 0, 1]), &header(1999)).unwrap();
+    assert_eq!(receipt.gas_used, 21000 + 3 * 16);
+    let receipt = processor.process_transaction(tx(validator, recipient, nonce + 1, 1, vec![0, 0, 1]), &header(2000)).unwrap();
+    assert_eq!(receipt.gas_used, 21000 + 4 + 4 + 16);
+}
+
+#[test]
+fn test_newer_release_runs_through_an_older_plan() {
+    let (mut state, validator) = setup();
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    pass_upgrade(&mut processor, validator, "1.2.0", 2000);
+
+    // Releases at or past the plan's carry on; older ones, or an unreadable version, halt
+    let governance = &processor.state.governance;
+    assert!(governance.upgrade_needed(2000, "1.3.0").is_none());
+    assert!(governance.upgrade_needed(2000, "1.10.0").is_none());
+    assert!(governance.upgrade_needed(2000, "1.1.9").is_some());
+    assert!(governance.upgrade_needed(2000, "nightly").is_some());
+
+    // A 1.3.0 binary reaching the plan still switches on the 1.2.0 forks
+    let plan = processor.state.governance.begin_block(2000, "1.3.0").unwrap().unwrap();
+    assert_eq!(plan.version, "1.2.0");
+    assert!(processor.state.governance.forks.is_active(Fork::Mcopy, 2000));
+    assert!(processor.state.governance.forks.is_active(Fork::CalldataGas, 2000));
+}
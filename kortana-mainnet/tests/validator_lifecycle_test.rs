@@ -0,0 +1,142 @@
+// tests/validator_lifecycle_test.rs
+mod common;
+
+use common::{account, header, staking_tx};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::crypto::bls::BlsSecretKey;
+use kortana_blockchain_rust::parameters::{BLOCKS_PER_EPOCH, MIN_VALIDATOR_STAKE, UNBONDING_PERIOD_BLOCKS};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::ValidatorProfile;
+
+/// Create-validator payload for the BLS key derived from `seed`.
+fn create_data(seed: u8, commission: u16, max: u16, max_change: u16, moniker: &str) -> Vec<u8> {
+    let bls = BlsSecretKey::from_seed(&[seed; 32]);
+    let mut data = vec![6u8];
+    data.extend_from_slice(&bls.public_key());
+    data.extend_from_slice(&bls.proof_of_possession());
+    for rate in [commission, max, max_change] {
+        data.extend_from_slice(&rate.to_be_bytes());
+    }
+    data.extend_from_slice(moniker.as_bytes());
+    data
+}
+
+fn funded_state(accounts: &[Address]) -> State {
+    let mut state = State::new();
+    for addr in accounts {
+        let mut acc = state.get_account(addr);
+        acc.balance = 3 * MIN_VALIDATOR_STAKE;
+        state.update_account(*addr, acc);
+    }
+    state
+}
+
+#[test]
+fn test_create_validator_requires_self_bond_and_valid_commission() {
+    let (operator, delegator) = (account(b"operator"), account(b"delegator"));
+    let mut state = funded_state(&[operator, delegator]);
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+
+    // Nobody can delegate to an account that is not a validator
+    let delegate = [&[1u8][..], &operator.to_bytes()].concat();
+    let receipt = processor.process_transaction(staking_tx(delegator, 0, MIN_VALIDATOR_STAKE, delegate.clone()), &header(1)).unwrap();
+    assert_eq!(receipt.status, 0);
+
+    // Too little self-bond, a commission above its own maximum, a maximum above 100%
+    for (nonce, (value, data)) in [
+        (MIN_VALIDATOR_STAKE - 1, create_data(1, 500, 2000, 100, "op")),
+        (MIN_VALIDATOR_STAKE, create_data(1, 2500, 2000, 100, "op")),
+        (MIN_VALIDATOR_STAKE, create_data(1, 500, 10001, 100, "op")),
+    ].into_iter().enumerate() {
+        let receipt = processor.process_transaction(staking_tx(operator, nonce as u64, value, data), &header(1)).unwrap();
+        assert_eq!(receipt.status, 0);
+    }
+    assert!(processor.state.staking.profiles.is_empty());
+    assert_eq!(processor.state.staking.self_bond(&operator), 0);
+
+    let receipt = processor.process_transaction(staking_tx(operator, 3, MIN_VALIDATOR_STAKE, create_data(1, 500, 2000, 100, "op")), &header(1)).unwrap();
+    assert_eq!(receipt.status, 1);
+    let staking = &processor.state.staking;
+    assert_eq!(staking.profiles[&operator].moniker, "op");
+    assert_eq!(staking.self_bond(&operator), MIN_VALIDATOR_STAKE);
+    assert_eq!(staking.consensus_keys[&operator], BlsSecretKey::from_seed(&[1u8; 32]).public_key());
+    assert_eq!(processor.state.get_account(&operator).balance, 2 * MIN_VALIDATOR_STAKE - 4 * 50_000);
+
+    // Registering twice fails; delegating now works
+    let receipt = processor.process_transaction(staking_tx(operator, 4, MIN_VALIDATOR_STAKE, create_data(1, 500, 2000, 100, "op")), &header(1)).unwrap();
+    assert_eq!(receipt.status, 0);
+    let receipt = processor.process_transaction(staking_tx(delegator, 1, MIN_VALIDATOR_STAKE, delegate), &header(1)).unwrap();
+    assert_eq!(receipt.status, 1);
+
+    processor.end_block(&header(BLOCKS_PER_EPOCH));
+    let set = processor.state.staking.validator_set();
+    assert!(set.iter().any(|v| v.address == operator && v.stake == 2 * MIN_VALIDATOR_STAKE && v.commission == 500));
+}
+
+#[test]
+fn test_commission_changes_are_bounded_and_take_effect_at_the_next_election() {
+    let operator = account(b"operator");
+    let mut state = funded_state(&[operator]);
+    state.staking.create_validator(operator, ValidatorProfile::new("op", 500, 1000, 200), None, MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.elect_validators(1, &[0u8; 32], &state.params);
+
+    // More than the maximum change, or above the maximum, is refused
+    assert!(state.staking.edit_validator(operator, Some(800), None).is_err());
+    state.staking.profiles.get_mut(&operator).unwrap().commission_bps = 900;
+    assert!(state.staking.edit_validator(operator, Some(1100), None).is_err());
+    state.staking.profiles.get_mut(&operator).unwrap().commission_bps = 500;
+
+    // One change per epoch; the moniker can change any time
+    state.staking.edit_validator(operator, Some(700), None).unwrap();
+    assert!(state.staking.edit_validator(operator, Some(600), None).is_err());
+    state.staking.edit_validator(operator, None, Some("renamed".to_string())).unwrap();
+    assert!(state.staking.edit_validator(operator, None, Some("x".repeat(71))).is_err());
+    assert_eq!(state.staking.validator_set()[0].commission, 500);
+
+    // Edits through a transaction; 0xFFFF keeps the commission
+    state.staking.elect_validators(2, &[0u8; 32], &state.params);
+    assert_eq!(state.staking.validator_set()[0].commission, 700);
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let edit = [&[7u8][..], &u16::MAX.to_be_bytes(), b"op"].concat();
+    assert_eq!(processor.process_transaction(staking_tx(operator, 0, 0, edit), &header(1)).unwrap().status, 1);
+    let edit = [&[7u8][..], &900u16.to_be_bytes()].concat();
+    assert_eq!(processor.process_transaction(staking_tx(operator, 1, 0, edit), &header(1)).unwrap().status, 1);
+    let profile = &processor.state.staking.profiles[&operator];
+    assert_eq!((profile.moniker.as_str(), profile.commission_bps), ("op", 900));
+}
+
+#[test]
+fn test_self_bond_stays_above_minimum_until_the_validator_exits() {
+    let (operator, delegator) = (account(b"operator"), account(b"delegator"));
+    let mut state = funded_state(&[operator, delegator]);
+    state.staking.create_validator(operator, ValidatorProfile::new("op", 500, 1000, 100), None, 2 * MIN_VALIDATOR_STAKE, 0, &state.params).unwrap();
+    state.staking.delegate(delegator, operator, MIN_VALIDATOR_STAKE, 0);
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
+
+    // The excess over the minimum can leave, the minimum cannot
+    state.staking.undelegate(operator, operator, MIN_VALIDATOR_STAKE, 1, &state.params).unwrap();
+    assert!(state.staking.undelegate(operator, operator, 1, 1, &state.params).is_err());
+
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let receipt = processor.process_transaction(staking_tx(operator, 0, 0, vec![8u8]), &header(2)).unwrap();
+    assert_eq!(receipt.status, 1);
+    let staking = &processor.state.staking;
+    assert!(staking.profiles[&operator].exited);
+    assert_eq!(staking.self_bond(&operator), 0);
+    assert_eq!(staking.unbonding.iter().filter(|u| u.delegator == operator).map(|u| u.amount).sum::<u128>(), 2 * MIN_VALIDATOR_STAKE);
+    assert!(staking.unbonding.iter().all(|u| u.release_block <= 2 + UNBONDING_PERIOD_BLOCKS));
+
+    // Still in the set until the next election, then gone; its delegator stays bonded
+    assert!(staking.validator_set().iter().any(|v| v.address == operator));
+    processor.end_block(&header(BLOCKS_PER_EPOCH));
+    assert!(processor.state.staking.validator_set().is_empty());
+    assert_eq!(processor.state.staking.delegated(&delegator, &operator), MIN_VALIDATOR_STAKE);
+    assert!(processor.state.staking.exit_validator(operator, 3, &processor.state.params).is_err());
+
+    // An exited operator may register again
+    processor.state.staking.create_validator(operator, ValidatorProfile::new("back", 500, 1000, 100), None, MIN_VALIDATOR_STAKE, 4, &processor.state.params).unwrap();
+    processor.state.staking.elect_validators(2, &[0u8; 32], &processor.state.params);
+    assert_eq!(processor.state.staking.validator_set()[0].stake, 2 * MIN_VALIDATOR_STAKE);
+}
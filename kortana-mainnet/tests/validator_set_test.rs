@@ -0,0 +1,105 @@
+// tests/validator_set_test.rs
+mod common;
+
+use common::{account, header};
+use kortana_blockchain_rust::address::Address;
+use kortana_blockchain_rust::consensus::ConsensusEngine;
+use kortana_blockchain_rust::core::fees::FeeMarket;
+use kortana_blockchain_rust::core::genesis::create_genesis_state;
+use kortana_blockchain_rust::core::processor::BlockProcessor;
+use kortana_blockchain_rust::parameters::{ACTIVE_VALIDATOR_COUNT, BLOCKS_PER_EPOCH, MIN_VALIDATOR_STAKE};
+use kortana_blockchain_rust::state::account::State;
+use kortana_blockchain_rust::staking::{staking_address, ValidatorProfile, STAKING_ROOT_SLOT};
+
+#[test]
+fn test_validator_set_changes_only_at_epoch_boundaries() {
+    let mut state = State::new();
+    for (i, seed) in [b"a", b"b", b"c", b"d"].iter().enumerate() {
+        let v = account(*seed);
+        state.staking.create_validator(v, ValidatorProfile::new("", 500, 1000, 100), None, MIN_VALIDATOR_STAKE * (i as u128 + 1), 0, &state.params).unwrap();
+    }
+    // Below the minimum self-bond: never a candidate
+    assert!(state.staking.create_validator(account(b"e"), ValidatorProfile::new("", 500, 1000, 100), None, MIN_VALIDATOR_STAKE - 1, 0, &state.params).is_err());
+    state.staking.delegate(account(b"e"), account(b"e"), MIN_VALIDATOR_STAKE - 1, 0);
+    state.staking.elect_validators(0, &[0u8; 32], &state.params);
+
+    let active: Vec<Address> = state.staking.validator_set().iter().filter(|v| v.is_active).map(|v| v.address).collect();
+    assert_eq!(state.staking.validator_set().len(), 4);
+    assert_eq!(active.len(), ACTIVE_VALIDATOR_COUNT);
+    assert!(!active.contains(&account(b"a")));
+
+    // A delegation mid-epoch changes the committed state but not the set
+    state.staking.delegate(account(b"x"), account(b"a"), 10 * MIN_VALIDATOR_STAKE, 1);
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    let before = processor.state.calculate_root();
+    processor.end_block(&header(BLOCKS_PER_EPOCH - 1));
+    assert_ne!(processor.state.calculate_root(), before);
+    assert_eq!(processor.state.staking.epoch, 0);
+    assert!(!processor.state.staking.validator_set().iter().any(|v| v.address == account(b"a") && v.is_active));
+
+    // Jailing takes a validator out at once
+    processor.state.staking.jail(account(b"d"), 10);
+    assert!(!processor.state.staking.validator_set().iter().any(|v| v.address == account(b"d") && v.is_active));
+
+    // The boundary block elects the new set, in which "a" replaces the jailed "d"
+    processor.end_block(&header(BLOCKS_PER_EPOCH));
+    let set = processor.state.staking.validator_set();
+    assert_eq!(processor.state.staking.epoch, 1);
+    assert!(set.iter().any(|v| v.address == account(b"a") && v.is_active && v.stake == 11 * MIN_VALIDATOR_STAKE));
+    assert!(set.iter().any(|v| v.address == account(b"d") && !v.is_active));
+    assert!(set.windows(2).all(|w| w[0].address < w[1].address));
+}
+
+#[test]
+fn test_state_root_commits_to_the_staking_store() {
+    let state = create_genesis_state();
+    assert_eq!(state.staking.validator_set().iter().filter(|v| v.is_active).count(), 3);
+    assert_eq!(state.storage[&staking_address()][&STAKING_ROOT_SLOT], state.staking.root());
+
+    // Two nodes that reach the same staking store by different routes agree on the root
+    let mut a = create_genesis_state();
+    let mut b = create_genesis_state();
+    a.staking.delegate(account(b"x"), account(b"y"), 1, 5);
+    a.staking.delegate(account(b"y"), account(b"x"), 2, 5);
+    b.staking.delegate(account(b"y"), account(b"x"), 2, 5);
+    b.staking.delegate(account(b"x"), account(b"y"), 1, 5);
+    a.commit_staking();
+    b.commit_staking();
+    assert_eq!(a.calculate_root(), b.calculate_root());
+    assert_ne!(a.calculate_root(), state.calculate_root());
+}
+
+#[test]
+fn test_leader_schedule_is_drawn_once_per_epoch() {
+    let mut state = create_genesis_state();
+    let genesis_schedule = state.staking.leader_schedule().clone();
+    assert_eq!(genesis_schedule.leaders.len() as u64, BLOCKS_PER_EPOCH);
+    let mut engine = ConsensusEngine::new(state.staking.validator_set().to_vec());
+    engine.sync_validators(&state.staking);
+    assert_eq!(engine.leader_schedule, genesis_schedule);
+
+    // Stake moving mid-epoch leaves the schedule alone
+    let leader = genesis_schedule.leader(7).unwrap();
+    state.staking.delegate(account(b"x"), leader, 100 * MIN_VALIDATOR_STAKE, 1);
+    let mut processor = BlockProcessor::new(&mut state, FeeMarket::new());
+    processor.end_block(&header(BLOCKS_PER_EPOCH - 1));
+    assert_eq!(processor.state.staking.leader_schedule(), &genesis_schedule);
+
+    // The boundary block draws the next epoch's schedule from its VRF output
+    let mut boundary = header(BLOCKS_PER_EPOCH);
+    processor.end_block(&boundary);
+    let next = processor.state.staking.leader_schedule().clone();
+    assert_eq!(next.epoch, 1);
+    assert_ne!(next.leaders, genesis_schedule.leaders);
+    assert!(next.slots_of(&leader).len() > genesis_schedule.slots_of(&leader).len());
+
+    boundary.vrf_output = [2u8; 32];
+    let mut other = create_genesis_state();
+    other.staking.delegate(account(b"x"), leader, 100 * MIN_VALIDATOR_STAKE, 1);
+    BlockProcessor::new(&mut other, FeeMarket::new()).end_block(&boundary);
+    assert_ne!(other.staking.leader_schedule().seed, next.seed);
+
+    engine.sync_validators(&processor.state.staking);
+    assert_eq!(engine.leader_schedule, next);
+    assert_eq!(engine.get_leader(BLOCKS_PER_EPOCH + 3), next.leader(3));
+}
@@ -1,74 +0,0 @@
-
-use kortana_blockchain_rust::address::Address;
-use std::process::Command;
-use serde_json::json;
-
-const RPC_URL: &str = "https://poseidon-rpc.kortana.worchsester.xyz";
-
-fn rpc_call(method: &str, params: serde_json::Value) -> serde_json::Value {
-    let json_body = json!({
-        "jsonrpc": "2.0",
-        "method": method,
-        "params": params,
-        "id": 1
-    });
-
-    let output = Command::new("curl")
-        .arg("-s")
-        .arg("-X").arg("POST")
-        .arg("-H").arg("Content-Type: application/json")
-        .arg("-d").arg(json_body.to_string())
-        .arg(RPC_URL)
-        .output()
-        .expect("Failed to execute curl");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let v: serde_json::Value = serde_json::from_str(&stdout).unwrap_or(json!({"result": null}));
-    v["result"].clone()
-}
-
-fn main() {
-    println!("=== KORTANA TOKEN AUDIT SUITE ===");
-
-    // Faucet address which deployed both tokens
-    let deployer_addr = "kn:0xc19d6dece56d290c71930c2f867ae9c2c652a19f7911ef64";
-    println!("\nAuditing Deployer: {}", deployer_addr);
-
-    // 1. Check DNR Balance
-    let balance_hex = rpc_call("eth_getBalance", json!([deployer_addr, "latest"]));
-    let balance_str = balance_hex.as_str().unwrap_or("0x0").trim_start_matches("0x");
-    let balance_dnr = u128::from_str_radix(balance_str, 16).unwrap_or(0);
-    println!("Balance of DNR:   {:.4} DNR", (balance_dnr as f64) / 1e18);
-
-    // 2. Identify Token Contracts (From previous deployment hashes)
-    // Note: In a real environment, we'd use 'eth_getTransactionReceipt' to get contractAddress.
-    // For this simulation/test, we check if the deployer's nonce has incremented.
-    let nonce_hex = rpc_call("eth_getTransactionCount", json!([deployer_addr, "latest"]));
-    println!("Total Transactions: {}", nonce_hex.as_str().unwrap_or("0x0"));
-
-    println!("\n--- SMART CONTRACT IDENTITIES ---");
-    
-    // Address Derivation (Nonce 0 = Bantumi, Nonce 1 = Maya)
-    let deployer = Address::from_hex(deployer_addr).unwrap();
-    let bantumi_addr = Address::derive_contract_address(&deployer, 0);
-    let maya_addr = Address::derive_contract_address(&deployer, 1);
-
-    println!("1. Bantumi Token (Solidity) contractAddress:");
-    println!("   > {}", bantumi_addr.to_hex());
-    println!("   > VM: EVM");
-    
-    println!("\n2. Maya Token (Quorlin) contractAddress:");
-    println!("   > {}", maya_addr.to_hex());
-    println!("   > VM: Quorlin");
-    
-    println!("\n--- METAMASK INTEGRATION ---");
-    println!("To see your tokens in MetaMask:");
-    println!("1. Open MetaMask and switch to 'Kortana Poseidon' Network");
-    println!("2. Click 'Import Tokens'");
-    println!("3. Bantumi (BANT) Address: {}", bantumi_addr.to_hex().replace("kn:0x", "0x").chars().take(42).collect::<String>());
-    println!("4. Maya (MAYA) Address:    {}", maya_addr.to_hex().replace("kn:0x", "0x").chars().take(42).collect::<String>());
-    println!("   (Note: Use the 42-char hex part for MetaMask compatibility)");
-    
-    println!("\nSTATUS: Token identities verified via Nonce-Derivation (SHA3).");
-}
-
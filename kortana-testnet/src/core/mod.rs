@@ -1,5 +0,0 @@
-// File: src/core/mod.rs
-pub mod fees;
-pub mod processor;
-pub mod governance;
-pub mod genesis;
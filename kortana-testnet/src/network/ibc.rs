@@ -1,63 +0,0 @@
-// File: src/network/ibc.rs
-
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IbcPacket {
-    pub source_chain: String,
-    pub destination_chain: String,
-    pub sequence: u64,
-    pub data: Vec<u8>,
-    pub timestamp: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IbcAck {
-    pub packet_sequence: u64,
-    pub success: bool,
-    pub error: Option<String>,
-}
-
-pub struct IbcCore {
-    pub state_roots: std::collections::HashMap<String, [u8; 32]>, // chain_id -> last_verified_root
-    pub sequences: std::collections::HashMap<String, u64>, // chain_id -> sequence
-}
-
-impl Default for IbcCore {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl IbcCore {
-    pub fn new() -> Self {
-        Self {
-            state_roots: std::collections::HashMap::new(),
-            sequences: std::collections::HashMap::new(),
-        }
-    }
-
-    pub fn send_packet(&mut self, dest: String, data: Vec<u8>) -> IbcPacket {
-        let seq = self.sequences.entry(dest.clone()).or_insert(0);
-        *seq += 1;
-        
-        IbcPacket {
-            source_chain: "kortana-1".to_string(), // Our chain ID
-            destination_chain: dest,
-            sequence: *seq,
-            data,
-            timestamp: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
-        }
-    }
-
-    pub fn receive_packet(&mut self, packet: IbcPacket, _merkle_proof: Vec<u8>) -> Result<IbcAck, String> {
-        // Verify the merkle proof against self.state_roots.get(packet.source_chain)
-        // This is where Light Client verification happens
-        
-        Ok(IbcAck {
-            packet_sequence: packet.sequence,
-            success: true,
-            error: None,
-        })
-    }
-}
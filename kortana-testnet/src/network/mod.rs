@@ -1,5 +0,0 @@
-// File: src/network/mod.rs
-pub mod peer;
-pub mod p2p;
-pub mod messages;
-pub mod ibc;
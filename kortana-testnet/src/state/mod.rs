@@ -1,3 +0,0 @@
-// File: src/state/mod.rs
-pub mod account;
-pub mod trie;
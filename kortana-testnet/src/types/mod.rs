@@ -1,3 +0,0 @@
-// File: src/types/mod.rs
-pub mod transaction;
-pub mod block;
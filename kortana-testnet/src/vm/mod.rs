@@ -1,4 +0,0 @@
-// File: src/vm/mod.rs
-pub mod evm;
-pub mod precompiles;
-pub mod quorlin;